@@ -1,15 +1,18 @@
-
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
\ No newline at end of file
+#[allow(unused)]
+pub mod rust_4_2_functional;
+#[allow(unused)]
+pub mod rust_4_3_type_conversion;
+#[allow(unused)]
+pub mod rust_4_4_smart_pointer;
+#[allow(unused)]
+pub mod rust_4_5_circle_self_ref;
+#[allow(unused)]
+pub mod rust_4_6_concurrency;
+#[allow(unused)]
+pub mod rust_4_7_global_variable;
+#[allow(unused)]
+pub mod rust_4_9_unsafe;
+#[allow(unused)]
+pub mod rust_4_10_macro;
+#[allow(unused)]
+pub mod rust_4_11_async;
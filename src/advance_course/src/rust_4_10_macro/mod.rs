@@ -0,0 +1,3 @@
+pub mod rust_4_10_1;
+pub mod rust_4_10_2;
+pub mod rust_4_10_3;
@@ -0,0 +1,95 @@
+//! # 4.10.1 声明式宏 macro_rules!
+//! 宏是一种"生成代码的代码"：它在编译期展开，参数是一段 token，可以接收可变数量的参数，也可以生成结构体、实现特征等任何代码。
+//! ```text
+//! macro_rules! 宏名 {
+//!     (模式1) => { 展开1 };
+//!     (模式2) => { 展开2 };
+//! }
+//! ```
+//! 模式中的 $name:kind 用来捕获一段代码，常用的 kind 有 expr、ident、ty、tt、pat、literal 等；
+//! $(...),* 表示重复零次或多次，$(...),+ 表示至少重复一次，展开时用同样的 $(...)* 写法把捕获的内容重复输出。
+
+use std::collections::HashMap;
+use common::{inner_print, outer_print};
+
+/// 用 key => value 的形式创建 HashMap，支持结尾的逗号
+#[macro_export]
+macro_rules! hashmap {
+    () => {
+        ::std::collections::HashMap::new()
+    };
+    ($($key:expr => $value:expr),+ $(,)?) => {{
+        // 先数出键值对的个数，一次分配足够的容量
+        let mut map = ::std::collections::HashMap::with_capacity($crate::count!($($key)+));
+        $(
+            map.insert($key, $value);
+        )+
+        map
+    }};
+}
+
+/// 递归地求最大值：max!(a) 就是 a，max!(a, b, ...) 是 a 和 max!(b, ...) 中较大的那个
+#[macro_export]
+macro_rules! max {
+    ($x:expr $(,)?) => {
+        $x
+    };
+    ($x:expr, $($rest:expr),+ $(,)?) => {{
+        let x = $x;
+        let y = $crate::max!($($rest),+);
+        if x > y { x } else { y }
+    }};
+}
+
+#[outer_print("重复")]
+pub fn repetition() {
+    let map: HashMap<&str, i32> = crate::hashmap! {
+        "one" => 1,
+        "two" => 2,
+        "three" => 3,
+    };
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(_, v)| **v);
+    println!("{:?}，capacity >= {}", entries, map.capacity());
+    assert_eq!(map["two"], 2);
+    let empty: HashMap<i32, i32> = crate::hashmap!();
+    assert!(empty.is_empty());
+
+    inner_print!("递归");
+    // 每一层递归都会生成一段新的代码，max!(3, 7, 5) 展开后有三层嵌套的块
+    let m = crate::max!(3, 7, 5);
+    println!("max!(3, 7, 5) = {}", m);
+    assert_eq!(m, 7);
+    assert_eq!(crate::max!("b", "a"), "b");
+}
+
+#[outer_print("卫生性")]
+/// 宏是卫生的(hygiene)：宏内部定义的局部变量和调用处的同名变量互不影响。
+/// max! 中的 let x 不会遮蔽调用者的 x，调用者想使用宏定义的变量，必须把变量名作为参数传进去。
+///
+/// 对于路径则不然，宏展开后按照调用处的作用域解析路径，所以导出的宏内部要使用 $crate:: 和 ::std:: 开头的完整路径。
+pub fn hygiene() {
+    let x = 1;
+    // 宏内部也有一个 x，但它们是两个不同的变量
+    let m = crate::max!(x + 10, x);
+    assert_eq!((x, m), (1, 11));
+
+    macro_rules! declare {
+        // 由调用者提供变量名，生成的变量在调用处可见
+        ($name:ident = $value:expr) => {
+            let $name = $value;
+        };
+    }
+    declare!(answer = 42);
+    println!("answer = {}", answer);
+    assert_eq!(answer, 42);
+
+    macro_rules! try_declare {
+        () => {
+            let hidden = 1;
+        };
+    }
+    try_declare!();
+    // println!("{}", hidden);
+    // error[E0425]: cannot find value `hidden` in this scope
+}
@@ -0,0 +1,105 @@
+//! # 4.10.2 tt 咀嚼器
+//! tt(token tree) 可以匹配任意一个 token，或者一对括号以及括号里的所有内容。
+//! tt 咀嚼器(tt-muncher)每次从输入的开头"咬下"一小段 token 进行处理，再把剩下的 token 交给自己递归处理，直到输入为空。
+//!
+//! 宏内部使用的规则通常以 @ 开头，例如 (@stack ...)，它们不会和正常的调用冲突，相当于宏的私有函数。
+//! 注意递归的深度受 #![recursion_limit] 限制，默认是 128。
+
+use common::{inner_print, outer_print};
+
+/// 统计 token 的个数，结果是一个常量表达式
+#[macro_export]
+macro_rules! count {
+    () => {
+        0usize
+    };
+    ($head:tt $($tail:tt)*) => {
+        1usize + $crate::count!($($tail)*)
+    };
+}
+
+/// 逆波兰表达式求值：rpn!(2 3 + 4 *) == (2 + 3) * 4
+#[macro_export]
+macro_rules! rpn {
+    // 输入已经处理完，栈中只剩一个值
+    (@stack [$result:expr]) => {
+        $result
+    };
+    // 遇到运算符，弹出栈顶的两个值，把运算结果压回栈中
+    (@stack [$b:expr, $a:expr $(, $rest:expr)*] + $($tail:tt)*) => {
+        $crate::rpn!(@stack [($a + $b) $(, $rest)*] $($tail)*)
+    };
+    (@stack [$b:expr, $a:expr $(, $rest:expr)*] - $($tail:tt)*) => {
+        $crate::rpn!(@stack [($a - $b) $(, $rest)*] $($tail)*)
+    };
+    (@stack [$b:expr, $a:expr $(, $rest:expr)*] * $($tail:tt)*) => {
+        $crate::rpn!(@stack [($a * $b) $(, $rest)*] $($tail)*)
+    };
+    (@stack [$b:expr, $a:expr $(, $rest:expr)*] / $($tail:tt)*) => {
+        $crate::rpn!(@stack [($a / $b) $(, $rest)*] $($tail)*)
+    };
+    // 遇到操作数，压入栈顶
+    (@stack [$($stack:expr),*] $operand:tt $($tail:tt)*) => {
+        $crate::rpn!(@stack [$operand $(, $stack)*] $($tail)*)
+    };
+    ($($tokens:tt)+) => {
+        $crate::rpn!(@stack [] $($tokens)+)
+    };
+}
+
+/// 为一组单元变体的枚举生成 ALL 和 name
+#[macro_export]
+macro_rules! named_enum {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($variant:ident),+ $(,)? }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            pub const ALL: [$name; $crate::count!($($variant)+)] = [$($name::$variant),+];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant)),+
+                }
+            }
+        }
+    };
+}
+
+crate::named_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Fragment { Expr, Ident, Ty, Tt, Pat, Literal }
+}
+
+#[outer_print("tt 咀嚼器")]
+pub fn tt_muncher() {
+    // 在常量中使用，说明 count! 的结果是在编译期计算出来的
+    const N: usize = crate::count!(a b (c d) [e] { f g h });
+    println!("count!(a b (c d) [e] {{ f g h }}) = {}", N);
+    assert_eq!(N, 5);
+
+    inner_print!("逆波兰表达式");
+    // rpn!(2 3 + 4 *)
+    // => rpn!(@stack [] 2 3 + 4 *)
+    // => rpn!(@stack [2] 3 + 4 *)
+    // => rpn!(@stack [3, 2] + 4 *)
+    // => rpn!(@stack [(2 + 3)] 4 *)
+    // => rpn!(@stack [4, (2 + 3)] *)
+    // => rpn!(@stack [((2 + 3) * 4)])
+    let v = crate::rpn!(2 3 + 4 *);
+    println!("2 3 + 4 * = {}", v);
+    assert_eq!(v, 20);
+    assert_eq!(crate::rpn!(10 2 8 * + 3 -), 23);
+}
+
+#[outer_print("用宏生成代码")]
+pub fn generate_items() {
+    for fragment in Fragment::ALL {
+        print!("{} ", fragment.name());
+    }
+    println!();
+    assert_eq!(Fragment::ALL.len(), 6);
+    assert_eq!(Fragment::Ty.name(), "Ty");
+}
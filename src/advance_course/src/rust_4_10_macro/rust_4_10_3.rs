@@ -0,0 +1,76 @@
+//! # 4.10.3 过程宏：解剖 common
+//! 过程宏是一个函数：输入一段 TokenStream，输出另一段 TokenStream。它必须放在 proc-macro = true 的独立 crate 中，
+//! 本课程一直在使用的 outer_print 和 inner_print 就定义在 common 中，按照用法分为三种：
+//! + 属性宏 #[outer_print("标题")]，接收属性参数和被修饰的函数，输出替换后的代码
+//! + 函数式宏 inner_print!("标题")，用法和声明式宏一样
+//! + 派生宏 #[derive(LessonDescribe)]，接收结构体的定义，输出额外的代码，原来的结构体保持不变
+//!
+//! common 中的过程宏都用 syn 把 TokenStream 解析成语法树，再用 quote! 拼出新的代码。
+//! common::expand! 在编译期完成展开，并用内置的格式化器把展开结果打印成代码，效果类似 cargo expand。
+
+use common::{expand, inner_print, outer_print, LessonDescribe};
+
+/// outer_print 展开之后的代码
+pub const OUTER_PRINT_EXPANSION: &str = expand! {
+    #[outer_print("示例")]
+    pub fn demo(name: &str) -> usize {
+        println!("hello, {}", name);
+        name.len()
+    }
+};
+
+#[outer_print("示例")]
+pub fn demo(name: &str) -> usize {
+    println!("hello, {}", name);
+    name.len()
+}
+
+/// 课程的元信息
+#[derive(Debug, LessonDescribe)]
+pub struct Lesson<'a, T> {
+    /// 章节编号
+    pub chapter: (u8, u8),
+    /// 标题
+    pub title: &'a str,
+    pub tags: Vec<String>,
+    pub extra: Option<T>,
+}
+
+/// 元组结构体同样可以派生
+#[derive(LessonDescribe)]
+pub struct Meters(pub f64);
+
+#[outer_print("属性宏 outer_print", expand)]
+/// 加上 expand 参数后，函数运行时会先打印自己展开后的代码。
+/// 展开后的代码里有一个 Guard 结构体，它在函数开头被创建，函数返回时被 drop，
+/// 所以无论函数从哪里返回，甚至 panic，[end] 都一定会被打印出来。
+pub fn attribute_macro() {
+    inner_print!("outer_print 展开");
+    println!("{}", OUTER_PRINT_EXPANSION);
+    assert_eq!(demo("macro"), 5);
+
+    inner_print!("inner_print 展开");
+    println!("{}", expand!(inner_print!("标题");));
+}
+
+#[outer_print("派生宏 LessonDescribe")]
+pub fn derive_macro() {
+    println!("{}", Lesson::<()>::describe());
+    for (name, ty) in Lesson::<()>::FIELDS {
+        println!("{:>8} : {}", name, ty);
+    }
+    assert_eq!(Lesson::<()>::FIELDS[1], ("title", "&'a str"));
+    println!("{}", Meters::describe());
+
+    inner_print!("LessonDescribe 展开");
+    println!(
+        "{}",
+        expand! {
+            #[derive(Debug, LessonDescribe)]
+            pub struct Point {
+                pub x: i32,
+                pub y: i32,
+            }
+        }
+    );
+}
@@ -0,0 +1,4 @@
+pub mod rust_4_11_1;
+pub mod rust_4_11_2;
+pub mod rust_4_11_3;
+pub mod rust_4_11_4;
@@ -0,0 +1,163 @@
+//! # 4.11.1 Future 与 Waker
+//! async fn 和 async 块返回的都是一个实现了 Future 特征的值，它是惰性的：不去 poll 它，里面的代码一行都不会执行。
+//! ```text
+//! pub trait Future {
+//!     type Output;
+//!     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output>;
+//! }
+//! ```
+//! poll 返回 Poll::Pending 表示还没有完成，此时 Future 需要把 cx 中的 Waker 保存下来，
+//! 等到可以继续推进时调用 waker.wake()，通知执行器再 poll 一次。执行器、Waker 和 Future 三者配合，就构成了一个异步运行时。
+//!
+//! 本节实现两种 Waker：基于 Wake 特征、唤醒阻塞线程的 [ThreadWaker]，以及手写 RawWakerVTable 的 [counting_waker]。
+
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Wake, Waker};
+use std::thread::{self, Thread};
+use common::{inner_print, outer_print};
+
+/// 被唤醒时 unpark 对应的线程
+pub struct ThreadWaker {
+    thread: Thread,
+    wakes: AtomicUsize,
+}
+
+impl ThreadWaker {
+    pub fn current() -> Arc<ThreadWaker> {
+        Arc::new(ThreadWaker { thread: thread::current(), wakes: AtomicUsize::new(0) })
+    }
+
+    /// 被唤醒的次数
+    pub fn wakes(&self) -> usize {
+        self.wakes.load(Ordering::Relaxed)
+    }
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wakes.fetch_add(1, Ordering::Relaxed);
+        self.thread.unpark();
+    }
+}
+
+/// 在当前线程上运行一个 Future 直到完成，Pending 时 park 当前线程，等待 Waker 把它 unpark
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    block_on_with(ThreadWaker::current(), future)
+}
+
+/// 同 [block_on]，由调用者提供 ThreadWaker，方便观察唤醒次数
+pub fn block_on_with<F: Future>(waker: Arc<ThreadWaker>, future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(waker);
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            // unpark 可能发生在 park 之前，此时 park 会立即返回；park 也可能被虚假唤醒，重新 poll 一次即可
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// 手写 vtable 的 Waker：data 指针是 Arc<AtomicUsize>，每次 wake 计数加一
+pub fn counting_waker(counter: Arc<AtomicUsize>) -> Waker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+
+    unsafe fn clone(data: *const ()) -> RawWaker {
+        // SAFETY: data 来自 Arc::into_raw，clone 时增加一次强引用
+        unsafe { Arc::increment_strong_count(data as *const AtomicUsize) };
+        RawWaker::new(data, &VTABLE)
+    }
+    unsafe fn wake(data: *const ()) {
+        // SAFETY: wake 会消耗 Waker，这里拿回 Arc，结束时 drop 掉这一份引用
+        let counter = unsafe { Arc::from_raw(data as *const AtomicUsize) };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+    unsafe fn wake_by_ref(data: *const ()) {
+        // SAFETY: data 在 Waker 存活期间一直有效
+        let counter = unsafe { &*(data as *const AtomicUsize) };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+    unsafe fn drop(data: *const ()) {
+        // SAFETY: 与 clone 或者 counting_waker 中的那一次 into_raw 配对
+        unsafe { Arc::decrement_strong_count(data as *const AtomicUsize) };
+    }
+
+    let raw = RawWaker::new(Arc::into_raw(counter) as *const (), &VTABLE);
+    // SAFETY: vtable 中的四个函数都遵守 RawWaker 的约定，且 Arc<AtomicUsize> 可以跨线程使用
+    unsafe { Waker::from_raw(raw) }
+}
+
+/// 返回 n 次 Pending 后完成，每次 Pending 前都会立刻唤醒自己
+pub struct Countdown(pub u32);
+
+impl Future for Countdown {
+    type Output = &'static str;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 == 0 {
+            return Poll::Ready("发射!");
+        }
+        self.0 -= 1;
+        // 不调用 wake 就返回 Pending 的话，执行器永远不会再 poll 它
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[outer_print("Future 是惰性的")]
+pub fn future_intro() {
+    async fn hello(log: &mut Vec<&'static str>) -> usize {
+        log.push("hello 开始执行");
+        42
+    }
+    let mut log = Vec::new();
+    let future = hello(&mut log);
+    // 只是创建了 Future，函数体还没有执行
+    println!("创建 future 后，size_of = {}", std::mem::size_of_val(&future));
+    let answer = block_on(future);
+    println!("block_on 之后：{:?}，结果 = {}", log, answer);
+    assert_eq!((log.as_slice(), answer), (["hello 开始执行"].as_slice(), 42));
+
+    inner_print!(".await");
+    // .await 只能在 async 中使用，它不会阻塞线程，而是在 Pending 时把控制权交还给执行器
+    let total = block_on(async {
+        let a = async { 1 }.await;
+        let b = Countdown(3).await;
+        println!("{}", b);
+        a + 1
+    });
+    assert_eq!(total, 2);
+}
+
+#[outer_print("Waker")]
+pub fn waker_intro() {
+    let waker = ThreadWaker::current();
+    let result = block_on_with(Arc::clone(&waker), Countdown(5));
+    println!("{}，ThreadWaker 被唤醒 {} 次", result, waker.wakes());
+    assert_eq!(waker.wakes(), 5);
+
+    inner_print!("手写 RawWakerVTable");
+    let counter = Arc::new(AtomicUsize::new(0));
+    let waker = counting_waker(Arc::clone(&counter));
+    let mut cx = Context::from_waker(&waker);
+    let mut countdown = Countdown(2);
+    let mut polls = 1;
+    while Pin::new(&mut countdown).poll(&mut cx).is_pending() {
+        polls += 1;
+    }
+    let cloned = waker.clone();
+    println!("poll {} 次，wake {} 次，Arc 强引用 = {}", polls, counter.load(Ordering::Relaxed), Arc::strong_count(&counter));
+    assert_eq!(Arc::strong_count(&counter), 3);
+    cloned.wake();
+    drop(waker);
+    assert_eq!(counter.load(Ordering::Relaxed), 3);
+    assert_eq!(Arc::strong_count(&counter), 1);
+}
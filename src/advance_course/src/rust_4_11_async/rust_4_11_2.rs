@@ -0,0 +1,183 @@
+//! # 4.11.2 执行器与定时器
+//! 标准库只定义了 Future，没有提供执行器，tokio、async-std 等运行时的核心就是执行器。
+//!
+//! 本节的执行器只有一个线程：
+//! + [Spawner] 把 Future 包装成 Task，放进就绪队列
+//! + Task 实现了 Wake，被唤醒时把自己重新放回就绪队列
+//! + [Executor] 不断从就绪队列中取出 Task 并 poll，所有 Task 都完成、队列的发送端全部关闭后退出
+//!
+//! [TimerFuture] 则演示了 Future 如何与"外部事件"配合：由后台线程计时，时间到了之后调用保存的 Waker。
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+use common::{inner_print, outer_print};
+
+struct TimerState {
+    completed: bool,
+    waker: Option<Waker>,
+}
+
+/// 后台线程睡眠 duration 后完成的 Future
+pub struct TimerFuture {
+    state: Arc<Mutex<TimerState>>,
+}
+
+impl TimerFuture {
+    pub fn new(duration: Duration) -> TimerFuture {
+        let state = Arc::new(Mutex::new(TimerState { completed: false, waker: None }));
+        let thread_state = Arc::clone(&state);
+        thread::spawn(move || {
+            thread::sleep(duration);
+            let mut state = thread_state.lock().unwrap();
+            state.completed = true;
+            // 先释放锁再唤醒，避免被唤醒的执行器立刻 poll 时等锁
+            let waker = state.waker.take();
+            drop(state);
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+        TimerFuture { state }
+    }
+}
+
+impl Future for TimerFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.completed {
+            return Poll::Ready(());
+        }
+        // 同一个 Future 可能被不同的执行器(任务)poll，每次都要更新 Waker，will_wake 用来省掉不必要的 clone
+        match &state.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => state.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+/// 睡眠 duration
+pub fn sleep(duration: Duration) -> TimerFuture {
+    TimerFuture::new(duration)
+}
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+struct Task {
+    // Task 需要 Sync 才能放进 Waker，但只有执行器会 poll 它，所以这把锁永远不会发生争用
+    future: Mutex<Option<BoxFuture>>,
+    queue: Sender<Arc<Task>>,
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        let queue = self.queue.clone();
+        // 执行器已经退出时发送会失败，此时任务也不再需要被执行
+        let _ = queue.send(self);
+    }
+}
+
+/// 单线程执行器
+pub struct Executor {
+    ready: Receiver<Arc<Task>>,
+}
+
+/// 向执行器提交任务，可以 clone 出多个
+#[derive(Clone)]
+pub struct Spawner {
+    queue: Sender<Arc<Task>>,
+}
+
+/// 创建一对执行器和提交者
+pub fn new_executor_and_spawner() -> (Executor, Spawner) {
+    let (queue, ready) = mpsc::channel();
+    (Executor { ready }, Spawner { queue })
+}
+
+impl Spawner {
+    pub fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        let task = Arc::new(Task { future: Mutex::new(Some(Box::pin(future))), queue: self.queue.clone() });
+        self.queue.send(task).expect("执行器已经退出");
+    }
+}
+
+impl Executor {
+    /// 运行到所有任务完成并且所有 Spawner 都被 drop 为止，返回 poll 的总次数
+    pub fn run(self) -> usize {
+        let mut polls = 0;
+        // 每个 Task 都持有一个发送端，任务完成后 Task 被 drop，最后一个发送端关闭时 recv 返回 Err
+        while let Ok(task) = self.ready.recv() {
+            let mut slot = task.future.lock().unwrap();
+            // 已经完成的任务可能还会被重复唤醒，此时 slot 为 None
+            if let Some(mut future) = slot.take() {
+                polls += 1;
+                let waker = Waker::from(Arc::clone(&task));
+                let mut cx = Context::from_waker(&waker);
+                if future.as_mut().poll(&mut cx).is_pending() {
+                    *slot = Some(future);
+                }
+            }
+        }
+        polls
+    }
+}
+
+#[outer_print("定时器")]
+pub fn timer_future() {
+    use super::rust_4_11_1::block_on;
+    let start = Instant::now();
+    block_on(sleep(Duration::from_millis(20)));
+    let elapsed = start.elapsed();
+    println!("sleep 20ms，实际 {:?}", elapsed);
+    assert!(elapsed >= Duration::from_millis(20));
+}
+
+#[outer_print("单线程执行器")]
+/// 三个任务各自睡眠不同的时间，它们在同一个线程上交替推进，总耗时接近最长的那个，而不是三者之和。
+pub fn executor() {
+    let (executor, spawner) = new_executor_and_spawner();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    for (name, ms) in [("慢", 30), ("快", 10), ("中", 20)] {
+        let log = Arc::clone(&log);
+        spawner.spawn(async move {
+            log.lock().unwrap().push(format!("{} 开始", name));
+            sleep(Duration::from_millis(ms)).await;
+            log.lock().unwrap().push(format!("{} 结束", name));
+        });
+    }
+    // 不再提交任务，drop 掉 Spawner，否则 run 永远不会返回
+    drop(spawner);
+
+    let start = Instant::now();
+    let polls = executor.run();
+    let elapsed = start.elapsed();
+    println!("{:?}", log.lock().unwrap());
+    println!("poll {} 次，耗时 {:?}", polls, elapsed);
+    let ends: Vec<String> = log.lock().unwrap().iter().filter(|l| l.ends_with("结束")).cloned().collect();
+    assert_eq!(ends, ["快 结束", "中 结束", "慢 结束"]);
+
+    inner_print!("在任务中提交任务");
+    let (executor, spawner) = new_executor_and_spawner();
+    let inner = spawner.clone();
+    let count = Arc::new(AtomicUsize::new(0));
+    let c = Arc::clone(&count);
+    spawner.spawn(async move {
+        for _ in 0..3 {
+            let c = Arc::clone(&c);
+            inner.spawn(async move {
+                c.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+    });
+    drop(spawner);
+    executor.run();
+    assert_eq!(count.load(Ordering::Relaxed), 3);
+}
@@ -0,0 +1,238 @@
+//! # 4.11.3 组合 Future：join 与 select
+//! 在一个 async 块中依次 .await 多个 Future，它们是串行执行的。想让多个 Future 并发推进，需要把它们组合成一个新的 Future：
+//! + join 同时 poll 所有 Future，全部完成后返回所有结果
+//! + select 同时 poll 所有 Future，任意一个完成就返回它的结果，其余的 Future 被 drop，也就是被取消了
+//!
+//! 与 futures 库中的同名宏一样，这里的 [join!](crate::join) 和 [select!](crate::select) 只是对 [join] 和 [select] 的简单包装。
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use common::{inner_print, outer_print};
+use super::rust_4_11_1::block_on;
+use super::rust_4_11_2::{sleep, TimerFuture};
+
+/// 同时 poll 两个 Future，全部完成后返回 (a, b)
+pub struct Join<A: Future, B: Future> {
+    a: Pin<Box<A>>,
+    b: Pin<Box<B>>,
+    a_out: Option<A::Output>,
+    b_out: Option<B::Output>,
+}
+
+// 两个 Future 都已经在堆上固定，输出值从来不会被 Pin，因此 Join 本身可以随意移动
+impl<A: Future, B: Future> Unpin for Join<A, B> {}
+
+pub fn join<A: Future, B: Future>(a: A, b: B) -> Join<A, B> {
+    Join { a: Box::pin(a), b: Box::pin(b), a_out: None, b_out: None }
+}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        // 已经完成的 Future 不能再被 poll
+        if this.a_out.is_none() {
+            if let Poll::Ready(out) = this.a.as_mut().poll(cx) {
+                this.a_out = Some(out);
+            }
+        }
+        if this.b_out.is_none() {
+            if let Poll::Ready(out) = this.b.as_mut().poll(cx) {
+                this.b_out = Some(out);
+            }
+        }
+        match (this.a_out.take(), this.b_out.take()) {
+            (Some(a), Some(b)) => Poll::Ready((a, b)),
+            (a, b) => {
+                this.a_out = a;
+                this.b_out = b;
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// 同时 poll 所有 Future，按原来的顺序返回所有结果
+pub struct JoinAll<F: Future> {
+    futures: Vec<Pin<Box<F>>>,
+    outputs: Vec<Option<F::Output>>,
+}
+
+impl<F: Future> Unpin for JoinAll<F> {}
+
+pub fn join_all<F: Future>(futures: impl IntoIterator<Item = F>) -> JoinAll<F> {
+    let futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+    let outputs = futures.iter().map(|_| None).collect();
+    JoinAll { futures, outputs }
+}
+
+impl<F: Future> Future for JoinAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        for (future, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+            if output.is_none() {
+                if let Poll::Ready(out) = future.as_mut().poll(cx) {
+                    *output = Some(out);
+                }
+            }
+        }
+        if this.outputs.iter().all(Option::is_some) {
+            Poll::Ready(this.outputs.iter_mut().map(|o| o.take().unwrap()).collect())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// select 的结果：先完成的是哪一个
+#[derive(Debug, PartialEq, Eq)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// 同时 poll 两个 Future，返回先完成的那个的结果，两个同时就绪时左边优先
+pub struct Select<A, B> {
+    a: Pin<Box<A>>,
+    b: Pin<Box<B>>,
+}
+
+pub fn select<A: Future, B: Future>(a: A, b: B) -> Select<A, B> {
+    Select { a: Box::pin(a), b: Box::pin(b) }
+}
+
+impl<A: Future, B: Future> Future for Select<A, B> {
+    type Output = Either<A::Output, B::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(a) = self.a.as_mut().poll(cx) {
+            return Poll::Ready(Either::Left(a));
+        }
+        if let Poll::Ready(b) = self.b.as_mut().poll(cx) {
+            return Poll::Ready(Either::Right(b));
+        }
+        Poll::Pending
+    }
+}
+
+/// 超时
+#[derive(Debug, PartialEq, Eq)]
+pub struct Elapsed;
+
+/// future 在 duration 内没有完成时返回 Err(Elapsed)，并且 future 会被 drop
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    match select(future, sleep(duration)).await {
+        Either::Left(output) => Ok(output),
+        Either::Right(()) => Err(Elapsed),
+    }
+}
+
+/// 第一次 poll 时让出执行权，把机会留给同一个 join 中的其它 Future
+pub fn yield_now() -> YieldNow {
+    YieldNow(false)
+}
+
+pub struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// 并发等待两个或三个 Future，返回结果组成的元组
+#[macro_export]
+macro_rules! join {
+    ($a:expr, $b:expr $(,)?) => {
+        $crate::rust_4_11_async::rust_4_11_3::join($a, $b)
+    };
+    ($a:expr, $b:expr, $c:expr $(,)?) => {
+        async {
+            let ((a, b), c) = $crate::rust_4_11_async::rust_4_11_3::join($crate::rust_4_11_async::rust_4_11_3::join($a, $b), $c).await;
+            (a, b, c)
+        }
+    };
+}
+
+/// 等待两个 Future 中先完成的那个，并执行对应的分支，只能在 async 中使用
+/// ```text
+/// select! {
+///     v = future_a => v + 1,
+///     _ = future_b => 0,
+/// }
+/// ```
+#[macro_export]
+macro_rules! select {
+    ($a:pat = $fa:expr => $ea:expr, $b:pat = $fb:expr => $eb:expr $(,)?) => {
+        match $crate::rust_4_11_async::rust_4_11_3::select($fa, $fb).await {
+            $crate::rust_4_11_async::rust_4_11_3::Either::Left($a) => $ea,
+            $crate::rust_4_11_async::rust_4_11_3::Either::Right($b) => $eb,
+        }
+    };
+}
+
+#[outer_print("join")]
+pub fn join_lesson() {
+    async fn task(name: &'static str, ms: u64) -> &'static str {
+        sleep(Duration::from_millis(ms)).await;
+        name
+    }
+
+    let start = Instant::now();
+    let (a, b) = block_on(async { (task("a", 20).await, task("b", 20).await) });
+    let serial = start.elapsed();
+    println!("依次 .await：{} {}，耗时 {:?}", a, b, serial);
+
+    let start = Instant::now();
+    let (a, b, c) = block_on(crate::join!(task("a", 20), task("b", 20), task("c", 20)));
+    let concurrent = start.elapsed();
+    println!("join!：{} {} {}，耗时 {:?}", a, b, c, concurrent);
+    assert_eq!((a, b, c), ("a", "b", "c"));
+    assert!(serial >= Duration::from_millis(40));
+
+    inner_print!("交替执行");
+    // join 中的 Future 在同一个线程上交替推进，yield_now 让出执行权后，另一个 Future 就有机会执行
+    let log = std::cell::RefCell::new(Vec::new());
+    let worker = |name: &'static str| {
+        let log = &log;
+        async move {
+            for i in 0..2 {
+                log.borrow_mut().push(format!("{}{}", name, i));
+                yield_now().await;
+            }
+        }
+    };
+    block_on(join_all([worker("x"), worker("y")]));
+    println!("{:?}", log.borrow());
+    assert_eq!(*log.borrow(), ["x0", "y0", "x1", "y1"]);
+}
+
+#[outer_print("select")]
+pub fn select_lesson() {
+    let winner = block_on(async {
+        crate::select! {
+            _ = sleep(Duration::from_millis(50)) => "慢的",
+            _ = sleep(Duration::from_millis(5)) => "快的",
+        }
+    });
+    println!("{} 先完成", winner);
+    assert_eq!(winner, "快的");
+
+    inner_print!("超时");
+    let ok = block_on(timeout(Duration::from_millis(50), async { 7 }));
+    let err = block_on(timeout(Duration::from_millis(5), TimerFuture::new(Duration::from_secs(1))));
+    println!("{:?} {:?}", ok, err);
+    assert_eq!((ok, err), (Ok(7), Err(Elapsed)));
+}
@@ -0,0 +1,126 @@
+//! # 4.11.4 Pin 与自引用 Future
+//! async 块中跨越 .await 的局部变量会被保存在编译器生成的 Future 结构体里，如果其中一个变量引用了另一个，这个 Future 就是自引用的。
+//! 自引用的值一旦被移动，内部的指针就会指向旧的地址，因此 poll 的签名是 self: Pin<&mut Self>：
+//! 被 Pin 住的值，在它被 drop 之前都不会再移动。
+//!
+//! + 绝大多数类型都实现了 Unpin，Pin 对它们没有任何限制
+//! + async 块生成的 Future 以及包含 PhantomPinned 的类型是 !Unpin 的，只能通过 Box::pin 或者 pin! 固定后才能 poll
+//!
+//! 本节手写了一个自引用的 Future [WordCount]，它的游标指向自身内部的缓冲区。
+
+use std::future::Future;
+use std::marker::PhantomPinned;
+use std::pin::{pin, Pin};
+use std::ptr::NonNull;
+use std::task::{Context, Poll};
+use common::{inner_print, outer_print};
+use super::rust_4_11_1::block_on;
+use super::rust_4_11_3::yield_now;
+
+/// 内部缓冲区的大小
+pub const CAPACITY: usize = 64;
+
+/// 逐个单词统计文本中的单词数，每数一个单词就让出一次执行权
+pub struct WordCount {
+    buf: [u8; CAPACITY],
+    len: usize,
+    /// 指向 buf 中尚未统计的部分，第一次 poll 之前为 None
+    rest: Option<NonNull<str>>,
+    words: usize,
+    _pin: PhantomPinned,
+}
+
+impl WordCount {
+    /// text 超过 [CAPACITY] 字节时 panic
+    pub fn new(text: &str) -> WordCount {
+        assert!(text.len() <= CAPACITY, "文本长度 {} 超过了 {}", text.len(), CAPACITY);
+        let mut buf = [0; CAPACITY];
+        buf[..text.len()].copy_from_slice(text.as_bytes());
+        WordCount { buf, len: text.len(), rest: None, words: 0, _pin: PhantomPinned }
+    }
+
+    /// 已经统计的单词数
+    pub fn words(&self) -> usize {
+        self.words
+    }
+}
+
+impl Future for WordCount {
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+        // SAFETY: 下面只修改字段，不会把 self 移出 Pin
+        let this = unsafe { self.get_unchecked_mut() };
+        let rest: &str = match this.rest {
+            // SAFETY: rest 指向 this.buf，而 this 已经被 Pin 住，从第一次 poll 开始地址就不会再变
+            Some(rest) => unsafe { rest.as_ref() },
+            // 只有被 Pin 住之后，才能安全地创建指向自身的指针
+            None => std::str::from_utf8(&this.buf[..this.len]).expect("new 接收的是 &str"),
+        };
+        let rest = rest.trim_start();
+        if rest.is_empty() {
+            return Poll::Ready(this.words);
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        this.words += 1;
+        this.rest = Some(NonNull::from(&rest[end..]));
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// 跨越 .await 借用局部变量，编译器生成的 Future 是自引用的
+pub async fn borrow_across_await(values: Vec<i32>) -> i32 {
+    let first = &values[0];
+    yield_now().await;
+    *first + values.len() as i32
+}
+
+#[outer_print("Pin 与 Unpin")]
+/// 没有固定的 !Unpin Future 无法调用 poll：
+/// ```text
+/// let future = borrow_across_await(vec![1]);
+/// Future::poll(Pin::new(&mut future), &mut cx);
+/// error[E0277]: `{async fn body of borrow_across_await()}` cannot be unpinned
+/// ```
+pub fn pin_intro() {
+    fn is_unpin<T: Unpin>(_: &T) -> bool {
+        true
+    }
+    // Pin::new 只接受 Unpin 的类型，对它们来说 Pin 和 &mut 没有区别
+    let mut n = 5;
+    let mut pinned = Pin::new(&mut n);
+    *pinned = 6;
+    assert!(is_unpin(&n) && n == 6);
+
+    inner_print!("固定在堆上和栈上");
+    let future = borrow_across_await(vec![10, 20, 30]);
+    println!("async fn 生成的 Future 大小 = {}", std::mem::size_of_val(&future));
+    // Box::pin 固定在堆上，Pin<Box<T>> 本身是 Unpin 的，可以随意移动
+    let boxed = Box::pin(future);
+    assert!(is_unpin(&boxed));
+    assert_eq!(block_on(boxed), 13);
+    // pin! 固定在栈上，原来的变量被移动进了宏里，之后无法再访问
+    let pinned = pin!(borrow_across_await(vec![1]));
+    assert_eq!(block_on(pinned), 2);
+}
+
+#[outer_print("自引用 Future")]
+pub fn self_ref_future() {
+    // 第一次 poll 之前还没有自引用，可以随意移动
+    let future = WordCount::new("Pin 住的 Future 不会再移动");
+    let moved = future;
+    let mut pinned = Box::pin(moved);
+
+    // 手动 poll 一次，之后 rest 就指向了堆上的 buf
+    let waker = std::task::Waker::noop();
+    let mut cx = Context::from_waker(waker);
+    assert!(pinned.as_mut().poll(&mut cx).is_pending());
+    println!("第一次 poll 后：words = {}，buf 地址 = {:p}", pinned.words(), &pinned.buf);
+    // let moved_again = *pinned;
+    // error[E0507]: cannot move out of dereference of `Pin<Box<WordCount>>`
+
+    let count = block_on(pinned);
+    println!("单词数 = {}", count);
+    assert_eq!(count, 4);
+}
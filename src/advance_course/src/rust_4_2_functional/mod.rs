@@ -0,0 +1,2 @@
+pub mod rust_4_2_1;
+pub mod rust_4_2_2;
@@ -0,0 +1,222 @@
+//! # 4.2.1 闭包 Closure
+//! 闭包是一种匿名函数，它可以赋值给变量也可以作为参数传递给其它函数，不同于函数的是，它允许捕获调用者作用域中的值。
+//!
+//! 本节实现一个小小的事件总线 [EventBus]，用它来展示闭包的三种特征：
+//! + Fn：以不可变借用的方式捕获环境，可以被调用任意次，例如过滤条件
+//! + FnMut：以可变借用的方式捕获环境，可以修改捕获的值，例如计数器
+//! + FnOnce：会拿走捕获的值的所有权，只能被调用一次，例如一次性的回调
+
+use std::collections::HashMap;
+use common::{inner_print, outer_print};
+
+type Filter<'a, E> = Box<dyn Fn(&E) -> bool + 'a>;
+type Handler<'a, E> = Box<dyn FnMut(&E) + 'a>;
+type OnceHandler<'a, E> = Box<dyn FnOnce(&E) + 'a>;
+
+/// 事件总线：订阅者是捕获了环境的闭包，'a 是它们所借用的数据的生命周期
+pub struct EventBus<'a, E> {
+    handlers: Vec<(Filter<'a, E>, Handler<'a, E>)>,
+    once: Vec<OnceHandler<'a, E>>,
+}
+
+impl<'a, E> EventBus<'a, E> {
+    pub fn new() -> EventBus<'a, E> {
+        EventBus { handlers: Vec::new(), once: Vec::new() }
+    }
+
+    /// 订阅所有事件，handler 可以修改它捕获的状态
+    pub fn subscribe(&mut self, handler: impl FnMut(&E) + 'a) -> &mut EventBus<'a, E> {
+        self.subscribe_if(|_| true, handler)
+    }
+
+    /// 只订阅满足条件的事件，条件只需读取环境，因此是 Fn
+    pub fn subscribe_if(
+        &mut self,
+        filter: impl Fn(&E) -> bool + 'a,
+        handler: impl FnMut(&E) + 'a,
+    ) -> &mut EventBus<'a, E> {
+        self.handlers.push((Box::new(filter), Box::new(handler)));
+        self
+    }
+
+    /// 只在下一次发布时调用一次，之后自动取消订阅
+    pub fn once(&mut self, handler: impl FnOnce(&E) + 'a) -> &mut EventBus<'a, E> {
+        self.once.push(Box::new(handler));
+        self
+    }
+
+    /// 订阅者的数量，包括还没有被调用的一次性订阅者
+    pub fn len(&self) -> usize {
+        self.handlers.len() + self.once.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 发布事件，返回被调用的订阅者数量
+    pub fn publish(&mut self, event: &E) -> usize {
+        let mut called = 0;
+        for (filter, handler) in self.handlers.iter_mut() {
+            if filter(event) {
+                handler(event);
+                called += 1;
+            }
+        }
+        // FnOnce 被调用时会消耗掉自身，因此要先把它们从 Vec 中取出来
+        for handler in self.once.drain(..) {
+            handler(event);
+            called += 1;
+        }
+        called
+    }
+}
+
+impl<E> Default for EventBus<'_, E> {
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
+
+/// 课程中使用的事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Login(String),
+    Logout(String),
+    Purchase { user: String, amount: u32 },
+}
+
+#[outer_print("闭包")]
+/// 闭包的形式定义：|param1, param2, ...| { 语句1; 语句2; 返回表达式 }，只有一个返回表达式时可以省略大括号。
+/// 闭包的参数和返回值类型可以由编译器推导，但一旦推导出来就固定了，不能再用其它类型调用。
+pub fn closure_intro() {
+    let x = 1;
+    // 闭包可以捕获作用域中的 x，函数则不行
+    let sum = |y| x + y;
+    assert_eq!(sum(2), 3);
+    // fn add(y: i32) -> i32 { x + y }
+    // error[E0434]: can't capture dynamic environment in a fn item
+
+    inner_print!("类型推导");
+    let example_closure = |x| x;
+    let s = example_closure(String::from("hello"));
+    // 第一次调用时 x 被推导为 String，下面的代码无法编译：
+    // let n = example_closure(5);
+    println!("{}", s);
+
+    inner_print!("结构体中的闭包");
+    // 只在第一次需要时才计算，并把结果缓存下来
+    struct Cacher<T: Fn(u64) -> u64> {
+        query: T,
+        value: Option<u64>,
+    }
+    impl<T: Fn(u64) -> u64> Cacher<T> {
+        fn value(&mut self, arg: u64) -> u64 {
+            *self.value.get_or_insert_with(|| (self.query)(arg))
+        }
+    }
+    let mut cacher = Cacher { query: |a| a * 2, value: None };
+    assert_eq!(cacher.value(10), 20);
+    assert_eq!(cacher.value(99), 20);
+}
+
+#[outer_print("三种 Fn 特征")]
+/// 闭包捕获变量有三种途径，恰好对应函数参数的三种传入方式：转移所有权、可变借用、不可变借用，因此相应的 Fn 特征也有三种：FnOnce、FnMut、Fn。
+/// 一个闭包实现了哪种 Fn 特征取决于该闭包如何使用被捕获的变量，而不是取决于闭包如何捕获它们。
+/// 三者的关系是：所有的闭包都实现了 FnOnce；没有移出所捕获变量的闭包实现了 FnMut；不需要修改所捕获变量的闭包实现了 Fn。
+pub fn fn_traits() {
+    let vip = [String::from("alice")];
+    let mut logins = 0;
+    let mut revenue: HashMap<String, u32> = HashMap::new();
+    let welcome = String::from("欢迎回来");
+    let mut greeted = Vec::new();
+    {
+        let mut bus = EventBus::new();
+        bus.subscribe(|e| {
+            if let Event::Login(_) = e {
+                logins += 1;
+            }
+        })
+        // filter 只读取 vip，是 Fn；handler 修改 revenue，是 FnMut
+        .subscribe_if(
+            |e| matches!(e, Event::Purchase { user, .. } if vip.contains(user)),
+            |e| {
+                if let Event::Purchase { user, amount } = e {
+                    *revenue.entry(user.clone()).or_default() += amount;
+                }
+            },
+        )
+        // 闭包中 drop 了 welcome，需要拿走它的所有权，只能调用一次，是 FnOnce
+        .once(|e| {
+            if let Event::Login(user) = e {
+                greeted.push(format!("{}, {}", welcome, user));
+            }
+            drop(welcome);
+        });
+
+        let events = [
+            Event::Login("alice".into()),
+            Event::Purchase { user: "alice".into(), amount: 30 },
+            Event::Purchase { user: "bob".into(), amount: 99 },
+            Event::Logout("alice".into()),
+            Event::Login("bob".into()),
+        ];
+        for event in &events {
+            let called = bus.publish(event);
+            println!("{:?} -> {} 个订阅者", event, called);
+        }
+        assert_eq!(bus.len(), 2);
+    }
+    // bus 离开作用域后，闭包对 logins、revenue 的借用才结束
+    println!("logins = {}, revenue = {:?}, greeted = {:?}", logins, revenue, greeted);
+    assert_eq!(logins, 2);
+    assert_eq!(revenue.get("alice"), Some(&30));
+    assert_eq!(greeted, ["欢迎回来, alice"]);
+}
+
+#[outer_print("move 关键字")]
+/// 在闭包前加上 move 会强制闭包取得捕获变量的所有权，这在把闭包传给新线程、或者从函数中返回闭包时非常有用。
+/// 注意：使用了 move 的闭包依然可能实现了 Fn 或 FnMut，因为一个闭包实现了哪种 Fn 特征取决于它如何使用捕获的变量。
+pub fn move_closure() {
+    let data: Vec<i32> = (1..=3).collect();
+    let handle = std::thread::spawn(move || data.iter().sum::<i32>());
+    // data 已经被移动到闭包中：
+    // println!("{:?}", data);
+    assert_eq!(handle.join().unwrap(), 6);
+
+    let name = String::from("rust");
+    let greet = move || format!("hello, {}", name);
+    // greet 没有移出 name，因此它实现了 Fn，可以调用多次
+    fn call_twice(f: impl Fn() -> String) -> String {
+        f() + " / " + &f()
+    }
+    println!("{}", call_twice(greet));
+}
+
+/// 返回一个把输入乘以 factor 的闭包，每个闭包的类型都不同，只能用 impl Fn 来表示
+pub fn multiplier(factor: i32) -> impl Fn(i32) -> i32 {
+    move |x| x * factor
+}
+
+/// 根据名称返回不同的运算，不同分支的闭包类型不同，需要使用特征对象
+pub fn operation(name: &str) -> Option<Box<dyn Fn(i32, i32) -> i32>> {
+    match name {
+        "add" => Some(Box::new(|a, b| a + b)),
+        "sub" => Some(Box::new(|a, b| a - b)),
+        "max" => Some(Box::new(std::cmp::max)),
+        _ => None,
+    }
+}
+
+#[outer_print("闭包作为函数返回值")]
+/// 就算签名一样的闭包，类型也是不同的，因此返回不同闭包的函数需要返回 Box<dyn Fn>。
+pub fn closure_as_return() {
+    let triple = multiplier(3);
+    assert_eq!(triple(5), 15);
+    for name in ["add", "sub", "max", "div"] {
+        match operation(name) {
+            Some(op) => println!("{}(7, 3) = {}", name, op(7, 3)),
+            None => println!("不支持 {}", name),
+        }
+    }
+}
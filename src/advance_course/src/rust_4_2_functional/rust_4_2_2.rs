@@ -0,0 +1,274 @@
+//! # 4.2.2 迭代器 Iterator
+//! 迭代器允许我们迭代一个连续的集合，例如数组、动态数组 Vec、HashMap 等，在此过程中，只需关心集合中的元素如何处理，而无需关心如何开始、如何结束、按照什么样的索引去访问等问题。
+//!
+//! 迭代器是惰性的：创建迭代器、在它上面调用 map、filter 等适配器都不会做任何事情，直到调用 next 或者 sum、collect 等消费者时才会真正开始迭代。
+//! 本节在标准库的基础上实现几个自己的迭代器和适配器，通过扩展特征 [IteratorExt] 让任何迭代器都能使用它们，拼成一条惰性的数据处理流水线。
+
+use std::collections::VecDeque;
+use std::cell::Cell;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use common::{inner_print, outer_print};
+
+/// 斐波那契数列，超出 u64 范围时结束
+#[derive(Debug, Clone)]
+pub struct Fibonacci {
+    curr: Option<u64>,
+    next: Option<u64>,
+}
+
+impl Fibonacci {
+    pub fn new() -> Fibonacci {
+        Fibonacci { curr: Some(0), next: Some(1) }
+    }
+}
+
+impl Default for Fibonacci {
+    fn default() -> Self {
+        Fibonacci::new()
+    }
+}
+
+impl Iterator for Fibonacci {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let curr = self.curr?;
+        // 下一项溢出时 next 变为 None，当前项依然会被返回
+        let after = self.next.and_then(|next| curr.checked_add(next));
+        self.curr = self.next;
+        self.next = after;
+        Some(curr)
+    }
+}
+
+/// 滑动窗口：与切片的 windows 不同，它适用于任何迭代器，每次只多读取一个元素
+#[derive(Debug, Clone)]
+pub struct Windows<I: Iterator> {
+    iter: I,
+    size: usize,
+    window: VecDeque<I::Item>,
+}
+
+impl<I> Iterator for Windows<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        if self.size == 0 {
+            return None;
+        }
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.size {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+}
+
+/// 把连续的、key 相同的元素分成一组，返回 (key, 这一组元素)
+#[derive(Debug, Clone)]
+pub struct GroupBy<I: Iterator, F, K> {
+    iter: I,
+    key: F,
+    pending: Option<(K, I::Item)>,
+}
+
+impl<I, F, K> Iterator for GroupBy<I, F, K>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> K,
+    K: PartialEq,
+{
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<(K, Vec<I::Item>)> {
+        let (key, first) = match self.pending.take() {
+            Some(pending) => pending,
+            None => {
+                let item = self.iter.next()?;
+                ((self.key)(&item), item)
+            }
+        };
+        let mut group = vec![first];
+        for item in self.iter.by_ref() {
+            let k = (self.key)(&item);
+            if k != key {
+                self.pending = Some((k, item));
+                break;
+            }
+            group.push(item);
+        }
+        Some((key, group))
+    }
+}
+
+/// 为所有迭代器添加的适配器，与标准库的 map、filter 一样，调用它们不会立即迭代
+pub trait IteratorExt: Iterator + Sized {
+    /// 长度为 size 的滑动窗口，size 为 0 时不产生任何元素。
+    /// 窗口不预先分配 size 个位置，随读入的元素增长，size 远大于元素个数时不会申请巨大的内存
+    fn windows(self, size: usize) -> Windows<Self> {
+        Windows { iter: self, size, window: VecDeque::new() }
+    }
+
+    /// 按 key 对连续的元素分组
+    fn group_by<K, F>(self, key: F) -> GroupBy<Self, F, K>
+    where
+        F: FnMut(&Self::Item) -> K,
+    {
+        GroupBy { iter: self, key, pending: None }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+#[outer_print("迭代器")]
+/// for 循环之所以能遍历数组、Vec，是因为它们实现了 IntoIterator 特征，for 循环会先调用 into_iter 把它们转换成迭代器：
+/// + into_iter 会夺走所有权
+/// + iter 是借用
+/// + iter_mut 是可变借用
+pub fn iterator_intro() {
+    let values = vec![1, 2, 3];
+    // for v in values { ... } 大致等价于：
+    // let mut iter = IntoIterator::into_iter(values);
+    // loop {
+    //     match iter.next() {
+    //         Some(v) => { ... }
+    //         None => break,
+    //     }
+    // }
+    let mut iter = IntoIterator::into_iter(values.clone());
+    assert_eq!(iter.next(), Some(1));
+    // by_ref 借用迭代器，for 循环结束后 iter 依然可用
+    for v in iter.by_ref() {
+        println!("{}", v);
+    }
+    // next 方法对迭代器的遍历是消耗性的，每次消耗它一个元素，最终迭代器中将没有任何元素，只能返回 None
+    assert_eq!(iter.next(), None);
+
+    let mut doubled = values;
+    for v in doubled.iter_mut() {
+        *v *= 2;
+    }
+    assert_eq!(doubled, [2, 4, 6]);
+}
+
+#[outer_print("消费者与适配器")]
+/// 只要迭代器上的某个方法 A 在其内部调用了 next 方法，那么 A 就被称为消费性适配器，例如 sum、collect、fold；
+/// 迭代器适配器则会返回一个新的迭代器，例如 map、filter、zip，它们是惰性的，需要一个消费者来收尾。
+pub fn adapters() {
+    let scores = [("alice", 90), ("bob", 62), ("carol", 78)];
+    let passed: Vec<&str> = scores.iter().filter(|(_, s)| *s >= 70).map(|(name, _)| *name).collect();
+    assert_eq!(passed, ["alice", "carol"]);
+    let total: u32 = scores.iter().map(|(_, s)| s).sum();
+    println!("passed = {:?}, total = {}", passed, total);
+
+    inner_print!("惰性");
+    let pulled = Cell::new(0);
+    let iter = (1..).inspect(|_| pulled.set(pulled.get() + 1)).map(|x| x * x).filter(|x| x % 3 == 1);
+    // 到这里为止还没有读取任何元素
+    assert_eq!(pulled.get(), 0);
+    let first: Vec<i32> = iter.take(3).collect();
+    assert_eq!(first, [1, 4, 16]);
+    // 无限序列也没有问题，只读取了需要的 4 个元素
+    println!("first = {:?}, 读取了 {} 个元素", first, pulled.get());
+    assert_eq!(pulled.get(), 4);
+}
+
+#[outer_print("自定义迭代器与适配器")]
+/// 实现 Iterator 特征只需要提供关联类型 Item 和 next 方法，其余几十个方法都有默认实现。
+/// 自定义的适配器是一个包裹着其它迭代器的结构体，再通过扩展特征让所有迭代器都能调用它。
+pub fn custom_iterator() {
+    let fib: Vec<u64> = Fibonacci::new().take(10).collect();
+    println!("{:?}", fib);
+    assert_eq!(fib, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    println!("u64 范围内共有 {} 个斐波那契数", Fibonacci::new().count());
+
+    inner_print!("滑动窗口");
+    // 相邻斐波那契数之比趋近于黄金分割
+    let ratio = Fibonacci::new().skip(1).windows(2).map(|w| w[1] as f64 / w[0] as f64).nth(30).unwrap();
+    println!("ratio = {:.6}", ratio);
+
+    inner_print!("分组");
+    let log = ["GET /", "GET /a", "POST /login", "GET /b", "GET /c", "GET /d"];
+    let groups: Vec<(&str, usize)> = log
+        .iter()
+        .group_by(|line| line.split(' ').next().unwrap())
+        .map(|(method, lines)| (method, lines.len()))
+        .collect();
+    println!("{:?}", groups);
+    assert_eq!(groups, [("GET", 2), ("POST", 1), ("GET", 3)]);
+}
+
+/// 迭代器与索引循环的耗时对比
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub len: usize,
+    pub rounds: u32,
+    pub iterator: Duration,
+    pub index_loop: Duration,
+    pub for_loop: Duration,
+    /// 三种写法的计算结果，必须相同
+    pub results: [u64; 3],
+}
+
+/// 对长度为 len 的数据计算 偶数的平方和，分别用迭代器、下标循环和 for 循环各跑 rounds 轮，返回总耗时
+pub fn bench(len: usize, rounds: u32) -> BenchReport {
+    let data: Vec<u64> = (0..len as u64).map(|i| i.wrapping_mul(2654435761) % 1000).collect();
+
+    fn by_iterator(data: &[u64]) -> u64 {
+        data.iter().filter(|&&x| x.is_multiple_of(2)).map(|&x| x * x).sum()
+    }
+    fn by_index(data: &[u64]) -> u64 {
+        let mut sum = 0;
+        let mut i = 0;
+        while i < data.len() {
+            // 每次访问 data[i] 都有边界检查，除非编译器能证明 i 不会越界
+            if data[i].is_multiple_of(2) {
+                sum += data[i] * data[i];
+            }
+            i += 1;
+        }
+        sum
+    }
+    fn by_for(data: &[u64]) -> u64 {
+        let mut sum = 0;
+        for &x in data {
+            if x.is_multiple_of(2) {
+                sum += x * x;
+            }
+        }
+        sum
+    }
+
+    let time = |f: fn(&[u64]) -> u64| {
+        let start = Instant::now();
+        let mut result = 0;
+        for _ in 0..rounds {
+            result = f(black_box(&data));
+        }
+        (start.elapsed(), result)
+    };
+    let (iterator, a) = time(by_iterator);
+    let (index_loop, b) = time(by_index);
+    let (for_loop, c) = time(by_for);
+    BenchReport { len, rounds, iterator, index_loop, for_loop, results: [a, b, c] }
+}
+
+#[outer_print("迭代器的性能")]
+/// 迭代器是 Rust 的零成本抽象之一：它在编译后与手写的循环几乎一样快，有时候还更快，因为迭代器不需要下标的边界检查，编译器也更容易做向量化。
+/// 下面的数字是在当前机器上实际测得的，debug 构建没有优化，迭代器会明显更慢，请用 cargo test --release 查看优化后的结果。
+pub fn iterator_performance() {
+    let report = bench(1_000_000, 20);
+    let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+    println!("{} 构建，{} 个元素 × {} 轮:", profile, report.len, report.rounds);
+    println!("  迭代器:   {:?}", report.iterator);
+    println!("  下标循环: {:?}", report.index_loop);
+    println!("  for 循环: {:?}", report.for_loop);
+    assert!(report.results.iter().all(|&r| r == report.results[0]));
+}
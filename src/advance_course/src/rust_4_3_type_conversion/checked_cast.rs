@@ -0,0 +1,174 @@
+//! 带检查的数值类型转换
+//!
+//! as 转换永远不会失败，但可能悄悄丢失信息：整数会被截断，浮点数转整数会被饱和截断，大整数转浮点数会被舍入。
+//! [checked_cast] 的结果和 as 完全相同，只是在丢失信息时返回 [CastError]，说明丢失的是什么。
+//!
+//! 所有的整数和浮点数都能精确地表示成 [Repr] 中的一种，比较和转换都在 [Repr] 上进行，
+//! 这样 14 种基本数值类型之间的 196 种组合只需要为每种类型实现一次 [Primitive]。
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// 数值的精确表示：有符号整数、无符号整数和浮点数，f32 转成 f64 不会丢失任何信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Repr {
+    Int(i128),
+    UInt(u128),
+    Float(f64),
+}
+
+/// 2^127 和 2^128，用来判断浮点数是否在 i128、u128 的范围内
+const TWO_POW_127: f64 = 170141183460469231731687303715884105728.0;
+const TWO_POW_128: f64 = 340282366920938463463374607431768211456.0;
+
+impl Repr {
+    /// 两个数值的精确比较，任意一方是 NaN 时返回 None
+    pub fn compare(self, other: Repr) -> Option<Ordering> {
+        match (self, other) {
+            (Repr::Int(a), Repr::Int(b)) => Some(a.cmp(&b)),
+            (Repr::UInt(a), Repr::UInt(b)) => Some(a.cmp(&b)),
+            (Repr::Float(a), Repr::Float(b)) => a.partial_cmp(&b),
+            (Repr::Int(a), Repr::UInt(b)) => Some(if a < 0 { Ordering::Less } else { (a as u128).cmp(&b) }),
+            (Repr::Int(a), Repr::Float(f)) => Self::compare_float(f, |t| a.cmp(&(t as i128)), -TWO_POW_127, TWO_POW_127),
+            (Repr::UInt(a), Repr::Float(f)) => Self::compare_float(f, |t| a.cmp(&(t as u128)), 0.0, TWO_POW_128),
+            (a, b) => b.compare(a).map(Ordering::reverse),
+        }
+    }
+
+    /// 整数和浮点数 f 的比较：f 在 [low, high) 之外时直接得出结果，否则先比较整数部分，再看小数部分
+    fn compare_float(f: f64, cmp_trunc: impl Fn(f64) -> Ordering, low: f64, high: f64) -> Option<Ordering> {
+        if f.is_nan() {
+            None
+        } else if f >= high {
+            Some(Ordering::Less)
+        } else if f < low {
+            Some(Ordering::Greater)
+        } else {
+            // 此时 f.trunc() 一定在整数类型的范围内，as 转换是精确的
+            Some(cmp_trunc(f.trunc()).then(if f.fract() > 0.0 {
+                Ordering::Less
+            } else if f.fract() < 0.0 {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }))
+        }
+    }
+}
+
+impl fmt::Display for Repr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Repr::Int(v) => write!(f, "{}", v),
+            Repr::UInt(v) => write!(f, "{}", v),
+            Repr::Float(v) => write!(f, "{:?}", v),
+        }
+    }
+}
+
+/// 基本数值类型
+pub trait Primitive: Copy + PartialOrd + fmt::Debug + fmt::Display + 'static {
+    const NAME: &'static str;
+    const BITS: u32;
+    const SIGNED: bool;
+    const FLOAT: bool;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_repr(self) -> Repr;
+
+    /// 和 as 相同的转换
+    fn from_repr(repr: Repr) -> Self;
+}
+
+macro_rules! impl_primitive {
+    ($variant:ident, $signed:expr, $float:expr, $($t:ty),+) => {
+        $(
+            impl Primitive for $t {
+                const NAME: &'static str = stringify!($t);
+                const BITS: u32 = (std::mem::size_of::<$t>() * 8) as u32;
+                const SIGNED: bool = $signed;
+                const FLOAT: bool = $float;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_repr(self) -> Repr {
+                    Repr::$variant(self as _)
+                }
+
+                // 先把源类型扩展成 i128、u128 或 f64 都是精确的，再 as 一次，结果和直接 as 相同
+                fn from_repr(repr: Repr) -> Self {
+                    match repr {
+                        Repr::Int(v) => v as $t,
+                        Repr::UInt(v) => v as $t,
+                        Repr::Float(v) => v as $t,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_primitive!(Int, true, false, i8, i16, i32, i64, i128, isize);
+impl_primitive!(UInt, false, false, u8, u16, u32, u64, u128, usize);
+impl_primitive!(Float, true, true, f32, f64);
+
+/// 转换中丢失的信息
+#[derive(Debug, Clone, PartialEq)]
+pub enum CastError {
+    /// 超出了目标类型的范围，as 会截断(整数)、饱和(浮点数转整数)或者得到无穷大(转浮点数)
+    OutOfRange { value: String, target: &'static str },
+    /// 在范围内但无法精确表示，as 会舍去小数部分或者舍入到最近的浮点数
+    Precision { value: String, target: &'static str, result: String },
+    /// NaN 无法转换成整数，as 会得到 0
+    NotANumber { target: &'static str },
+}
+
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CastError::OutOfRange { value, target } => write!(f, "{} 超出了 {} 的范围", value, target),
+            CastError::Precision { value, target, result } => write!(f, "{} 无法用 {} 精确表示，会变成 {}", value, target, result),
+            CastError::NotANumber { target } => write!(f, "NaN 无法转换成 {}", target),
+        }
+    }
+}
+
+impl std::error::Error for CastError {}
+
+/// 和 as 相同的转换，用于泛型代码
+pub fn cast_as<Src: Primitive, Dst: Primitive>(value: Src) -> Dst {
+    Dst::from_repr(value.to_repr())
+}
+
+/// value 是否在 Dst 的范围内，浮点数的范围是 [MIN, MAX]，无穷大不在范围内
+pub fn in_range<Src: Primitive, Dst: Primitive>(value: Src) -> bool {
+    repr_in_range::<Dst>(value.to_repr())
+}
+
+fn repr_in_range<Dst: Primitive>(repr: Repr) -> bool {
+    repr.compare(Dst::MIN.to_repr()).is_some_and(Ordering::is_ge) && repr.compare(Dst::MAX.to_repr()).is_some_and(Ordering::is_le)
+}
+
+/// 把 value 转换成 Dst，转换后的值与原来的值不相等时返回错误
+///
+/// 浮点数之间的转换中，NaN 和无穷大保持原样，不算作错误
+pub fn checked_cast<Src: Primitive, Dst: Primitive>(value: Src) -> Result<Dst, CastError> {
+    let repr = value.to_repr();
+    let result = Dst::from_repr(repr);
+    if let Repr::Float(f) = repr {
+        if f.is_nan() {
+            return if Dst::FLOAT { Ok(result) } else { Err(CastError::NotANumber { target: Dst::NAME }) };
+        }
+        if f.is_infinite() && Dst::FLOAT {
+            return Ok(result);
+        }
+    }
+    if repr.compare(result.to_repr()) == Some(Ordering::Equal) {
+        Ok(result)
+    } else if repr_in_range::<Dst>(repr) {
+        Err(CastError::Precision { value: repr.to_string(), target: Dst::NAME, result: result.to_repr().to_string() })
+    } else {
+        Err(CastError::OutOfRange { value: repr.to_string(), target: Dst::NAME })
+    }
+}
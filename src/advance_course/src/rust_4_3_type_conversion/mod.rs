@@ -0,0 +1,4 @@
+pub mod checked_cast;
+pub mod rust_4_3_1;
+pub mod rust_4_3_2;
+pub mod rust_4_3_3;
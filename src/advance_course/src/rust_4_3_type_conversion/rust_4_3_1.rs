@@ -0,0 +1,88 @@
+//! # 4.3.1 as 转换
+//! Rust 不会隐式地转换数值类型，i32 和 i64 相加都必须显式转换。as 是最直接的转换方式，它永远不会失败，代价是可能丢失信息：
+//! + 大整数转小整数：只保留低位，相当于对 2^N 取模
+//! + 小整数转大整数：有符号数做符号扩展，无符号数补零
+//! + 相同位数的有符号、无符号整数之间：二进制位不变，只是换了一种解释方式
+//! + 浮点数转整数：舍去小数部分，超出范围时饱和到 MIN 或 MAX，NaN 变成 0
+//! + 整数转浮点数、f64 转 f32：舍入到最近的可表示值，超出范围时得到无穷大
+//!
+//! 想知道一次转换有没有丢失信息，可以使用 [checked_cast](super::checked_cast::checked_cast)。
+
+use common::{inner_print, outer_print};
+use super::checked_cast::{checked_cast, CastError, Primitive};
+
+/// 以二进制展示一次转换前后的值
+pub fn show_bits(before: i128, before_bits: u32, after: i128, after_bits: u32) -> String {
+    let mask = |bits: u32| if bits == 128 { u128::MAX } else { (1u128 << bits) - 1 };
+    format!(
+        "{:0w1$b} -> {:0w2$b}",
+        before as u128 & mask(before_bits),
+        after as u128 & mask(after_bits),
+        w1 = before_bits as usize,
+        w2 = after_bits as usize
+    )
+}
+
+/// 用 checked_cast 检查转换，打印 as 的结果和检查的结论
+pub fn report<Src: Primitive, Dst: Primitive>(value: Src) -> String {
+    let plain: Dst = super::checked_cast::cast_as(value);
+    match checked_cast::<Src, Dst>(value) {
+        Ok(_) => format!("{}{} as {} = {}，精确", value, Src::NAME, Dst::NAME, plain),
+        Err(e) => format!("{}{} as {} = {}，{}", value, Src::NAME, Dst::NAME, plain, e),
+    }
+}
+
+#[outer_print("整数之间的 as")]
+pub fn integer_as() {
+    // 截断：300 = 0b1_0010_1100，u8 只保留低 8 位 0b0010_1100 = 44
+    let truncated = 300i32 as u8;
+    println!("300i32 as u8 = {}，{}", truncated, show_bits(300, 16, truncated as i128, 8));
+    assert_eq!(truncated, 44);
+
+    inner_print!("符号扩展与零扩展");
+    // -1i8 的二进制是 1111_1111，扩展到 i16 时用符号位 1 填充高位，数值不变
+    let sign_extended = -1i8 as i16;
+    println!("-1i8 as i16 = {}，{}", sign_extended, show_bits(-1, 8, sign_extended as i128, 16));
+    // 先重新解释成 u8(255)，再零扩展，高位全部填 0
+    let zero_extended = -1i8 as u8 as u16;
+    println!("-1i8 as u8 as u16 = {}，{}", zero_extended, show_bits(-1, 8, zero_extended as i128, 16));
+    // 直接把 -1i8 转成 u16：先符号扩展到 16 位，再重新解释，得到 65535
+    assert_eq!(-1i8 as u16, 65535);
+    assert_eq!((sign_extended, zero_extended), (-1, 255));
+
+    inner_print!("重新解释符号");
+    println!("200u8 as i8 = {}，-56i8 as u8 = {}", 200u8 as i8, -56i8 as u8);
+    assert_eq!(i32::MIN as u32, 1 << 31);
+}
+
+#[outer_print("浮点数的 as")]
+pub fn float_as() {
+    for v in [3.99f64, -3.99, 300.0, -1.0, 1e10, f64::NAN, f64::INFINITY] {
+        println!("{:>6} as u8 = {:>3}，as i32 = {}", v, v as u8, v as i32);
+    }
+    #[allow(clippy::cast_nan_to_int)]
+    let nan = f64::NAN as i32;
+    assert_eq!((300.0f64 as u8, -1.0f64 as u8, nan), (255, 0, 0));
+
+    inner_print!("整数转浮点数会舍入");
+    // f32 只有 24 位有效数字，16777217 = 2^24 + 1 无法精确表示
+    let big = 16_777_217i32;
+    println!("{} as f32 = {}", big, big as f32);
+    assert_eq!(big as f32 as i32, 16_777_216);
+    assert_eq!(u128::MAX as f32, f32::INFINITY);
+}
+
+#[outer_print("checked_cast")]
+pub fn checked_cast_lesson() {
+    println!("{}", report::<i32, u8>(300));
+    println!("{}", report::<i8, u16>(-1));
+    println!("{}", report::<i32, f32>(16_777_217));
+    println!("{}", report::<f64, i32>(2.5));
+    println!("{}", report::<f64, f32>(1e300));
+    println!("{}", report::<f32, u64>(f32::NAN));
+    println!("{}", report::<u64, f64>(1 << 53));
+
+    assert_eq!(checked_cast::<i32, u8>(255), Ok(255));
+    assert_eq!(checked_cast::<i32, u8>(300), Err(CastError::OutOfRange { value: "300".into(), target: "u8" }));
+    assert!(matches!(checked_cast::<f64, i32>(2.5), Err(CastError::Precision { .. })));
+}
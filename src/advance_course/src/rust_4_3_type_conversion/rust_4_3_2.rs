@@ -0,0 +1,115 @@
+//! # 4.3.2 From、Into 与 TryFrom
+//! as 只能用于基本类型，自定义类型之间的转换通过特征来完成：
+//! + From<T> 和 Into<T> 用于不会失败的转换，实现了 From<T> for U，就自动获得了 Into<U> for T
+//! + TryFrom<T> 和 TryInto<T> 用于可能失败的转换，返回 Result，错误类型由实现者定义
+//!
+//! 标准库只为不丢失信息的整数转换实现了 From，例如 u8 到 u32、i16 到 f32；其余的整数之间都实现了 TryFrom。
+//! ? 运算符在传播错误时会调用 From::from，所以为错误类型实现 From 之后，不同的错误可以自动转换。
+
+use std::fmt;
+use std::num::TryFromIntError;
+use common::{inner_print, outer_print};
+
+/// RGB 颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl From<(u8, u8, u8)> for Rgb {
+    fn from((r, g, b): (u8, u8, u8)) -> Rgb {
+        Rgb(r, g, b)
+    }
+}
+
+impl From<Rgb> for u32 {
+    fn from(Rgb(r, g, b): Rgb) -> u32 {
+        (r as u32) << 16 | (g as u32) << 8 | b as u32
+    }
+}
+
+/// 从 "#rrggbb" 或者 0xrrggbb 转换成颜色时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+    /// 不是 # 开头的 7 个字符
+    Format(String),
+    /// 不是合法的十六进制数字
+    Digit(String),
+    /// 数值超过了 0xffffff
+    Range(u32),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorError::Format(s) => write!(f, "颜色的格式应该是 #rrggbb: {}", s),
+            ColorError::Digit(s) => write!(f, "不是合法的十六进制数字: {}", s),
+            ColorError::Range(v) => write!(f, "颜色超出了范围: {:#x}", v),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+impl From<std::num::ParseIntError> for ColorError {
+    fn from(e: std::num::ParseIntError) -> ColorError {
+        ColorError::Digit(e.to_string())
+    }
+}
+
+impl TryFrom<&str> for Rgb {
+    type Error = ColorError;
+
+    fn try_from(s: &str) -> Result<Rgb, ColorError> {
+        let hex = s.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii() && !hex.starts_with('+')).ok_or_else(|| ColorError::Format(s.to_string()))?;
+        // from_str_radix 允许开头的 +，上面已经排除了；ParseIntError 通过上面的 From 实现自动转换成 ColorError
+        let value = u32::from_str_radix(hex, 16)?;
+        Rgb::try_from(value)
+    }
+}
+
+impl TryFrom<u32> for Rgb {
+    type Error = ColorError;
+
+    fn try_from(value: u32) -> Result<Rgb, ColorError> {
+        if value > 0xff_ffff {
+            return Err(ColorError::Range(value));
+        }
+        Ok(Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8))
+    }
+}
+
+#[outer_print("From 与 Into")]
+pub fn from_into() {
+    // 标准库中的 From
+    let s: String = "hello".into();
+    let wide = i64::from(7i32);
+    let float: f64 = 3u32.into();
+    println!("{} {} {}", s, wide, float);
+    // i64 转 i32 可能丢失信息，所以没有实现 From
+    // let narrow = i32::from(7i64);
+    // error[E0277]: the trait bound `i32: From<i64>` is not satisfied
+
+    inner_print!("自定义类型");
+    let orange = Rgb::from((255, 165, 0));
+    let packed: u32 = orange.into();
+    println!("{:?} => {:#08x}", orange, packed);
+    assert_eq!(packed, 0xffa500);
+}
+
+#[outer_print("TryFrom")]
+pub fn try_from() {
+    let ok: Result<u8, TryFromIntError> = u8::try_from(255i32);
+    let err = u8::try_from(256i32);
+    println!("{:?} {:?}", ok, err);
+    assert!(err.is_err());
+
+    inner_print!("自定义错误");
+    for input in ["#ffa500", "ffa500", "#ffa50g", "#ffa5000"] {
+        match Rgb::try_from(input) {
+            Ok(color) => println!("{} => {:?}", input, color),
+            Err(e) => println!("{} => {}", input, e),
+        }
+    }
+    let color: Result<Rgb, _> = 0x1_000_000u32.try_into();
+    println!("{}", color.unwrap_err());
+    assert_eq!(Rgb::try_from("#ffa500"), Ok(Rgb(255, 165, 0)));
+}
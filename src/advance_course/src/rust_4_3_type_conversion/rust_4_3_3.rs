@@ -0,0 +1,69 @@
+//! # 4.3.3 transmute
+//! mem::transmute<T, U> 把 T 的二进制位原封不动地解释成 U，只要求两者大小相同，其它一概不检查，是 Rust 中最危险的操作之一：
+//! + 产生非法的值：bool 只能是 0 或 1，char 必须是合法的 Unicode 标量值，枚举必须是合法的判别值，否则就是未定义行为
+//! + 生命周期：可以把任意引用延长成 'static，编译器不再能阻止悬垂引用
+//! + 布局：没有 #[repr(C)] 的结构体，字段顺序由编译器决定，transmute 的结果没有保证
+//!
+//! 绝大多数场景都有安全的替代品：f32::to_bits、u32::from_ne_bytes、char::from_u32、指针的 as 转换等。
+
+use std::mem;
+use common::{inner_print, outer_print};
+
+/// 安全地把 u8 转成 bool，只有 0 和 1 是合法的
+pub fn bool_from_u8(byte: u8) -> Option<bool> {
+    match byte {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    }
+}
+
+#[outer_print("transmute")]
+pub fn transmute_intro() {
+    // 相同大小的类型之间重新解释二进制位，编译器会提示 unnecessary_transmutes 并给出安全的替代写法
+    // SAFETY: f32 和 u32 大小相同，且任意 u32 都是合法的值
+    #[allow(unnecessary_transmutes)]
+    let bits: u32 = unsafe { mem::transmute(1.0f32) };
+    println!("1.0f32 的二进制位 = {:#010x}", bits);
+    // 安全的写法
+    assert_eq!(bits, 1.0f32.to_bits());
+    assert_eq!(f32::from_bits(bits), 1.0);
+
+    // SAFETY: [u8; 4] 和 u32 大小相同，任意字节都是合法的 u32
+    #[allow(unnecessary_transmutes)]
+    let n: u32 = unsafe { mem::transmute([1u8, 0, 0, 0]) };
+    // 结果取决于字节序，安全的写法会把字节序写在函数名里
+    assert_eq!(n, u32::from_ne_bytes([1, 0, 0, 0]));
+    println!("[1, 0, 0, 0] => {}(本机字节序)，from_le_bytes = {}", n, u32::from_le_bytes([1, 0, 0, 0]));
+
+    // 大小不同时无法编译
+    // let x: u64 = unsafe { mem::transmute(1u32) };
+    // error[E0512]: cannot transmute between types of different sizes, or dependently-sized types
+}
+
+#[outer_print("transmute 的危险")]
+/// 下面这些代码都能编译通过，但都是未定义行为，这里只用注释展示：
+/// ```text
+/// let b: bool = unsafe { mem::transmute(2u8) };            // bool 只能是 0 或 1
+/// let c: char = unsafe { mem::transmute(0xD800u32) };      // 代理区不是合法的 char
+/// let r: &'static String = unsafe { mem::transmute(&local) }; // 延长了生命周期，local 释放后就是悬垂引用
+/// ```
+pub fn transmute_danger() {
+    // 用安全的函数做检查
+    for byte in [0u8, 1, 2] {
+        println!("{} => {:?}", byte, bool_from_u8(byte));
+    }
+    assert_eq!(bool_from_u8(2), None);
+    for code in [0x41u32, 0xD800, 0x11_0000] {
+        println!("{:#x} => {:?}", code, char::from_u32(code));
+    }
+    assert_eq!(char::from_u32(0xD800), None);
+
+    inner_print!("Option<&T> 的空指针优化");
+    // 引用永远不为空，所以 None 用空指针表示，这是少数有文档保证的布局
+    assert_eq!(mem::size_of::<Option<&u8>>(), mem::size_of::<usize>());
+    // SAFETY: Option<&u8> 和 usize 大小相同，None 保证是 0
+    let none: usize = unsafe { mem::transmute(None::<&u8>) };
+    println!("None::<&u8> 的二进制位 = {}", none);
+    assert_eq!(none, 0);
+}
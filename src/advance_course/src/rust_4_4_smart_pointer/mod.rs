@@ -0,0 +1,5 @@
+pub mod rust_4_4_1;
+pub mod rust_4_4_2;
+pub mod rust_4_4_3;
+pub mod rust_4_4_4;
+pub mod rust_4_4_5;
@@ -0,0 +1,98 @@
+//! # 4.4.1 Box<T> 堆对象分配
+//! 智能指针往往是基于结构体实现，它与我们自定义的结构体最大的区别在于它实现了 Deref 和 Drop 特征：
+//! + Deref 可以让智能指针像引用那样工作，这样你就可以写出同时支持智能指针和引用的代码，例如 *T
+//! + Drop 允许你指定智能指针超出作用域后自动执行的代码，例如做一些数据清除等收尾工作
+//!
+//! Box<T> 是最简单的智能指针：它把值分配在堆上，自己只是栈上的一个指针，离开作用域时释放堆内存。
+//! 本节照着 Box 实现一个自己的 [Owned]，Deref 和 Drop 的实现分别放在后面两节中讲解。
+
+use std::alloc::{self, Layout};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr::NonNull;
+use common::{inner_print, outer_print};
+
+/// 一个简化版的 Box：独占地拥有一个堆上的 T
+pub struct Owned<T> {
+    pub(crate) ptr: NonNull<T>,
+    // 告诉编译器 Owned<T> 拥有一个 T，drop 检查时会把 T 也考虑进去
+    _owns: PhantomData<T>,
+}
+
+impl<T> Owned<T> {
+    /// 在堆上分配内存并把 value 移进去
+    pub fn new(value: T) -> Owned<T> {
+        let layout = Layout::new::<T>();
+        let ptr = if layout.size() == 0 {
+            // 零大小类型不需要分配内存，一个对齐的悬垂指针就够了
+            NonNull::dangling()
+        } else {
+            // SAFETY: layout 的大小不为 0
+            let raw = unsafe { alloc::alloc(layout) } as *mut T;
+            NonNull::new(raw).unwrap_or_else(|| alloc::handle_alloc_error(layout))
+        };
+        // SAFETY: ptr 指向一块刚分配的、大小和对齐都满足 T 的内存
+        unsafe { ptr.as_ptr().write(value) };
+        Owned { ptr, _owns: PhantomData }
+    }
+
+    /// 取回堆上的值并释放内存，相当于 *boxed
+    pub fn into_inner(self) -> T {
+        // SAFETY: ptr 指向一个有效的 T，读取后立即释放内存，并用 forget 阻止 Drop 再次释放
+        let value = unsafe { self.ptr.as_ptr().read() };
+        unsafe { Self::dealloc(self.ptr) };
+        mem::forget(self);
+        value
+    }
+
+    /// 堆上的地址
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// 释放内存，不会调用 T 的 drop
+    ///
+    /// # Safety
+    /// ptr 必须来自 Owned::new，且只能释放一次
+    pub(crate) unsafe fn dealloc(ptr: NonNull<T>) {
+        let layout = Layout::new::<T>();
+        if layout.size() != 0 {
+            alloc::dealloc(ptr.as_ptr() as *mut u8, layout);
+        }
+    }
+}
+
+// SAFETY: Owned<T> 独占地拥有 T，与 Box<T> 一样，T 能跨线程时它也能
+unsafe impl<T: Send> Send for Owned<T> {}
+unsafe impl<T: Sync> Sync for Owned<T> {}
+
+#[outer_print("Box<T> 堆对象分配")]
+/// Box 的使用场景：
+/// + 特意将数据分配在堆上，例如避免栈上大数组的拷贝
+/// + 数据较大时，又不想在转移所有权时进行数据拷贝
+/// + 类型的大小在编译期无法确定，但是我们又需要固定大小的类型时，例如递归类型
+/// + 特征对象，用于说明对象实现了一个特征，而不是某个特定的类型
+pub fn box_intro() {
+    let a = Box::new(3);
+    // a 是一个智能指针，需要解引用才能参与运算
+    let b = *a + 1;
+    println!("a = {}, b = {}", a, b);
+
+    inner_print!("递归类型");
+    // 编译器无法知道递归类型的大小，用 Box 包一层后，每一层只占一个指针的大小
+    #[derive(Debug)]
+    enum List {
+        Cons(i32, Box<List>),
+        Nil,
+    }
+    use List::{Cons, Nil};
+    let list = Cons(1, Box::new(Cons(2, Box::new(Nil))));
+    println!("{:?}", list);
+
+    inner_print!("自己实现的 Box");
+    let owned = Owned::new([1u8; 1024]);
+    println!("Owned<[u8; 1024]> 在栈上只占 {} 字节，数据在堆上的 {:p}", mem::size_of_val(&owned), owned.as_ptr());
+    assert_eq!(mem::size_of::<Owned<[u8; 1024]>>(), mem::size_of::<usize>());
+    let array = owned.into_inner();
+    assert_eq!(array.len(), 1024);
+}
@@ -0,0 +1,51 @@
+//! # 4.4.2 Deref 解引用
+//! 实现 Deref 后的智能指针结构体，就可以像普通引用一样，通过 * 进行解引用，*owned 背后实际上是 *(owned.deref())。
+//! 函数和方法的参数还会发生隐式的 Deref 转换：&Owned<String> 会被自动转换成 &String，再转换成 &str。
+
+use std::ops::{Deref, DerefMut};
+use common::{inner_print, outer_print};
+use super::rust_4_4_1::Owned;
+
+impl<T> Deref for Owned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: ptr 始终指向一个有效的 T，返回的引用与 &self 的生命周期相同
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for Owned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: &mut self 保证了独占访问
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+#[outer_print("Deref 解引用")]
+/// Rust 会在函数调用时自动进行连续的 Deref 转换，直到类型与参数匹配为止，而且这一切都发生在编译期，没有运行时开销。
+/// 三种 Deref 转换：
+/// + 当 T: Deref<Target=U>，可以将 &T 转换成 &U
+/// + 当 T: DerefMut<Target=U>，可以将 &mut T 转换成 &mut U
+/// + 当 T: Deref<Target=U>，可以将 &mut T 转换成 &U，但反过来不行
+pub fn deref_intro() {
+    let x = 5;
+    let y = Owned::new(x);
+    assert_eq!(5, *y);
+
+    inner_print!("隐式 Deref 转换");
+    fn display(s: &str) {
+        println!("{}", s);
+    }
+    let s = Owned::new(String::from("hello world"));
+    // &Owned<String> -> &String -> &str
+    display(&s);
+    // 方法调用也会自动解引用
+    assert_eq!(s.len(), 11);
+
+    inner_print!("DerefMut");
+    let mut v = Owned::new(vec![1, 2]);
+    v.push(3);
+    assert_eq!(*v, [1, 2, 3]);
+    println!("{:?}", *v);
+}
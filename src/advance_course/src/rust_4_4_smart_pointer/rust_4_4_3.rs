@@ -0,0 +1,79 @@
+//! # 4.4.3 Drop 释放资源
+//! 在 Rust 中，我们之所以可以一拳打跑 GC 的同时一脚踢翻手动资源回收，主要就归功于 Drop 特征，同时它也是智能指针的必备特征之一。
+//! 指定在一个变量超出作用域时，执行一段特定的代码，最终编译器将帮你自动插入这段收尾代码。
+//!
+//! 本节为 [Owned] 实现 Drop，并用 [DropProbe] 把每一次 drop 记录下来，观察它们的顺序。
+
+use std::cell::RefCell;
+use std::ptr;
+use std::rc::Rc;
+use common::{inner_print, outer_print};
+use super::rust_4_4_1::Owned;
+
+impl<T> Drop for Owned<T> {
+    fn drop(&mut self) {
+        // SAFETY: ptr 指向一个有效的 T，先析构 T，再释放内存，且只会执行一次
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+            Owned::dealloc(self.ptr);
+        }
+    }
+}
+
+/// 共享的 drop 日志
+pub type DropLog = Rc<RefCell<Vec<String>>>;
+
+/// 被 drop 时把自己的名字写进日志
+#[derive(Debug)]
+pub struct DropProbe {
+    pub name: String,
+    log: DropLog,
+}
+
+impl DropProbe {
+    pub fn new(name: &str, log: &DropLog) -> DropProbe {
+        DropProbe { name: name.to_string(), log: Rc::clone(log) }
+    }
+}
+
+impl Drop for DropProbe {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.name.clone());
+    }
+}
+
+#[outer_print("Drop 释放资源")]
+/// Drop 的顺序：
+/// + 变量级别，按照逆序的方式，先创建的变量后 drop
+/// + 结构体内部，按照字段定义中的顺序依次 drop
+///
+/// 我们无法手动调用 drop 方法，因为这会导致值被 drop 两次；想要提前释放，可以调用 std::mem::drop 函数，它会拿走值的所有权。
+pub fn drop_order() {
+    let log = DropLog::default();
+    {
+        struct Pair {
+            _first: DropProbe,
+            _second: DropProbe,
+        }
+        let _a = DropProbe::new("a", &log);
+        let _pair = Pair { _first: DropProbe::new("pair.first", &log), _second: DropProbe::new("pair.second", &log) };
+        let _boxed = Owned::new(DropProbe::new("owned", &log));
+    }
+    println!("{:?}", log.borrow());
+    assert_eq!(*log.borrow(), ["owned", "pair.first", "pair.second", "a"]);
+
+    inner_print!("提前释放");
+    log.borrow_mut().clear();
+    let early = Owned::new(DropProbe::new("early", &log));
+    // early.drop();
+    // error[E0040]: explicit use of destructor method
+    drop(early);
+    assert_eq!(*log.borrow(), ["early"]);
+
+    inner_print!("into_inner 不会 drop");
+    log.borrow_mut().clear();
+    let probe = Owned::new(DropProbe::new("inner", &log)).into_inner();
+    assert!(log.borrow().is_empty());
+    drop(probe);
+    assert_eq!(*log.borrow(), ["inner"]);
+}
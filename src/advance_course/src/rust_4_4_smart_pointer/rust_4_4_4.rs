@@ -0,0 +1,64 @@
+//! # 4.4.4 Rc 与 Arc
+//! Rust 所有权机制要求一个值只能有一个所有者，但在图数据结构、多线程共享数据等场景中，一个值需要被多个所有者同时拥有。
+//! Rc(reference counting) 通过引用计数的方式，允许一个数据资源在同一时刻拥有多个所有者：每 clone 一次计数加一，每 drop 一次计数减一，计数归零时才真正释放数据。
+//!
+//! Rc 只能用于同一线程内部，想在线程之间共享数据需要使用原子化的 Arc，它的计数器是原子类型，代价是略慢一些。
+
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+use common::{inner_print, outer_print};
+
+#[outer_print("Rc<T>")]
+/// Rc::clone 不会拷贝底层数据，只是复制了智能指针并增加引用计数；Rc<T> 是指向底层数据的不可变引用，无法通过它修改数据。
+pub fn rc_intro() {
+    let a = Rc::new(String::from("hello, world"));
+    println!("创建 a 后的计数 = {}", Rc::strong_count(&a));
+    let b = Rc::clone(&a);
+    println!("创建 b 后的计数 = {}", Rc::strong_count(&a));
+    {
+        let c = Rc::clone(&a);
+        println!("创建 c 后的计数 = {}", Rc::strong_count(&c));
+        assert_eq!(Rc::strong_count(&a), 3);
+    }
+    println!("c 离开作用域后的计数 = {}", Rc::strong_count(&a));
+    assert_eq!(Rc::strong_count(&b), 2);
+    // a 和 b 指向同一份数据
+    assert!(Rc::ptr_eq(&a, &b));
+
+    inner_print!("共享所有权");
+    // 两个列表共享同一个尾巴
+    #[derive(Debug)]
+    enum List {
+        Cons(i32, Rc<List>),
+        Nil,
+    }
+    use List::{Cons, Nil};
+    let tail = Rc::new(Cons(10, Rc::new(Nil)));
+    let x = Cons(3, Rc::clone(&tail));
+    let y = Cons(4, Rc::clone(&tail));
+    println!("x = {:?}\ny = {:?}\ntail 的计数 = {}", x, y, Rc::strong_count(&tail));
+    assert_eq!(Rc::strong_count(&tail), 3);
+}
+
+#[outer_print("多线程中的 Arc<T>")]
+/// Rc<T> 没有实现 Send，无法移动到其它线程：
+/// ```text
+/// let s = Rc::new(String::from("多线程漫游者"));
+/// thread::spawn(move || println!("{}", s));
+/// error[E0277]: `Rc<String>` cannot be sent between threads safely
+/// ```
+pub fn arc_intro() {
+    let s = Arc::new(String::from("多线程漫游者"));
+    let handles: Vec<_> = (0..3)
+        .map(|i| {
+            let s = Arc::clone(&s);
+            thread::spawn(move || format!("线程 {}: {}", i, s))
+        })
+        .collect();
+    for handle in handles {
+        println!("{}", handle.join().unwrap());
+    }
+    // 所有线程都已结束，它们持有的 Arc 也已经被 drop
+    assert_eq!(Arc::strong_count(&s), 1);
+}
@@ -0,0 +1,61 @@
+//! # 4.4.5 Cell 与 RefCell
+//! Rust 的借用规则在编译期检查，有时过于严格：我们明确知道某个操作是安全的，编译器却无法证明。
+//! Cell 和 RefCell 提供了内部可变性：可以通过不可变引用修改内部的值。
+//! + Cell<T> 适用于 T 实现了 Copy 的情况，get 返回值的拷贝，set 直接替换，不会产生引用
+//! + RefCell<T> 把借用规则的检查从编译期推迟到运行时，违反规则时会 panic
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use common::{inner_print, outer_print};
+
+#[outer_print("Cell")]
+pub fn cell() {
+    let c = Cell::new("asdf");
+    let one = c.get();
+    c.set("qwer");
+    let two = c.get();
+    println!("{}, {}", one, two);
+    assert_eq!((one, two), ("asdf", "qwer"));
+
+    // 通过 &self 修改内部的计数，调用者无需 &mut
+    struct Counter {
+        hits: Cell<u32>,
+    }
+    impl Counter {
+        fn hit(&self) -> u32 {
+            self.hits.set(self.hits.get() + 1);
+            self.hits.get()
+        }
+    }
+    let counter = Counter { hits: Cell::new(0) };
+    counter.hit();
+    assert_eq!(counter.hit(), 2);
+}
+
+#[outer_print("RefCell")]
+/// | Rust 规则 | 智能指针带来的额外规则 |
+/// | --- | --- |
+/// | 一个数据只有一个所有者 | Rc/Arc 让一个数据可以拥有多个所有者 |
+/// | 要么多个不可变借用，要么一个可变借用 | RefCell 实现编译期可变、不可变引用共存 |
+/// | 违背规则导致编译错误 | 违背规则导致运行时 panic |
+pub fn ref_cell() {
+    let s = RefCell::new(String::from("hello, world"));
+    {
+        let s1 = s.borrow();
+        // 已经有不可变借用时，再可变借用会失败；borrow_mut 会 panic，try_borrow_mut 返回 Err
+        assert!(s.try_borrow_mut().is_err());
+        println!("{}", s1);
+    }
+    s.borrow_mut().push('!');
+    assert_eq!(*s.borrow(), "hello, world!");
+
+    inner_print!("Rc<RefCell<T>>");
+    // Rc 提供多个所有者，RefCell 提供可变性，组合起来就是多个所有者共享并修改同一份数据
+    let shared = Rc::new(RefCell::new(Vec::new()));
+    let writers: Vec<Rc<RefCell<Vec<i32>>>> = (0..3).map(|_| Rc::clone(&shared)).collect();
+    for (i, w) in writers.iter().enumerate() {
+        w.borrow_mut().push(i as i32);
+    }
+    println!("{:?}，strong_count = {}", shared.borrow(), Rc::strong_count(&shared));
+    assert_eq!(*shared.borrow(), [0, 1, 2]);
+}
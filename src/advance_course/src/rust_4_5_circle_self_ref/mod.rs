@@ -0,0 +1,2 @@
+pub mod rust_4_5_1;
+pub mod rust_4_5_2;
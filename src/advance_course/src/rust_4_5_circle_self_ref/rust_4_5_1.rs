@@ -0,0 +1,296 @@
+//! # 4.5.1 Weak 与循环引用
+//! Rust 的安全性是众所周知的，但是不代表它不会内存泄漏。一个典型的例子就是同时使用 Rc<T> 和 RefCell<T> 创建循环引用，
+//! 最终这些引用的计数都无法被归零，因此 Rc<T> 拥有的值也不会被释放清理。
+//!
+//! Weak 非常类似于 Rc，但是与 Rc 持有所有权不同，Weak 不持有所有权，它仅仅保存一份指向数据的弱引用：
+//! 通过 upgrade 访问数据，返回 Option<Rc<T>>，数据已经被释放时返回 None；Weak 的数量不影响数据何时被释放。
+//!
+//! 本节用 Rc + Weak 实现双向链表和带父指针的树：指向"下一个"、"子节点"的是 Rc，指回"上一个"、"父节点"的是 Weak。
+
+use std::cell::{Ref, RefCell};
+use std::rc::{Rc, Weak};
+use common::{inner_print, outer_print};
+use crate::rust_4_4_smart_pointer::rust_4_4_3::{DropLog, DropProbe};
+
+type Link<T> = Option<Rc<RefCell<ListNode<T>>>>;
+
+/// 双向链表的节点
+pub struct ListNode<T> {
+    pub value: T,
+    next: Link<T>,
+    prev: Option<Weak<RefCell<ListNode<T>>>>,
+}
+
+/// 双向链表：前一个节点用 Rc 拥有后一个节点，后一个节点用 Weak 指回前一个节点
+pub struct DoublyLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+}
+
+impl<T> DoublyLinkedList<T> {
+    pub fn new() -> DoublyLinkedList<T> {
+        DoublyLinkedList { head: None, tail: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(ListNode { value, next: None, prev: None }));
+        match self.tail.take() {
+            Some(old) => {
+                node.borrow_mut().prev = Some(Rc::downgrade(&old));
+                old.borrow_mut().next = Some(Rc::clone(&node));
+            }
+            None => self.head = Some(Rc::clone(&node)),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(ListNode { value, next: None, prev: None }));
+        match self.head.take() {
+            Some(old) => {
+                old.borrow_mut().prev = Some(Rc::downgrade(&node));
+                node.borrow_mut().next = Some(old);
+            }
+            None => self.tail = Some(Rc::clone(&node)),
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let old = self.head.take()?;
+        match old.borrow_mut().next.take() {
+            Some(next) => {
+                next.borrow_mut().prev = None;
+                self.head = Some(next);
+            }
+            None => self.tail = None,
+        }
+        self.len -= 1;
+        // 此时 old 已经没有其它强引用了，可以取出其中的值
+        Some(Self::unwrap(old))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let old = self.tail.take()?;
+        match old.borrow_mut().prev.take().and_then(|prev| prev.upgrade()) {
+            Some(prev) => {
+                prev.borrow_mut().next = None;
+                self.tail = Some(prev);
+            }
+            None => self.head = None,
+        }
+        self.len -= 1;
+        Some(Self::unwrap(old))
+    }
+
+    fn unwrap(node: Rc<RefCell<ListNode<T>>>) -> T {
+        match Rc::try_unwrap(node) {
+            Ok(node) => node.into_inner().value,
+            Err(_) => unreachable!("链表节点在弹出时不应再有其它强引用"),
+        }
+    }
+
+    /// 头节点的值。返回的是借用而不是节点的 Rc，弹出节点时不会有外部的强引用，借用期间也不能修改链表：
+    /// ```compile_fail
+    /// use advance_course::rust_4_5_circle_self_ref::rust_4_5_1::DoublyLinkedList;
+    /// let mut list = DoublyLinkedList::new();
+    /// list.push_back(1);
+    /// let head = list.head().unwrap();
+    /// list.pop_front();
+    /// assert_eq!(*head, 1);
+    /// ```
+    pub fn head(&self) -> Option<Ref<'_, T>> {
+        self.head.as_ref().map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    /// 尾节点的值
+    pub fn tail(&self) -> Option<Ref<'_, T>> {
+        self.tail.as_ref().map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    /// 头节点的强引用和弱引用计数，用来观察引用计数
+    pub fn head_counts(&self) -> Option<(usize, usize)> {
+        self.head.as_ref().map(|node| (Rc::strong_count(node), Rc::weak_count(node)))
+    }
+
+    /// 尾节点的强引用和弱引用计数
+    pub fn tail_counts(&self) -> Option<(usize, usize)> {
+        self.tail.as_ref().map(|node| (Rc::strong_count(node), Rc::weak_count(node)))
+    }
+}
+
+impl<T: Clone> DoublyLinkedList<T> {
+    /// 从头到尾的所有值
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut cursor = self.head.clone();
+        while let Some(node) = cursor {
+            values.push(node.borrow().value.clone());
+            cursor = node.borrow().next.clone();
+        }
+        values
+    }
+
+    /// 沿着 Weak 指针从尾到头的所有值
+    pub fn to_vec_rev(&self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut cursor = self.tail.clone();
+        while let Some(node) = cursor {
+            values.push(node.borrow().value.clone());
+            cursor = node.borrow().prev.as_ref().and_then(Weak::upgrade);
+        }
+        values
+    }
+}
+
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        DoublyLinkedList::new()
+    }
+}
+
+impl<T> Drop for DoublyLinkedList<T> {
+    /// 默认的 drop 会递归地释放 next 链，链表很长时可能栈溢出，这里改成循环
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+/// 带父指针的树节点：父节点用 Rc 拥有子节点，子节点用 Weak 指向父节点
+#[derive(Debug)]
+pub struct TreeNode {
+    pub value: i32,
+    parent: RefCell<Weak<TreeNode>>,
+    children: RefCell<Vec<Rc<TreeNode>>>,
+}
+
+impl TreeNode {
+    pub fn new(value: i32) -> Rc<TreeNode> {
+        Rc::new(TreeNode { value, parent: RefCell::new(Weak::new()), children: RefCell::new(Vec::new()) })
+    }
+
+    /// 把 child 挂到 parent 下面
+    pub fn add_child(parent: &Rc<TreeNode>, child: Rc<TreeNode>) {
+        *child.parent.borrow_mut() = Rc::downgrade(parent);
+        parent.children.borrow_mut().push(child);
+    }
+
+    /// 父节点已经被释放或者没有父节点时返回 None
+    pub fn parent(&self) -> Option<Rc<TreeNode>> {
+        self.parent.borrow().upgrade()
+    }
+
+    pub fn children(&self) -> Vec<Rc<TreeNode>> {
+        self.children.borrow().clone()
+    }
+
+    /// 从根节点到当前节点的路径
+    pub fn path(node: &Rc<TreeNode>) -> Vec<i32> {
+        let mut path = vec![node.value];
+        let mut cursor = node.parent();
+        while let Some(parent) = cursor {
+            path.push(parent.value);
+            cursor = parent.parent();
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// 用 Rc 互相指向的节点，会造成循环引用
+pub struct CycleNode {
+    pub probe: DropProbe,
+    pub next: RefCell<Option<Rc<CycleNode>>>,
+}
+
+/// 创建 a -> b -> a 的循环，返回两个节点的 Weak，调用者持有的强引用在函数返回时就已经全部 drop 了
+pub fn make_cycle(log: &DropLog) -> (Weak<CycleNode>, Weak<CycleNode>) {
+    let a = Rc::new(CycleNode { probe: DropProbe::new("a", log), next: RefCell::new(None) });
+    let b = Rc::new(CycleNode { probe: DropProbe::new("b", log), next: RefCell::new(Some(Rc::clone(&a))) });
+    *a.next.borrow_mut() = Some(Rc::clone(&b));
+    (Rc::downgrade(&a), Rc::downgrade(&b))
+}
+
+/// 断开循环：取出 a 中指向 b 的 Rc，之后两个节点都会被释放
+pub fn break_cycle(a: &Weak<CycleNode>) {
+    if let Some(a) = a.upgrade() {
+        a.next.borrow_mut().take();
+    }
+}
+
+#[outer_print("双向链表")]
+/// 如果两个方向都用 Rc，相邻节点会互相持有对方，形成循环引用，整个链表永远不会被释放；
+/// 把指向前一个节点的指针换成 Weak，强引用就只有从头到尾一个方向了。
+pub fn doubly_linked_list() {
+    let mut list = DoublyLinkedList::new();
+    for v in [2, 3] {
+        list.push_back(v);
+    }
+    list.push_front(1);
+    println!("正向 {:?}，反向 {:?}", list.to_vec(), list.to_vec_rev());
+
+    let (head_strong, head_weak) = list.head_counts().unwrap();
+    let (tail_strong, tail_weak) = list.tail_counts().unwrap();
+    // head 只被 list.head 持有，第二个节点的 prev 弱引用它；tail 被前一个节点的 next 和 list.tail 持有
+    println!("head = {}: strong = {}, weak = {}", *list.head().unwrap(), head_strong, head_weak);
+    println!("tail = {}: strong = {}, weak = {}", *list.tail().unwrap(), tail_strong, tail_weak);
+    assert_eq!((head_strong, head_weak), (1, 1));
+    assert_eq!((tail_strong, tail_weak), (2, 0));
+
+    assert_eq!(list.pop_back(), Some(3));
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.to_vec(), [2]);
+}
+
+#[outer_print("带父指针的树")]
+pub fn parent_tree() {
+    let leaf = TreeNode::new(3);
+    println!("leaf parent = {:?}", leaf.parent().map(|p| p.value));
+    {
+        let branch = TreeNode::new(5);
+        TreeNode::add_child(&branch, Rc::clone(&leaf));
+        println!("leaf parent = {:?}", leaf.parent().map(|p| p.value));
+        println!(
+            "branch: strong = {}, weak = {}；leaf: strong = {}, weak = {}",
+            Rc::strong_count(&branch),
+            Rc::weak_count(&branch),
+            Rc::strong_count(&leaf),
+            Rc::weak_count(&leaf),
+        );
+        assert_eq!((Rc::strong_count(&branch), Rc::weak_count(&branch)), (1, 1));
+        assert_eq!((Rc::strong_count(&leaf), Rc::weak_count(&leaf)), (2, 0));
+        assert_eq!(TreeNode::path(&leaf), [5, 3]);
+    }
+    // branch 离开作用域后被释放，leaf 的父指针自动失效
+    println!("leaf parent = {:?}", leaf.parent().map(|p| p.value));
+    assert!(leaf.parent().is_none());
+    assert_eq!(Rc::strong_count(&leaf), 1);
+}
+
+#[outer_print("循环引用导致的内存泄漏")]
+/// a 和 b 互相持有对方的 Rc，离开作用域时各自的计数只能从 2 减到 1，永远不会归零，Drop 也永远不会执行。
+pub fn cycle_leak() {
+    let log = DropLog::default();
+    let (a, b) = make_cycle(&log);
+    // 外部已经没有任何强引用了，节点却依然活着
+    println!("a: strong = {}，b: strong = {}，已 drop: {:?}", a.strong_count(), b.strong_count(), log.borrow());
+    assert_eq!((a.strong_count(), b.strong_count()), (1, 1));
+    assert!(log.borrow().is_empty());
+
+    inner_print!("断开循环");
+    break_cycle(&a);
+    println!("a: strong = {}，b: strong = {}，已 drop: {:?}", a.strong_count(), b.strong_count(), log.borrow());
+    assert!(a.upgrade().is_none() && b.upgrade().is_none());
+    assert_eq!(*log.borrow(), ["b", "a"]);
+}
@@ -0,0 +1,140 @@
+//! # 4.5.2 结构体中的自引用与 arena
+//! 结构体中的字段引用同一个结构体中的其它字段，或者节点之间互相引用，在 Rust 中都很难直接表达：
+//! 用引用会遇到生命周期问题，用 Rc + Weak 又需要到处 borrow、upgrade，还要时刻提防循环引用。
+//!
+//! 另一种常见的做法是 arena：所有节点都放在同一个 Vec 中，节点之间用下标相互引用。
+//! 下标只是普通的整数，既没有生命周期的限制，也不会造成循环引用，整棵树随着 arena 一起被释放。
+
+use common::{inner_print, outer_print};
+
+/// 节点在 arena 中的编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Debug)]
+pub struct ArenaNode<T> {
+    pub value: T,
+    pub parent: Option<NodeId>,
+    pub children: Vec<NodeId>,
+}
+
+/// 以 arena 为存储的树(或者森林)
+#[derive(Debug)]
+pub struct Arena<T> {
+    nodes: Vec<ArenaNode<T>>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Arena<T> {
+        Arena { nodes: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 添加一个没有父节点的节点
+    pub fn add_root(&mut self, value: T) -> NodeId {
+        self.nodes.push(ArenaNode { value, parent: None, children: Vec::new() });
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// 在 parent 下添加一个子节点
+    pub fn add_child(&mut self, parent: NodeId, value: T) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(ArenaNode { value, parent: Some(parent), children: Vec::new() });
+        self.nodes[parent.0].children.push(id);
+        id
+    }
+
+    pub fn get(&self, id: NodeId) -> &ArenaNode<T> {
+        &self.nodes[id.0]
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> &mut ArenaNode<T> {
+        &mut self.nodes[id.0]
+    }
+
+    /// 从 id 开始一直到根节点
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(Some(id), move |&id| self.nodes[id.0].parent)
+    }
+
+    /// 节点的深度，根节点为 0
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.ancestors(id).count() - 1
+    }
+
+    /// 先序遍历以 id 为根的子树
+    pub fn preorder(&self, id: NodeId) -> Vec<NodeId> {
+        let mut order = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            stack.extend(self.nodes[id.0].children.iter().rev());
+        }
+        order
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+#[outer_print("结构体自引用")]
+/// 下面的结构体无法创建：name 的引用必须在 value 创建之后才能获得，而 value 被移动进结构体后，引用又会失效。
+/// ```text
+/// struct SelfRef<'a> {
+///     value: String,
+///     pointer_to_value: &'a str,
+/// }
+/// ```
+/// 常见的解决办法有：使用下标代替引用、使用裸指针并保证结构体不再被移动(Pin)、或者使用 ouroboros 这类三方库。
+pub fn self_ref() {
+    // 保存下标范围而不是引用
+    struct Document {
+        text: String,
+        title: std::ops::Range<usize>,
+    }
+    impl Document {
+        fn title(&self) -> &str {
+            &self.text[self.title.clone()]
+        }
+    }
+    let text = String::from("# Rust\n正文");
+    let end = text.find('\n').unwrap();
+    let doc = Document { text, title: 2..end };
+    // doc 可以随意移动，下标依然有效
+    let moved = doc;
+    assert_eq!(moved.title(), "Rust");
+    println!("title = {}", moved.title());
+}
+
+#[outer_print("arena 树")]
+pub fn arena_tree() {
+    let mut tree = Arena::new();
+    let root = tree.add_root("src");
+    let basic = tree.add_child(root, "basic_course");
+    let advance = tree.add_child(root, "advance_course");
+    let lib = tree.add_child(advance, "lib.rs");
+    tree.add_child(basic, "lib.rs");
+
+    let path: Vec<&str> = tree.ancestors(lib).map(|id| tree.get(id).value).collect();
+    println!("{}", path.iter().rev().cloned().collect::<Vec<_>>().join("/"));
+    assert_eq!(tree.depth(lib), 2);
+
+    inner_print!("先序遍历");
+    for id in tree.preorder(root) {
+        println!("{}{}", "  ".repeat(tree.depth(id)), tree.get(id).value);
+    }
+
+    // 修改节点不需要 RefCell，拿到 &mut Arena 就可以修改任意节点
+    tree.get_mut(lib).value = "mod.rs";
+    assert_eq!(tree.get(lib).value, "mod.rs");
+}
@@ -0,0 +1,5 @@
+pub mod rust_4_6_1;
+pub mod rust_4_6_2;
+pub mod rust_4_6_3;
+pub mod rust_4_6_4;
+pub mod rust_4_6_5;
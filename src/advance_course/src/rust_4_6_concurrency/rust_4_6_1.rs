@@ -0,0 +1,70 @@
+//! # 4.6.1 使用线程
+//! 并发和并行都是对"多任务"处理的描述，并发是同一时间段内交替处理多个任务，并行是同一时刻真正同时处理多个任务。
+//!
+//! Rust 标准库使用 1:1 线程模型：一个语言级线程对应一个操作系统线程。
+//! 线程内部的代码使用闭包来执行，main 线程一旦结束，程序就立刻结束，因此需要通过 join 等待子线程结束。
+
+use std::thread;
+use std::time::Duration;
+use common::{inner_print, outer_print};
+
+/// 把 data 切成 parts 份，每份交给一个作用域线程求和
+pub fn parallel_sum(data: &[u64], parts: usize) -> u64 {
+    assert!(parts > 0, "parts 不能为 0");
+    let chunk = data.len().div_ceil(parts).max(1);
+    thread::scope(|s| {
+        let handles: Vec<_> = data.chunks(chunk).map(|part| s.spawn(move || part.iter().sum::<u64>())).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+#[outer_print("创建线程")]
+pub fn spawn_thread() {
+    let handle = thread::spawn(|| {
+        for i in 1..4 {
+            println!("hi number {} from the spawned thread!", i);
+            thread::sleep(Duration::from_millis(1));
+        }
+        "spawned"
+    });
+    for i in 1..3 {
+        println!("hi number {} from the main thread!", i);
+        thread::sleep(Duration::from_millis(1));
+    }
+    // join 会阻塞当前线程直到子线程结束，并拿到子线程的返回值
+    assert_eq!(handle.join().unwrap(), "spawned");
+
+    inner_print!("move 闭包");
+    // 子线程的生命周期可能比 main 更长，因此必须用 move 拿走 s 的所有权
+    // thread::spawn(|| println!("{}", s));
+    // error[E0373]: closure may outlive the current function, but it borrows `s`, which is owned by the current function
+    let s = String::from("hello");
+    let upper = thread::spawn(move || s.to_uppercase()).join().unwrap();
+    assert_eq!(upper, "HELLO");
+
+    inner_print!("线程 panic");
+    // 通过 Builder 可以给线程命名，panic 信息中会带上线程名
+    let name = thread::Builder::new().name("worker".into()).spawn(|| thread::current().name().map(String::from));
+    assert_eq!(name.unwrap().join().unwrap().as_deref(), Some("worker"));
+    // 子线程 panic 不会影响 main 线程，join 会返回 Err
+    let result = thread::Builder::new().name("panicker".into()).spawn(|| panic!("子线程 panic 了")).unwrap().join();
+    assert!(result.is_err());
+}
+
+#[outer_print("作用域线程")]
+/// thread::scope 保证作用域内创建的所有线程在作用域结束前都已经 join，因此线程可以直接借用外部的局部变量，不再需要 move 和 Arc。
+pub fn scoped_thread() {
+    let data: Vec<u64> = (1..=1000).collect();
+    let mut evens = 0;
+    thread::scope(|s| {
+        s.spawn(|| println!("共 {} 个数", data.len()));
+        // 可变借用也可以，只要没有其它线程同时借用
+        s.spawn(|| evens = data.iter().filter(|n| *n % 2 == 0).count());
+    });
+    println!("偶数 {} 个", evens);
+    assert_eq!(evens, 500);
+
+    let sum = parallel_sum(&data, 4);
+    println!("4 个线程求和 = {}", sum);
+    assert_eq!(sum, 500500);
+}
@@ -0,0 +1,101 @@
+//! # 4.6.2 线程间的消息传递
+//! 不要通过共享内存来通讯，而是通过通讯来共享内存。
+//!
+//! 标准库提供的 std::sync::mpsc 是多生产者、单消费者的通道：发送者可以 clone 出多个，接收者只能有一个。
+//! 所有发送者都被 drop 之后，接收者的 recv 会返回 Err，for 循环也随之结束。
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use common::{inner_print, outer_print};
+
+/// 用一个共享的任务通道把 jobs 分发给 workers 个线程，返回每个任务的 (worker 编号, 任务, 结果)，按任务排序
+pub fn distribute<F>(jobs: Vec<u64>, workers: usize, work: F) -> Vec<(usize, u64, u64)>
+where
+    F: Fn(u64) -> u64 + Send + Sync + 'static,
+{
+    let (job_tx, job_rx) = mpsc::channel::<u64>();
+    let (result_tx, result_rx) = mpsc::channel();
+    // 接收者只能有一个，多个 worker 通过 Mutex 轮流从同一个接收者中取任务
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let work = Arc::new(work);
+
+    let handles: Vec<_> = (0..workers)
+        .map(|id| {
+            let job_rx = Arc::clone(&job_rx);
+            let result_tx = result_tx.clone();
+            let work = Arc::clone(&work);
+            thread::spawn(move || loop {
+                // 取出任务后锁立刻释放，计算时其它 worker 可以继续取任务
+                let job = job_rx.lock().unwrap().recv();
+                match job {
+                    Ok(job) => result_tx.send((id, job, work(job))).unwrap(),
+                    Err(_) => break,
+                }
+            })
+        })
+        .collect();
+    // 只保留 worker 手里的发送者，它们全部退出后 result_rx 的迭代才会结束
+    drop(result_tx);
+
+    for job in jobs {
+        job_tx.send(job).unwrap();
+    }
+    drop(job_tx);
+
+    let mut results: Vec<_> = result_rx.iter().collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    results.sort_by_key(|&(_, job, _)| job);
+    results
+}
+
+#[outer_print("通道")]
+pub fn channel() {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let val = String::from("hi");
+        tx.send(val).unwrap();
+        // val 的所有权已经被转移到了接收者
+        // println!("val is {}", val);
+        // error[E0382]: borrow of moved value: `val`
+    });
+    let received = rx.recv().unwrap();
+    println!("Got: {}", received);
+    // 发送者已经被 drop，再接收会返回错误
+    assert!(rx.recv().is_err());
+
+    inner_print!("多个发送者");
+    let (tx, rx) = mpsc::channel();
+    for id in 0..3 {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for i in 0..2 {
+                tx.send(id * 10 + i).unwrap();
+            }
+        });
+    }
+    drop(tx);
+    let mut all: Vec<i32> = rx.iter().collect();
+    all.sort();
+    println!("{:?}", all);
+    assert_eq!(all, [0, 1, 10, 11, 20, 21]);
+
+    inner_print!("同步通道");
+    // 容量为 0 时，send 会一直阻塞到消息被接收
+    let (tx, rx) = mpsc::sync_channel(0);
+    let handle = thread::spawn(move || tx.send("同步消息").unwrap());
+    assert_eq!(rx.recv().unwrap(), "同步消息");
+    handle.join().unwrap();
+}
+
+#[outer_print("分发任务")]
+pub fn work_distribution() {
+    let results = distribute((1..=8).collect(), 3, |n| n * n);
+    for (worker, job, result) in &results {
+        println!("worker {} : {}^2 = {}", worker, job, result);
+    }
+    let squares: Vec<u64> = results.iter().map(|&(_, _, r)| r).collect();
+    assert_eq!(squares, [1, 4, 9, 16, 25, 36, 49, 64]);
+}
@@ -0,0 +1,148 @@
+//! # 4.6.3 共享状态：Mutex、RwLock 与 Condvar
+//! 共享内存是消息传递之外的另一种同步方式，多个线程同时访问同一份数据时，需要用锁来保证同一时刻只有一个线程在修改。
+//! + Mutex 互斥锁，同一时刻只允许一个线程访问数据，lock 返回的 MutexGuard 离开作用域时自动解锁
+//! + RwLock 读写锁，同一时刻允许多个读或者一个写，适合读多写少的场景
+//! + Condvar 条件变量，让线程挂起等待某个条件成立，常与 Mutex 配合使用
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread;
+use common::{inner_print, outer_print};
+
+/// 有界阻塞队列：队列满时 push 阻塞，队列空时 pop 阻塞
+pub struct BlockingQueue<T> {
+    items: Mutex<VecDeque<T>>,
+    capacity: usize,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T> BlockingQueue<T> {
+    pub fn new(capacity: usize) -> BlockingQueue<T> {
+        assert!(capacity > 0, "capacity 不能为 0");
+        BlockingQueue { items: Mutex::new(VecDeque::with_capacity(capacity)), capacity, not_empty: Condvar::new(), not_full: Condvar::new() }
+    }
+
+    pub fn push(&self, item: T) {
+        // wait_while 会在条件成立时释放锁并挂起，被唤醒后重新加锁再检查条件，可以避免虚假唤醒
+        let mut items = self.not_full.wait_while(self.items.lock().unwrap(), |items| items.len() == self.capacity).unwrap();
+        items.push_back(item);
+        self.not_empty.notify_one();
+    }
+
+    pub fn pop(&self) -> T {
+        let mut items = self.not_empty.wait_while(self.items.lock().unwrap(), |items| items.is_empty()).unwrap();
+        let item = items.pop_front().unwrap();
+        self.not_full.notify_one();
+        item
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[outer_print("Mutex")]
+pub fn mutex() {
+    let m = Mutex::new(5);
+    {
+        let mut num = m.lock().unwrap();
+        *num = 6;
+        // 锁还没有释放，try_lock 会失败
+        assert!(m.try_lock().is_err());
+    }
+    println!("m = {:?}", m);
+
+    inner_print!("多线程计数");
+    // Rc<T> 不能在线程间安全传递，这里使用 Arc<T>
+    let counter = Arc::new(Mutex::new(0));
+    let handles: Vec<_> = (0..10)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    *counter.lock().unwrap() += 1;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!("Result: {}", *counter.lock().unwrap());
+    assert_eq!(*counter.lock().unwrap(), 1000);
+
+    inner_print!("锁中毒");
+    // 持有锁的线程 panic 后，锁会被标记为中毒，之后的 lock 返回 Err，但依然可以取出数据
+    let poisoned = Arc::new(Mutex::new(0));
+    let p = Arc::clone(&poisoned);
+    let _ = thread::spawn(move || {
+        let _guard = p.lock().unwrap();
+        panic!("持有锁时 panic");
+    })
+    .join();
+    assert!(poisoned.is_poisoned());
+    let value = poisoned.lock().unwrap_or_else(|e| e.into_inner());
+    assert_eq!(*value, 0);
+}
+
+#[outer_print("RwLock")]
+pub fn rw_lock() {
+    let config = RwLock::new(vec![String::from("debug = false")]);
+    {
+        // 多个读锁可以同时存在
+        let r1 = config.read().unwrap();
+        let r2 = config.read().unwrap();
+        assert_eq!(r1.len(), r2.len());
+        // 有读锁时无法获取写锁
+        assert!(config.try_write().is_err());
+    }
+    config.write().unwrap().push(String::from("threads = 4"));
+
+    let config = Arc::new(config);
+    let readers: Vec<_> = (0..4)
+        .map(|i| {
+            let config = Arc::clone(&config);
+            thread::spawn(move || format!("reader {} 看到 {} 条配置", i, config.read().unwrap().len()))
+        })
+        .collect();
+    for reader in readers {
+        let line = reader.join().unwrap();
+        println!("{}", line);
+        assert!(line.ends_with("2 条配置"));
+    }
+}
+
+#[outer_print("Condvar")]
+pub fn condvar() {
+    // 等待另一个线程把 ready 置为 true
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+    let pair2 = Arc::clone(&pair);
+    thread::spawn(move || {
+        let (lock, cvar) = &*pair2;
+        *lock.lock().unwrap() = true;
+        cvar.notify_one();
+    });
+    let (lock, cvar) = &*pair;
+    let ready = cvar.wait_while(lock.lock().unwrap(), |ready| !*ready).unwrap();
+    println!("ready = {}", *ready);
+    drop(ready);
+
+    inner_print!("生产者与消费者");
+    let queue = BlockingQueue::new(2);
+    thread::scope(|s| {
+        s.spawn(|| {
+            for i in 0..10 {
+                queue.push(i);
+            }
+        });
+        let received: Vec<i32> = (0..10).map(|_| queue.pop()).collect();
+        println!("{:?}", received);
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+    });
+    assert!(queue.is_empty());
+}
@@ -0,0 +1,118 @@
+//! # 4.6.4 原子类型与内存顺序
+//! 原子类型是无锁类型，它的操作由 CPU 指令保证不可分割，多个线程同时读写也不会出现数据竞争，性能通常比 Mutex 更好。
+//!
+//! 编译器和 CPU 都可能为了性能调整内存访问的顺序，原子操作需要通过 Ordering 告诉它们哪些重排是不允许的：
+//! + Relaxed 只保证操作本身是原子的，不限制与其它内存访问之间的顺序，适合单纯的计数
+//! + Release 用于写，在它之前的读写不会被重排到它之后
+//! + Acquire 用于读，在它之后的读写不会被重排到它之前；与 Release 配对后，读到 Release 写入值的线程一定能看到 Release 之前的所有写入
+//! + AcqRel 同时具有 Acquire 和 Release 的语义，用于 fetch_add、compare_exchange 这类读改写操作
+//! + SeqCst 在 AcqRel 的基础上，所有线程看到的 SeqCst 操作都是同一个全局顺序，最严格也最慢
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use common::{inner_print, outer_print};
+
+/// 无锁计数器
+#[derive(Debug, Default)]
+pub struct AtomicCounter {
+    value: AtomicU64,
+    max: AtomicU64,
+}
+
+impl AtomicCounter {
+    pub fn new() -> AtomicCounter {
+        AtomicCounter::default()
+    }
+
+    /// 计数只关心最终结果，不需要与其它内存访问同步，Relaxed 就足够了
+    pub fn increment(&self) -> u64 {
+        self.value.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// 用 compare_exchange 循环实现的"记录最大值"，是无锁算法最基本的写法：
+    /// 读取当前值，计算新值，只有当前值没有被其它线程修改时才写入，否则用最新的值重试
+    pub fn record_max(&self, sample: u64) -> u64 {
+        let mut current = self.max.load(Ordering::Relaxed);
+        while sample > current {
+            match self.max.compare_exchange_weak(current, sample, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return sample,
+                Err(actual) => current = actual,
+            }
+        }
+        current
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max.load(Ordering::Relaxed)
+    }
+}
+
+/// 一个线程写入 data 后用 Release 发布 ready，另一个线程用 Acquire 等到 ready 后读取 data
+pub fn publish(value: usize) -> usize {
+    let data = Arc::new(AtomicUsize::new(0));
+    let ready = Arc::new(AtomicBool::new(false));
+    let consumer = {
+        let (data, ready) = (Arc::clone(&data), Arc::clone(&ready));
+        thread::spawn(move || {
+            while !ready.load(Ordering::Acquire) {
+                std::hint::spin_loop();
+            }
+            // Acquire 读到了 true，Release 之前写入的 data 一定可见，这里即使用 Relaxed 读也不会读到 0
+            data.load(Ordering::Relaxed)
+        })
+    };
+    data.store(value, Ordering::Relaxed);
+    ready.store(true, Ordering::Release);
+    consumer.join().unwrap()
+}
+
+#[outer_print("原子类型")]
+pub fn atomic_counter() {
+    let counter = Arc::new(AtomicCounter::new());
+    let handles: Vec<_> = (0..8u64)
+        .map(|t| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    counter.increment();
+                    counter.record_max(t * 1000 + i);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!("count = {}, max = {}", counter.get(), counter.max());
+    assert_eq!(counter.get(), 8000);
+    assert_eq!(counter.max(), 7999);
+
+    inner_print!("非原子的 += 会丢失更新");
+    // load + store 是两个独立的原子操作，两次之间可能被其它线程插入修改，这也是 Mutex 或 fetch_add 存在的原因
+    let racy = Arc::new(AtomicU64::new(0));
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    let v = racy.load(Ordering::Relaxed);
+                    racy.store(v + 1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    // 结果不确定，多核机器上通常小于 4000
+    println!("load + store 的结果 = {}", racy.load(Ordering::Relaxed));
+    assert!(racy.load(Ordering::Relaxed) <= 4000);
+}
+
+#[outer_print("内存顺序")]
+pub fn memory_ordering() {
+    let value = publish(42);
+    println!("consumer 读到 {}", value);
+    assert_eq!(value, 42);
+}
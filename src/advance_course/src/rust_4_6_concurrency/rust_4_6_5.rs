@@ -0,0 +1,125 @@
+//! # 4.6.5 线程池
+//! 为每个任务创建一个线程的开销很大，线程数量也不受控制。线程池预先创建固定数量的线程，通过通道把任务分发给它们。
+//!
+//! 优雅关闭：线程池被 drop 时先关闭任务通道，worker 处理完通道中剩余的任务后，recv 返回 Err，循环退出，最后逐个 join。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use common::outer_print;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct Worker {
+    id: usize,
+    handle: JoinHandle<()>,
+}
+
+/// 固定大小的线程池
+pub struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: Option<Sender<Job>>,
+    completed: Arc<AtomicUsize>,
+}
+
+impl ThreadPool {
+    /// 创建一个包含 size 个线程的线程池，size 为 0 时 panic
+    pub fn new(size: usize) -> ThreadPool {
+        assert!(size > 0, "线程池大小不能为 0");
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let completed = Arc::new(AtomicUsize::new(0));
+        let workers = (0..size)
+            .map(|id| {
+                let receiver = Arc::clone(&receiver);
+                let completed = Arc::clone(&completed);
+                let handle = thread::Builder::new()
+                    .name(format!("pool-worker-{}", id))
+                    .spawn(move || loop {
+                        // 不能写成 while let Ok(job) = receiver.lock().unwrap().recv()，
+                        // 那样 MutexGuard 会一直存活到循环体结束，任务执行期间其它 worker 都拿不到锁
+                        let job = receiver.lock().unwrap().recv();
+                        match job {
+                            Ok(job) => {
+                                job();
+                                completed.fetch_add(1, Ordering::Release);
+                            }
+                            Err(_) => break,
+                        }
+                    })
+                    .expect("创建线程失败");
+                Worker { id, handle }
+            })
+            .collect();
+        ThreadPool { workers, sender: Some(sender), completed }
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// 提交一个任务
+    pub fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender.as_ref().unwrap().send(Box::new(f)).expect("线程池已经关闭");
+    }
+
+    /// 已经执行完的任务数
+    pub fn completed(&self) -> usize {
+        self.completed.load(Ordering::Acquire)
+    }
+
+    /// 等待所有已提交的任务执行完毕后关闭线程池，返回执行完的任务数
+    pub fn shutdown(mut self) -> usize {
+        self.join_all();
+        self.completed()
+    }
+
+    fn join_all(&mut self) {
+        // drop 发送者，通道关闭，worker 取完剩余任务后退出
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            if worker.handle.join().is_err() {
+                eprintln!("worker {} panic 了", worker.id);
+            }
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.join_all();
+    }
+}
+
+#[outer_print("线程池")]
+pub fn thread_pool() {
+    let pool = ThreadPool::new(4);
+    let (tx, rx) = mpsc::channel();
+    for i in 0..8u64 {
+        let tx = tx.clone();
+        pool.execute(move || {
+            let name = thread::current().name().unwrap_or_default().to_string();
+            tx.send((i, i * i, name)).unwrap();
+        });
+    }
+    drop(tx);
+    let mut results: Vec<_> = rx.iter().collect();
+    results.sort();
+    for (i, square, name) in &results {
+        println!("{} 计算 {}^2 = {}", name, i, square);
+    }
+    assert_eq!(results.len(), 8);
+
+    // 关闭时会等待已经提交的任务全部完成
+    let slow = ThreadPool::new(2);
+    for _ in 0..6 {
+        slow.execute(|| thread::sleep(std::time::Duration::from_millis(5)));
+    }
+    let done = slow.shutdown();
+    println!("关闭前完成了 {} 个任务", done);
+    assert_eq!(done, 6);
+}
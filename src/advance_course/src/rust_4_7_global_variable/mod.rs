@@ -0,0 +1,3 @@
+pub mod rust_4_7_1;
+pub mod rust_4_7_2;
+pub mod rust_4_7_3;
@@ -0,0 +1,98 @@
+//! # 4.7.1 编译期初始化的全局变量
+//! 全局变量的生命周期是 'static，有两种：
+//! + 常量 const：没有固定的内存地址，编译器会把值内联到每一个使用它的地方
+//! + 静态变量 static：在程序中只有一个实例，有固定的内存地址，可以声明为 static mut
+//!
+//! 两者的初始值都必须在编译期确定，只能使用字面量、const fn 和其它常量。
+//! static mut 的读写都需要 unsafe，因为编译器无法阻止多个线程同时修改它；
+//! 需要修改的全局计数器应该使用原子类型，static 原子变量不需要 mut，也不需要 unsafe。
+
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use common::{inner_print, outer_print};
+
+/// 常量会被内联到使用的地方
+pub const MAX_LESSONS: usize = 128;
+
+/// 静态变量只有一份
+pub static COURSE_NAME: &str = "Rust 语言圣经";
+
+/// 演示用的 static mut，只能在 [bump_unsafe_counter] 中访问
+static mut UNSAFE_COUNTER: u32 = 0;
+
+/// static mut 本身不阻止并发访问，课程可能同时在多个测试线程中执行，所有访问都要先拿到这把锁
+static UNSAFE_COUNTER_LOCK: Mutex<()> = Mutex::new(());
+
+/// 把 UNSAFE_COUNTER 加一，返回直接读取和通过原始指针读取的值
+fn bump_unsafe_counter() -> (u32, u32) {
+    let _guard = UNSAFE_COUNTER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    // SAFETY: 持有 UNSAFE_COUNTER_LOCK 期间没有其它线程访问 UNSAFE_COUNTER，其它地方也不会访问它
+    unsafe {
+        UNSAFE_COUNTER += 1;
+        // 对 static mut 取引用同样是未定义行为的来源，2024 版本中直接报错，需要时使用原始指针
+        // let r = &UNSAFE_COUNTER;
+        // error: creating a shared reference to mutable static
+        let raw = &raw const UNSAFE_COUNTER;
+        (UNSAFE_COUNTER, raw.read())
+    }
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 全局唯一的递增 id，多个线程同时调用也不会重复
+pub fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[outer_print("const 与 static")]
+pub fn const_and_static() {
+    println!("{} 最多 {} 节课", COURSE_NAME, MAX_LESSONS);
+    // 每次取 static 的地址都是同一个
+    let first: *const &str = &COURSE_NAME;
+    let second: *const &str = &COURSE_NAME;
+    println!("COURSE_NAME 的地址: {:p} {:p}", first, second);
+    assert!(ptr::eq(first, second));
+
+    inner_print!("初始值必须在编译期确定");
+    // const fn 可以在编译期执行
+    const fn square(n: usize) -> usize {
+        n * n
+    }
+    const GRID: usize = square(MAX_LESSONS);
+    static EMPTY: Vec<u8> = Vec::new();
+    println!("GRID = {}，EMPTY = {:?}", GRID, EMPTY);
+    // String::from 不是 const fn，只能在运行时调用，需要用 4.7.2 中的 LazyLock
+    // static NAME: String = String::from("rust");
+    // error[E0015]: cannot call non-const associated function `<String as From<&str>>::from` in statics
+}
+
+#[outer_print("static mut")]
+/// 读写 static mut 都需要 unsafe，编译器不检查是否有其它线程同时访问，这要由我们自己保证。
+/// 这里把所有访问都放进 bump_unsafe_counter，并用一把锁保护，对外的函数仍然是安全的。
+pub fn static_mut() {
+    let (value, through_raw) = bump_unsafe_counter();
+    println!("UNSAFE_COUNTER = {}", value);
+    println!("通过原始指针读取: {}", through_raw);
+    assert_eq!(value, through_raw);
+}
+
+#[outer_print("static 原子变量")]
+pub fn static_atomic() {
+    let handles: Vec<_> = (0..4).map(|_| thread::spawn(|| (0..100).map(|_| next_id()).collect::<Vec<_>>())).collect();
+    let mut ids: Vec<u64> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    println!("4 个线程共取得 {} 个不重复的 id", ids.len());
+    assert_eq!(ids.len(), 400);
+
+    inner_print!("课程运行次数");
+    // outer_print 展开后的代码在每节课开始时把 lesson_runtime 中的 static 原子变量加一
+    println!(
+        "已经开始 {} 节课，结束 {} 节课，当前这节课还没有结束",
+        lesson_runtime::section::started(),
+        lesson_runtime::section::finished()
+    );
+    assert!(lesson_runtime::section::started() > lesson_runtime::section::finished());
+}
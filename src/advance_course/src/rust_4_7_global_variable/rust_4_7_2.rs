@@ -0,0 +1,88 @@
+//! # 4.7.2 运行期初始化：OnceLock 与 LazyLock
+//! 很多全局值只能在运行时得到：读取环境变量、解析配置文件、分配堆内存。标准库提供了两种只初始化一次的容器：
+//! + OnceLock<T>：一开始是空的，第一次调用 set 或者 get_or_init 时写入，之后只能读取
+//! + LazyLock<T>：创建时就给出初始化函数，第一次解引用时才调用它
+//!
+//! 多个线程同时初始化时，只有一个线程会执行初始化函数，其它线程会等待它完成，之后的读取不需要加锁。
+//!
+//! 课程的全局配置 lesson_runtime::config 就是用 OnceLock 实现的：在第一节课开始之前可以用 init 指定配置，
+//! 否则第一次输出标题时从环境变量中读取，outer_print 和 inner_print 展开后的代码都会读取它。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, OnceLock};
+use std::thread;
+use common::{inner_print, outer_print};
+use lesson_runtime::config::{self, Config, Sink, Verbosity};
+
+static KEYWORD_INITS: AtomicUsize = AtomicUsize::new(0);
+
+static KEYWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    KEYWORD_INITS.fetch_add(1, Ordering::Relaxed);
+    "as break const continue crate else enum extern false fn for if impl in let loop match mod move mut pub ref return self Self static struct super trait true type unsafe use where while async await dyn"
+        .split_whitespace()
+        .collect()
+});
+
+/// 是否是 Rust 的关键字，关键字表在第一次调用时才创建
+pub fn is_keyword(word: &str) -> bool {
+    KEYWORDS.contains(word)
+}
+
+/// 关键字表被创建的次数，无论调用多少次 is_keyword 都不会超过 1
+pub fn keyword_inits() -> usize {
+    KEYWORD_INITS.load(Ordering::Relaxed)
+}
+
+#[outer_print("LazyLock")]
+pub fn lazy_lock() {
+    for word in ["fn", "main", "async", "println"] {
+        println!("{:>8} 是关键字: {}", word, is_keyword(word));
+    }
+    println!("关键字表创建了 {} 次", keyword_inits());
+    assert_eq!(keyword_inits(), 1);
+
+    inner_print!("多个线程同时初始化");
+    static ROOT: LazyLock<String> = LazyLock::new(|| {
+        println!("{:?} 执行了初始化", thread::current().id());
+        "/".repeat(3)
+    });
+    let lengths: Vec<usize> = thread::scope(|s| {
+        let handles: Vec<_> = (0..4).map(|_| s.spawn(|| ROOT.len())).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(lengths, [3; 4]);
+}
+
+#[outer_print("OnceLock")]
+pub fn once_lock() {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    println!("初始化之前: {:?}", TOKEN.get());
+    let token = TOKEN.get_or_init(|| format!("token-{}", std::process::id()));
+    println!("初始化之后: {}", token);
+    // 已经有值了，set 会失败并把传入的值还回来
+    assert_eq!(TOKEN.set("other".to_string()), Err("other".to_string()));
+    assert!(TOKEN.get_or_init(|| unreachable!()).starts_with("token-"));
+}
+
+#[outer_print("课程的全局配置")]
+pub fn lesson_config() {
+    // 这节课的 [start] 已经输出，全局配置在那时就初始化好了
+    assert!(config::is_initialized());
+    let current = config::get();
+    println!("当前配置: {:?}，使用颜色: {}", current, current.use_color());
+    // 再次设置会失败，整个进程中的配置保持不变
+    let verbose = Config { verbosity: Verbosity::Verbose, ..Config::default() };
+    assert!(config::init(verbose.clone()).is_err());
+    assert_eq!(config::get(), current);
+
+    inner_print!("从环境变量读取");
+    let vars = HashMap::from([("LESSON_SINK", "stderr"), ("LESSON_VERBOSITY", "Verbose")]);
+    let parsed = Config::from_lookup(|key| vars.get(key).map(|v| v.to_string()));
+    println!("{:?}", parsed);
+    assert_eq!(parsed, Ok(Config { sink: Sink::Stderr, ..verbose }));
+    match Config::from_lookup(|key| (key == "LESSON_COLOR").then(|| "rainbow".to_string())) {
+        Ok(config) => println!("{:?}", config),
+        Err(e) => println!("{}", e),
+    }
+}
@@ -0,0 +1,73 @@
+//! # 4.7.3 线程局部变量
+//! thread_local! 声明的变量在每个线程中都有一份独立的实例，线程第一次访问时初始化，线程结束时销毁。
+//! 因为不会被其它线程访问，线程局部变量可以使用 Cell、RefCell 这类非线程安全的类型，读写时不需要加锁。
+//!
+//! 线程局部变量只能通过 with 在闭包中访问，不能拿到 'static 引用；Cell 和 RefCell 还提供了 get、set、take、replace 等简便方法。
+//!
+//! lesson_runtime 用线程局部的栈记录当前正在执行的课程：outer_print 在课程开始时入栈，结束时出栈，
+//! inner_print 记录当前的小节。多个线程同时执行课程时，各自的栈互不影响。
+
+use std::cell::Cell;
+use std::thread;
+use common::{inner_print, outer_print};
+use lesson_runtime::section;
+
+thread_local! {
+    static COUNTER: Cell<usize> = const { Cell::new(0) };
+}
+
+/// 在当前线程中把计数器加一，返回加一之后的值
+pub fn bump() -> usize {
+    COUNTER.set(COUNTER.get() + 1);
+    COUNTER.get()
+}
+
+/// 启动 threads 个线程，每个线程调用 bump 的次数依次为 1、2、3……，返回每个线程最后的计数
+pub fn per_thread_counts(threads: usize) -> Vec<usize> {
+    thread::scope(|s| {
+        let handles: Vec<_> = (1..=threads)
+            .map(|n| {
+                s.spawn(move || {
+                    (0..n).for_each(|_| {
+                        bump();
+                    });
+                    COUNTER.get()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+#[outer_print("thread_local!")]
+pub fn thread_local_intro() {
+    let counts = per_thread_counts(4);
+    println!("每个线程的计数: {:?}", counts);
+    assert_eq!(counts, [1, 2, 3, 4]);
+    // 子线程的计数不影响当前线程
+    let before = COUNTER.get();
+    assert_eq!(bump(), before + 1);
+}
+
+#[outer_print("嵌套的课程")]
+/// 返回课程中看到的小节路径
+pub fn nested_section() -> Vec<String> {
+    inner_print!("内层小节");
+    let path = section::path();
+    println!("嵌套深度 {}，路径: {}", section::depth(), path.join(" > "));
+    path
+}
+
+#[outer_print("课程栈")]
+pub fn section_stack() {
+    inner_print!("外层小节");
+    let inner = nested_section();
+    assert!(inner.ends_with(&["课程栈".to_string(), "外层小节".to_string(), "嵌套的课程".to_string(), "内层小节".to_string()]));
+    // 内层课程结束后出栈
+    assert_eq!(section::path().last().map(String::as_str), Some("外层小节"));
+
+    inner_print!("其它线程");
+    let (depth, path) = thread::spawn(|| (section::depth(), section::path())).join().unwrap();
+    println!("新线程中的深度 {}，路径 {:?}", depth, path);
+    assert_eq!(depth, 0);
+}
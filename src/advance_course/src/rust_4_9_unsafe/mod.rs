@@ -0,0 +1,5 @@
+pub mod rust_4_9_1;
+pub mod rust_4_9_2;
+pub mod rust_4_9_3;
+pub mod rust_4_9_4;
+pub mod rust_4_9_5;
@@ -0,0 +1,114 @@
+//! # 4.9.1 解引用裸指针
+//! 裸指针(raw pointer，又称原生指针) 在功能上跟引用类似，同时它也需要显式地注明可变性。但是又和引用有所不同，裸指针长这样: *const T 和 *mut T，它们分别代表了不可变和可变。
+//!
+//! 裸指针：
+//! + 可以绕过 Rust 的借用规则，可以同时拥有一个数据的可变、不可变指针，甚至还能拥有多个可变的指针
+//! + 并不能保证指向合法的内存
+//! + 可以是 null
+//! + 没有实现任何自动的回收 (drop)
+//!
+//! 创建裸指针是安全的行为，而解引用裸指针才是不安全的行为。本节的每个 unsafe 操作都被包在一个安全的函数中，调用者无需关心内部细节。
+
+use std::ptr;
+use common::{inner_print, outer_print};
+
+/// 用指针运算遍历切片并求和，溢出时回绕
+pub fn sum_by_pointer(values: &[i32]) -> i32 {
+    let mut sum = 0i32;
+    let start = values.as_ptr();
+    for i in 0..values.len() {
+        // SAFETY: i < len，start.add(i) 始终落在切片内部
+        sum = sum.wrapping_add(unsafe { *start.add(i) });
+    }
+    sum
+}
+
+/// 用头尾两个可变裸指针原地反转切片
+pub fn reverse<T>(values: &mut [T]) {
+    let len = values.len();
+    if len < 2 {
+        return;
+    }
+    let base = values.as_mut_ptr();
+    // SAFETY: front < back 时两者都在切片内且互不重叠，ptr::swap 可以交换
+    unsafe {
+        let mut front = base;
+        let mut back = base.add(len - 1);
+        while front < back {
+            ptr::swap(front, back);
+            front = front.add(1);
+            back = back.sub(1);
+        }
+    }
+}
+
+/// 两个指针之间相隔的元素个数，要求两者来自同一个切片，否则返回 None
+pub fn distance<T>(values: &[T], a: &T, b: &T) -> Option<isize> {
+    let range = values.as_ptr_range();
+    let (a, b) = (a as *const T, b as *const T);
+    if !range.contains(&a) || !range.contains(&b) {
+        return None;
+    }
+    // SAFETY: 两个指针都指向同一个切片内部
+    Some(unsafe { b.offset_from(a) })
+}
+
+#[outer_print("创建裸指针")]
+/// 基于引用创建裸指针是安全的，as 转换或者 &raw const / &raw mut 语法都可以。
+/// 也可以基于一个内存地址创建裸指针，但这样的指针大概率是无效的，解引用它是未定义行为。
+pub fn create_raw_pointer() {
+    let mut num = 5;
+    let r1 = &num as *const i32;
+    let r2 = &raw mut num;
+    // 同时拥有不可变和可变的裸指针，编译器不会报错
+    println!("r1 = {:p}, r2 = {:p}", r1, r2);
+    assert_eq!(r1, r2 as *const i32);
+
+    inner_print!("基于内存地址创建裸指针");
+    let address = 0x012345usize;
+    let r = address as *const i32;
+    // 只要不解引用，就不会出问题
+    println!("r = {:p}, is_null = {}", r, r.is_null());
+    assert!(ptr::null::<i32>().is_null());
+}
+
+#[outer_print("解引用裸指针")]
+/// 使用 * 可以对裸指针进行解引用，由于该指针的内存安全性并没有任何保证，因此需要使用 unsafe 来包裹解引用的逻辑。
+pub fn deref_raw_pointer() {
+    let mut num = 5;
+    // r1 从 r2 转换而来，两者指向同一个位置：如果各自从 num 创建，创建 r2 时 r1 就失效了，之后再通过 r1 读取是未定义行为(Miri 会报告)
+    let r2 = &raw mut num;
+    let r1 = r2 as *const i32;
+    unsafe {
+        *r2 += 1;
+        println!("r1 is: {}", *r1);
+        assert_eq!(*r1, 6);
+    }
+
+    inner_print!("Box::into_raw 与 Box::from_raw");
+    // Box 交出所有权后得到一个裸指针，之后必须且只能通过 from_raw 收回一次，否则就是内存泄漏或者重复释放
+    let raw = Box::into_raw(Box::new(String::from("hello")));
+    unsafe {
+        (*raw).push_str(", world");
+        let boxed = Box::from_raw(raw);
+        assert_eq!(*boxed, "hello, world");
+    }
+}
+
+#[outer_print("指针运算")]
+/// 裸指针的 add、sub、offset 以元素为单位移动，而不是字节；offset_from 计算两个指针之间的元素个数。
+/// 这些方法要求结果仍在同一块分配的内存中(或者恰好指向末尾之后一位)，否则就是未定义行为；
+/// 只计算地址而不解引用时，可以使用 wrapping_add 等方法。
+pub fn pointer_arithmetic() {
+    let values = [1, 2, 3, 4, 5];
+    println!("sum = {}", sum_by_pointer(&values));
+    assert_eq!(distance(&values, &values[1], &values[4]), Some(3));
+
+    let mut words = ["a", "b", "c"];
+    reverse(&mut words);
+    assert_eq!(words, ["c", "b", "a"]);
+    println!("{:?}", words);
+
+    let base = values.as_ptr();
+    println!("相邻元素的地址相差 {} 字节", base.wrapping_add(1) as usize - base as usize);
+}
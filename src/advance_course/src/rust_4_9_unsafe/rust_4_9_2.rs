@@ -0,0 +1,80 @@
+//! # 4.9.2 调用 unsafe 函数或方法
+//! unsafe 函数从外表上来看跟普通函数并无区别，唯一的区别就是它需要使用 unsafe fn 来进行定义。
+//! 这种定义方式是为了告诉调用者：当调用此函数时，你需要注意它的相关需求，因为 Rust 无法担保调用者在使用该函数时能满足它所需的一切需求。
+//! 按照惯例，unsafe 函数的文档中要有一个 # Safety 小节，写明调用者必须满足的条件。
+//!
+//! 一个函数包含了 unsafe 代码不代表我们需要将整个函数都定义为 unsafe fn，用安全的函数把 unsafe 代码包起来是非常常见的抽象方式。
+
+use std::slice;
+use common::{inner_print, outer_print};
+
+/// 标准库 split_at_mut 的重新实现：把一个可变切片分成两个互不重叠的可变切片
+///
+/// # Panics
+/// mid > len 时 panic
+pub fn split_at_mut<T>(values: &mut [T], mid: usize) -> (&mut [T], &mut [T]) {
+    let len = values.len();
+    assert!(mid <= len, "mid {} 超出了切片长度 {}", mid, len);
+    let ptr = values.as_mut_ptr();
+    // SAFETY: [0, mid) 与 [mid, len) 都在原切片内且互不重叠，生命周期与 values 相同
+    unsafe {
+        (
+            slice::from_raw_parts_mut(ptr, mid),
+            slice::from_raw_parts_mut(ptr.add(mid), len - mid),
+        )
+    }
+}
+
+/// 不检查下标地读取元素
+///
+/// # Safety
+/// 调用者必须保证 index < values.len()
+pub unsafe fn get_unchecked<T: Copy>(values: &[T], index: usize) -> T {
+    *values.as_ptr().add(index)
+}
+
+/// get_unchecked 的安全版本：先检查下标，再调用 unsafe 函数
+pub fn get_checked<T: Copy>(values: &[T], index: usize) -> Option<T> {
+    if index < values.len() {
+        // SAFETY: 上面已经检查过下标
+        Some(unsafe { get_unchecked(values, index) })
+    } else {
+        None
+    }
+}
+
+#[outer_print("unsafe 函数")]
+/// 调用 unsafe 函数必须放在 unsafe 块中，这相当于调用者向编译器保证：我已经阅读了文档，满足了它的要求。
+pub fn unsafe_function() {
+    let values = [10, 20, 30];
+    // 下面的代码无法编译：
+    // get_unchecked(&values, 1);
+    // error[E0133]: call to unsafe function `get_unchecked` is unsafe and requires unsafe function or block
+    let v = unsafe { get_unchecked(&values, 1) };
+    assert_eq!(v, 20);
+    assert_eq!(get_checked(&values, 3), None);
+    println!("get_checked(&values, 2) = {:?}", get_checked(&values, 2));
+}
+
+#[outer_print("用安全抽象包裹 unsafe 代码")]
+/// 借用检查器无法理解我们要借用同一个切片的两个不同部分，因此下面的实现无法通过编译：
+/// ```compile_fail
+/// fn split_at_mut(values: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+///     let len = values.len();
+///     (&mut values[..mid], &mut values[mid..])
+/// }
+/// ```
+/// 使用裸指针和 slice::from_raw_parts_mut 就可以绕过借用检查，由我们自己保证两个切片不重叠。
+pub fn safe_abstraction() {
+    let mut values = [1, 2, 3, 4, 5, 6];
+    let (left, right) = split_at_mut(&mut values, 3);
+    left[0] = 100;
+    right[0] = 400;
+    left.swap_with_slice(right);
+    assert_eq!(values, [400, 5, 6, 100, 2, 3]);
+    println!("{:?}", values);
+
+    inner_print!("边界");
+    let (left, right) = split_at_mut(&mut values, 6);
+    assert_eq!((left.len(), right.len()), (6, 0));
+}
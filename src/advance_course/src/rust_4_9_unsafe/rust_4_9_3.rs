@@ -0,0 +1,89 @@
+//! # 4.9.3 FFI
+//! FFI(Foreign Function Interface)可以用来与其它语言进行交互，但是并不是所有语言都这么称呼，例如 Java 称之为 JNI(Java Native Interface)。
+//! 在 Rust 中调用 C 函数，需要先在 extern "C" 块中声明函数签名，"C" 定义了外部函数所使用的应用二进制接口 ABI。
+//! 编译器无法检查外部函数的实现，因此调用它们总是 unsafe 的。
+//!
+//! 本节通过 libc 包调用 C 标准库中的函数，libc 包已经为我们写好了这些 extern 声明。
+
+use std::ffi::{c_int, c_void, CStr, CString};
+use common::{inner_print, outer_print};
+
+/// C 的 strlen：统计 '\0' 之前的字节数，CStr 保证了字符串以 '\0' 结尾。
+/// 实际开发中直接用 CStr::count_bytes 即可，这里是为了演示调用 C 函数
+#[allow(clippy::strlen_on_c_strings)]
+pub fn c_strlen(s: &CStr) -> usize {
+    // SAFETY: CStr 指向一个以 '\0' 结尾的有效字符串
+    unsafe { libc::strlen(s.as_ptr()) }
+}
+
+/// C 的 abs。C 标准规定 abs(INT_MIN) 是未定义行为，安全的包装必须把这种输入挡在外面
+pub fn c_abs(n: i32) -> Option<i32> {
+    if n == i32::MIN {
+        None
+    } else {
+        // SAFETY: n != INT_MIN，结果可以用 int 表示
+        Some(unsafe { libc::abs(n) })
+    }
+}
+
+/// 传给 qsort 的比较函数：用 extern "C" 定义的 Rust 函数可以被 C 代码回调
+pub extern "C" fn compare_i32(a: *const c_void, b: *const c_void) -> c_int {
+    // SAFETY: qsort 只会传入指向数组元素的指针，元素类型是 i32
+    let (a, b) = unsafe { (*(a as *const i32), *(b as *const i32)) };
+    a.cmp(&b) as c_int
+}
+
+/// 调用 C 的 qsort 对切片排序
+pub fn c_sort(values: &mut [i32]) {
+    if values.is_empty() {
+        return;
+    }
+    // SAFETY: 指针、元素个数和元素大小都来自同一个切片，比较函数与元素类型一致
+    unsafe {
+        libc::qsort(
+            values.as_mut_ptr() as *mut c_void,
+            values.len(),
+            size_of::<i32>(),
+            Some(compare_i32),
+        );
+    }
+}
+
+/// 当前进程的 id，与 std::process::id() 相同
+pub fn c_getpid() -> u32 {
+    // SAFETY: getpid 没有参数，也不会失败
+    unsafe { libc::getpid() as u32 }
+}
+
+#[outer_print("调用 C 标准库")]
+/// Rust 字符串不以 '\0' 结尾，中间还可能包含 '\0'，传给 C 之前需要转换成 CString；
+/// 从 C 拿回来的 *const c_char 则用 CStr 包装。
+pub fn call_libc() {
+    let s = CString::new("你好, FFI").unwrap();
+    println!("strlen = {}", c_strlen(&s));
+    assert_eq!(c_strlen(&s), "你好, FFI".len());
+    assert!(CString::new("a\0b").is_err());
+
+    println!("abs(-3) = {:?}, abs(i32::MIN) = {:?}", c_abs(-3), c_abs(i32::MIN));
+    println!("getpid = {}", c_getpid());
+
+    inner_print!("C 回调 Rust 函数");
+    let mut values = [5, -1, 3, 0, 2];
+    c_sort(&mut values);
+    println!("{:?}", values);
+    assert_eq!(values, [-1, 0, 2, 3, 5]);
+}
+
+/// 供 C 代码调用的 Rust 函数：#[unsafe(no_mangle)] 禁止编译器修改函数名，C 代码中声明为 int32_t rust_course_add(int32_t, int32_t) 即可调用
+#[unsafe(no_mangle)]
+pub extern "C" fn rust_course_add(a: i32, b: i32) -> i32 {
+    a.wrapping_add(b)
+}
+
+#[outer_print("在其它语言中调用 Rust 函数")]
+/// 使用 extern "C" 创建的函数可以被其它语言调用；编译成 cdylib 或 staticlib 后，C 代码就可以链接它。
+pub fn export_to_c() {
+    // 函数指针的类型与 C 中的 int32_t (*)(int32_t, int32_t) 一致
+    let f: extern "C" fn(i32, i32) -> i32 = rust_course_add;
+    println!("rust_course_add(1, 2) = {}", f(1, 2));
+}
@@ -0,0 +1,147 @@
+//! # 4.9.4 访问可变静态变量与实现 unsafe 特征
+//! 全局可变静态变量 static mut 可以被任何线程在任何时候读写，编译器无法保证不存在数据竞争，因此读写它都需要 unsafe。
+//! 一个特征中只要有一个方法是 unsafe 的，或者实现者需要保证编译器无法检查的性质，这个特征就应该是 unsafe trait，实现它时也要用 unsafe impl。
+//! Send 和 Sync 就是标准库中最常见的 unsafe 特征。
+
+use std::mem;
+use std::sync::Once;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use common::{inner_print, outer_print};
+
+static mut POPCOUNT_TABLE: [u8; 256] = [0; 256];
+static POPCOUNT_INIT: Once = Once::new();
+
+/// 每个字节中 1 的个数。表格保存在 static mut 中，只在第一次调用时由 Once 初始化一次，之后只读
+pub fn popcount_table() -> &'static [u8; 256] {
+    POPCOUNT_INIT.call_once(|| {
+        let table = &raw mut POPCOUNT_TABLE;
+        for i in 1..256 {
+            // SAFETY: call_once 保证只有一个线程会执行这里，其它线程会等待它完成
+            unsafe { (*table)[i] = (i & 1) as u8 + (*table)[i / 2] };
+        }
+    });
+    let table = &raw const POPCOUNT_TABLE;
+    // SAFETY: 初始化已经完成，之后不会再有写入
+    unsafe { &*table }
+}
+
+/// 查表统计 bytes 中 1 的个数
+pub fn count_ones(bytes: &[u8]) -> u32 {
+    let table = popcount_table();
+    bytes.iter().map(|&b| table[b as usize] as u32).sum()
+}
+
+/// 线程安全的计数器不需要 static mut，原子类型的 static 就可以在多个线程中修改
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn record_call() -> usize {
+    CALLS.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+#[outer_print("访问或修改可变静态变量")]
+/// 静态变量中的值必须是 Sized 且在编译期确定，static 变量在程序中只有一个实例，有固定的内存地址。
+/// 对 static mut 的引用很容易造成数据竞争，从 2024 版本开始，直接创建 static mut 的引用会报错，应当使用 &raw const / &raw mut 获得裸指针。
+/// 大多数时候，原子类型、Mutex、OnceLock 都是比 static mut 更好的选择。
+pub fn static_mut() {
+    let table = popcount_table();
+    assert_eq!((table[0], table[0xff], table[0b1011]), (0, 8, 3));
+    println!("count_ones(\"hello\") = {}", count_ones(b"hello"));
+
+    inner_print!("用原子类型代替 static mut");
+    let handles: Vec<_> = (0..4).map(|_| thread::spawn(record_call)).collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!("record_call 已被调用 {} 次", CALLS.load(Ordering::Relaxed));
+}
+
+/// 可以在线程间传递的裸指针。裸指针没有实现 Send，因为编译器不知道它指向的数据能否被其它线程访问，
+/// 这里由我们来保证：每个线程只访问互不重叠的区域
+struct SendPtr<T>(*mut T);
+
+// derive 会要求 T: Copy，而指针本身总是可以复制的，所以手动实现
+impl<T> Clone for SendPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SendPtr<T> {}
+
+// SAFETY: 只在 parallel_fill 中使用，各线程写入的下标互不重叠，且所有线程都在 scope 结束前退出
+unsafe impl<T: Send> Send for SendPtr<T> {}
+
+impl<T> SendPtr<T> {
+    /// 通过方法取出指针，这样闭包捕获的是整个 SendPtr 而不是其中的裸指针字段
+    fn get(self) -> *mut T {
+        self.0
+    }
+}
+
+/// 用多个线程并行地填充切片，第 i 个元素的值为 f(i)
+pub fn parallel_fill<T: Send>(values: &mut [T], threads: usize, f: impl Fn(usize) -> T + Sync) {
+    let len = values.len();
+    let threads = threads.clamp(1, len.max(1));
+    let chunk = len.div_ceil(threads);
+    let base = SendPtr(values.as_mut_ptr());
+    let f = &f;
+    thread::scope(|s| {
+        for start in (0..len).step_by(chunk.max(1)) {
+            let end = (start + chunk).min(len);
+            s.spawn(move || {
+                for i in start..end {
+                    // SAFETY: i < len，且每个线程负责的 [start, end) 互不重叠；
+                    // 原来的值需要被正确 drop，因此用赋值而不是 ptr::write
+                    unsafe { *base.get().add(i) = f(i) };
+                }
+            });
+        }
+    });
+}
+
+/// 全部字节为 0 时是合法值的类型。实现者必须保证这一点，编译器无法检查，因此这是一个 unsafe 特征
+///
+/// # Safety
+/// 实现该特征的类型，其所有字节为 0 的位模式必须是一个有效值，例如 bool、整数，而引用、Box、NonZeroU8 都不满足
+pub unsafe trait Zeroable: Sized {
+    fn zeroed() -> Self {
+        // SAFETY: 由实现者保证全 0 是有效值
+        unsafe { mem::zeroed() }
+    }
+}
+
+macro_rules! impl_zeroable {
+    ($($t:ty),*) => {$(
+        // SAFETY: 原生数值类型和 bool 的全 0 位模式都是有效值
+        unsafe impl Zeroable for $t {}
+    )*};
+}
+
+impl_zeroable!(bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+// SAFETY: 数组的每个元素都是 Zeroable，整个数组的全 0 位模式也是有效值
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+
+#[outer_print("实现 unsafe 特征")]
+/// 之所以会有 unsafe 的特征，是因为该特征至少有一个方法包含有编译器无法验证的内容，或者实现者需要满足编译器无法检查的约定。
+/// unsafe impl 相当于实现者向编译器承诺：这些约定我都满足了。
+/// 自动实现的 Send 和 Sync 是最典型的例子：裸指针没有实现它们，包含裸指针的结构体也就没有，需要时可以手动 unsafe impl。
+pub fn unsafe_trait() {
+    let mut squares = vec![0u64; 10];
+    parallel_fill(&mut squares, 3, |i| (i * i) as u64);
+    println!("{:?}", squares);
+    assert_eq!(squares[9], 81);
+
+    let mut names = vec![String::new(); 4];
+    parallel_fill(&mut names, 2, |i| format!("#{}", i));
+    assert_eq!(names, ["#0", "#1", "#2", "#3"]);
+
+    inner_print!("自定义 unsafe 特征");
+    let zero: [f64; 3] = Zeroable::zeroed();
+    assert_eq!(zero, [0.0; 3]);
+    assert!(!bool::zeroed());
+    // 下面的代码无法编译，因为 String 没有实现 Zeroable：全 0 的 String 包含一个空指针
+    // let s: String = Zeroable::zeroed();
+    println!("{:?}", zero);
+}
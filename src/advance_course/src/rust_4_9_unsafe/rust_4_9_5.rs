@@ -1,2 +1,316 @@
+//! # 4.9.5 内联汇编
+//! Rust 提供了 asm! 宏，可以让大家在 Rust 代码中嵌入汇编代码，对于一些极致高性能或者底层的场景还是非常有用的，例如操作系统内核开发。
+//! 除非你确实需要，否则不要使用内联汇编：它必须写在 unsafe 块中，编译器无法检查汇编的正确性，同时也失去了跨平台的能力。
+//!
+//! 本节的汇编都是 x86_64 的 Intel 语法(asm! 的默认语法)，每个函数都有一个用 cfg 选出的纯 Rust 版本，在其它架构上编译时会使用它。
+//! 测试会把汇编版本的结果与纯 Rust 或标准库的 core::arch 实现相互对照。
 
+#[cfg(target_arch = "x86_64")]
+use std::arch::asm;
+use common::{inner_print, outer_print};
 
+/// 当前架构是否会执行本节中的汇编代码
+pub const ASM_ENABLED: bool = cfg!(target_arch = "x86_64");
+
+/// 用汇编计算 a + b，溢出时回绕
+#[cfg(target_arch = "x86_64")]
+pub fn add(a: u64, b: u64) -> u64 {
+    let result: u64;
+    unsafe {
+        // {0}、{1} 是格式化字符串的占位符，由编译器替换成它分配的寄存器
+        asm!(
+            "mov {0}, {1}",
+            "add {0}, {2}",
+            out(reg) result,
+            in(reg) a,
+            in(reg) b,
+            options(pure, nomem, nostack),
+        );
+    }
+    result
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn add(a: u64, b: u64) -> u64 {
+    a.wrapping_add(b)
+}
+
+/// 用移位和加法计算 x * 6：inout 让同一个寄存器既是输入又是输出，out(reg) _ 申请一个用完即弃的临时寄存器
+#[cfg(target_arch = "x86_64")]
+pub fn mul6(x: u64) -> u64 {
+    let mut x = x;
+    unsafe {
+        asm!(
+            "mov {tmp}, {x}",
+            "shl {tmp}, 1",
+            "shl {x}, 2",
+            "add {x}, {tmp}",
+            x = inout(reg) x,
+            tmp = out(reg) _,
+            options(pure, nomem, nostack),
+        );
+    }
+    x
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn mul6(x: u64) -> u64 {
+    x.wrapping_mul(6)
+}
+
+/// 64 位乘法得到 128 位结果：mul 指令固定使用 rax 作为一个乘数，结果的高 64 位写入 rdx，低 64 位写入 rax，
+/// 这种场景必须指定具体的寄存器，而不是让编译器分配
+#[cfg(target_arch = "x86_64")]
+pub fn mul_wide(a: u64, b: u64) -> (u64, u64) {
+    let hi: u64;
+    let lo: u64;
+    unsafe {
+        asm!(
+            "mul {}",
+            in(reg) b,
+            inlateout("rax") a => lo,
+            lateout("rdx") hi,
+            options(pure, nomem, nostack),
+        );
+    }
+    (hi, lo)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn mul_wide(a: u64, b: u64) -> (u64, u64) {
+    let r = a as u128 * b as u128;
+    ((r >> 64) as u64, r as u64)
+}
+
+/// 被汇编通过 call 调用的函数，遵循 C 调用约定
+extern "C" fn triple(x: u64) -> u64 {
+    x.wrapping_mul(3)
+}
+
+/// 在汇编中调用 Rust 函数：clobber_abi("C") 告诉编译器，C 调用约定中由调用者保存的寄存器都可能被改写
+#[cfg(target_arch = "x86_64")]
+pub fn call_triple(x: u64) -> u64 {
+    let result: u64;
+    unsafe {
+        asm!(
+            "call {f}",
+            f = sym triple,
+            in("rdi") x,
+            out("rax") result,
+            clobber_abi("C"),
+        );
+    }
+    result
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn call_triple(x: u64) -> u64 {
+    triple(x)
+}
+
+/// 读取时间戳计数器：rdtsc 把 64 位计数的高 32 位写入 edx，低 32 位写入 eax。
+/// 这里不能加 pure，否则编译器会认为两次调用的结果相同而只保留一次
+#[cfg(target_arch = "x86_64")]
+pub fn rdtsc() -> u64 {
+    let hi: u32;
+    let lo: u32;
+    unsafe {
+        asm!("rdtsc", out("eax") lo, out("edx") hi, options(nomem, nostack, preserves_flags));
+    }
+    ((hi as u64) << 32) | lo as u64
+}
+
+/// 其它架构上没有时间戳计数器，用单调时钟的纳秒数代替
+#[cfg(not(target_arch = "x86_64"))]
+pub fn rdtsc() -> u64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
+/// cpuid 指令返回的四个寄存器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Cpuid {
+    pub eax: u32,
+    pub ebx: u32,
+    pub ecx: u32,
+    pub edx: u32,
+}
+
+/// 执行 cpuid。rbx 被 LLVM 保留，不能作为操作数，因此先把它保存到一个临时寄存器中，执行完再交换回来
+#[cfg(target_arch = "x86_64")]
+pub fn cpuid(leaf: u32, sub_leaf: u32) -> Cpuid {
+    let (eax, ebx, ecx, edx): (u32, u32, u32, u32);
+    unsafe {
+        asm!(
+            "mov {tmp:r}, rbx",
+            "cpuid",
+            "xchg {tmp:r}, rbx",
+            tmp = out(reg) ebx,
+            inout("eax") leaf => eax,
+            inout("ecx") sub_leaf => ecx,
+            out("edx") edx,
+            options(nomem, nostack, preserves_flags),
+        );
+    }
+    Cpuid { eax, ebx, ecx, edx }
+}
+
+/// 其它架构上没有 cpuid，返回全 0
+#[cfg(not(target_arch = "x86_64"))]
+pub fn cpuid(_leaf: u32, _sub_leaf: u32) -> Cpuid {
+    Cpuid::default()
+}
+
+/// CPU 厂商字符串，例如 GenuineIntel、AuthenticAMD，依次保存在 leaf 0 的 ebx、edx、ecx 中
+pub fn cpu_vendor() -> Option<String> {
+    if !ASM_ENABLED {
+        return None;
+    }
+    let r = cpuid(0, 0);
+    let bytes: Vec<u8> = [r.ebx, r.edx, r.ecx].iter().flat_map(|v| v.to_le_bytes()).collect();
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 通过 cpuid leaf 1 检测的部分 CPU 特性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Features {
+    pub sse2: bool,
+    pub sse3: bool,
+    pub ssse3: bool,
+    pub sse4_1: bool,
+    pub sse4_2: bool,
+    pub popcnt: bool,
+}
+
+/// 其它架构上所有特性都是 false
+pub fn features() -> Features {
+    if !ASM_ENABLED {
+        return Features::default();
+    }
+    let r = cpuid(1, 0);
+    let bit = |reg: u32, n: u32| reg & (1 << n) != 0;
+    Features {
+        sse2: bit(r.edx, 26),
+        sse3: bit(r.ecx, 0),
+        ssse3: bit(r.ecx, 9),
+        sse4_1: bit(r.ecx, 19),
+        sse4_2: bit(r.ecx, 20),
+        popcnt: bit(r.ecx, 23),
+    }
+}
+
+// global_asm! 在 Rust 函数之外定义汇编代码，可以用来编写完整的函数。
+// 这里按 System V 调用约定用辗转相除法实现 gcd：参数在 rdi、rsi 中，返回值放在 rax 中。
+// 符号名与目标文件的格式有关，因此只在 Linux 上启用
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+std::arch::global_asm!(
+    ".pushsection .text.rust_course_gcd, \"ax\", @progbits",
+    ".globl rust_course_gcd",
+    ".type rust_course_gcd, @function",
+    "rust_course_gcd:",
+    "    mov rax, rdi",
+    "2:",
+    "    test rsi, rsi",
+    "    jz 3f",
+    "    xor edx, edx",
+    "    div rsi",
+    "    mov rax, rsi",
+    "    mov rsi, rdx",
+    "    jmp 2b",
+    "3:",
+    "    ret",
+    ".size rust_course_gcd, . - rust_course_gcd",
+    ".popsection",
+);
+
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+extern "sysv64" {
+    fn rust_course_gcd(a: u64, b: u64) -> u64;
+}
+
+/// 最大公约数，gcd(0, 0) = 0
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+pub fn gcd(a: u64, b: u64) -> u64 {
+    unsafe { rust_course_gcd(a, b) }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_os = "linux")))]
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[outer_print("内联汇编 asm!")]
+/// asm! 的第一部分是汇编模板字符串，多个字符串之间会自动插入换行；后面是操作数：
+/// + in(reg) 输入，out(reg) 输出，inout(reg) 既是输入又是输出，reg 表示由编译器选择一个通用寄存器
+/// + 输出写成 _ 表示丢弃，可以用来申请临时寄存器
+/// + 模板中用 {} 引用操作数，可以按位置，也可以按名称，与 format! 一样
+///
+/// options 告诉编译器汇编代码的性质，从而允许更多的优化：
+/// + pure：没有副作用，结果只取决于输入
+/// + nomem：不读写内存
+/// + nostack：不使用栈
+/// + preserves_flags：不修改标志寄存器
+pub fn asm_intro() {
+    if !ASM_ENABLED {
+        println!("当前架构不是 x86_64，以下结果来自纯 Rust 实现");
+    }
+    println!("add(40, 2) = {}", add(40, 2));
+    println!("mul6(7) = {}", mul6(7));
+    assert_eq!(add(u64::MAX, 1), 0);
+    assert_eq!(mul6(7), 42);
+}
+
+#[outer_print("显式寄存器与 clobber")]
+/// 有些指令只能使用固定的寄存器，这时用寄存器的名字代替 reg，例如 in("rax")；显式寄存器不能在模板中用 {} 引用。
+///
+/// lateout 表示输出只在所有输入都被读取后才写入，因此编译器可以让它与某个输入共用寄存器。
+///
+/// 汇编中修改了却没有声明为输出的寄存器被称为 clobber，必须告诉编译器：
+/// + 用 out("rdx") _ 声明单个寄存器会被破坏
+/// + 在汇编中调用函数时，用 clobber_abi("C") 一次性声明调用约定中所有会被破坏的寄存器
+/// + 没有 preserves_flags 选项时，编译器默认标志寄存器会被修改
+pub fn register_operands() {
+    let (hi, lo) = mul_wide(u64::MAX, 3);
+    println!("u64::MAX * 3 = 0x{:016x}_{:016x}", hi, lo);
+    assert_eq!((hi, lo), (2, u64::MAX - 2));
+
+    inner_print!("在汇编中调用函数");
+    println!("call_triple(14) = {}", call_triple(14));
+}
+
+#[outer_print("读取时间戳计数器 rdtsc")]
+/// rdtsc 读取 CPU 从复位开始经过的时钟周期数，常用于非常精细的计时。
+/// 现代 CPU 的计数频率是恒定的，与当前的实际主频无关；指令可能被乱序执行，严谨的测量还需要配合 lfence 等序列化指令。
+pub fn rdtsc_lesson() {
+    let start = rdtsc();
+    let mut sum = 0u64;
+    for i in 0..1000u64 {
+        sum = std::hint::black_box(sum + i);
+    }
+    let end = rdtsc();
+    println!("累加 1000 次，sum = {}，耗时 {} 个计数周期", sum, end.wrapping_sub(start));
+}
+
+#[outer_print("cpuid 特性检测")]
+/// cpuid 是查询 CPU 信息的指令：eax 传入要查询的叶号(leaf)，结果写在 eax、ebx、ecx、edx 四个寄存器中。
+/// 日常开发中应优先使用标准库的 is_x86_feature_detected! 宏，它内部也是通过 cpuid 实现的，并且会缓存结果。
+pub fn cpuid_lesson() {
+    match cpu_vendor() {
+        Some(vendor) => println!("CPU 厂商: {}", vendor),
+        None => println!("当前架构不支持 cpuid"),
+    }
+    println!("{:?}", features());
+}
+
+#[outer_print("全局汇编 global_asm!")]
+/// global_asm! 写在函数外部，用来定义完整的汇编函数或者数据段，再通过 extern 块声明后调用。
+/// 与 asm! 不同，编译器不会替你保存和恢复寄存器，必须自己遵守调用约定。
+pub fn global_asm_lesson() {
+    println!("gcd(48, 18) = {}", gcd(48, 18));
+    assert_eq!(gcd(48, 18), 6);
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashMap;
+    use advance_course::rust_4_10_macro::rust_4_10_2::{self, Fragment};
+    use advance_course::rust_4_10_macro::rust_4_10_3::{self, Lesson, Meters};
+    use advance_course::rust_4_10_macro::rust_4_10_1;
+    use advance_course::{count, hashmap, max, rpn};
+    use common::{expand, LessonDescribe};
+
+    #[test]
+    fn test_macro() {
+        rust_4_10_1::repetition();
+        rust_4_10_1::hygiene();
+        rust_4_10_2::tt_muncher();
+        rust_4_10_2::generate_items();
+        rust_4_10_3::attribute_macro();
+        rust_4_10_3::derive_macro();
+    }
+
+    #[test]
+    fn test_macro_rules() {
+        let map: HashMap<i32, &str> = hashmap! { 1 => "a", 2 => "b" };
+        assert_eq!((map.len(), map[&1], map[&2]), (2, "a", "b"));
+        assert_eq!(max!(1), 1);
+        assert_eq!(max!(-5, -2, -9,), -2);
+        assert_eq!(count!(), 0);
+        assert_eq!(count!(() [] {} x 1 "s"), 6);
+        assert_eq!(rpn!(7), 7);
+        assert_eq!(rpn!(1 2 3 * +), 7);
+        assert_eq!(rpn!(20 4 / 3 -), 2);
+        assert_eq!(Fragment::ALL[0], Fragment::Expr);
+        assert_eq!(Fragment::ALL.map(|f| f.name()).join(","), "Expr,Ident,Ty,Tt,Pat,Literal");
+    }
+
+    #[test]
+    fn test_lesson_describe() {
+        assert_eq!(
+            Lesson::<()>::describe(),
+            "struct Lesson<'a, T> {\n    /// 章节编号\n    chapter: (u8, u8),\n    /// 标题\n    title: &'a str,\n    tags: Vec<String>,\n    extra: Option<T>,\n}"
+        );
+        assert_eq!(
+            Lesson::<()>::FIELDS,
+            [("chapter", "(u8, u8)"), ("title", "&'a str"), ("tags", "Vec<String>"), ("extra", "Option<T>")]
+        );
+        assert_eq!(Meters::describe(), "struct Meters(f64);");
+        assert_eq!(Meters::FIELDS, [("0", "f64")]);
+
+        #[derive(LessonDescribe)]
+        struct Unit;
+        assert_eq!(Unit::describe(), "struct Unit;");
+        assert!(Unit::FIELDS.is_empty());
+
+        #[derive(LessonDescribe)]
+        #[allow(dead_code)]
+        struct Nested<T: Clone> {
+            map: HashMap<String, Vec<Option<T>>>,
+            f: fn(&mut [u8]) -> Result<(), String>,
+            dynamic: Box<dyn Fn(i32) -> i32 + Send>,
+        }
+        assert_eq!(
+            Nested::<u8>::FIELDS,
+            [
+                ("map", "HashMap<String, Vec<Option<T>>>"),
+                ("f", "fn(&mut [u8]) -> Result<(), String>"),
+                ("dynamic", "Box<dyn Fn(i32) -> i32 + Send>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            rust_4_10_3::OUTER_PRINT_EXPANSION,
+            r#"struct demoGuard;
+impl Drop for demoGuard {
+    fn drop(&mut self) {
+        ::lesson_runtime::section::end("示例");
+    }
+}
+pub fn demo(name: &str) -> usize {
+    ::lesson_runtime::section::start("示例");
+    let _guard = demoGuard;
+    {
+        println!("hello, {}", name);
+        name.len()
+    }
+}"#
+        );
+        assert_eq!(expand!(inner_print!("x");), r#"::lesson_runtime::section::inner("x");"#);
+
+        let expanded = expand! {
+            /// 注释会被还原成 ///
+            #[derive(Clone, LessonDescribe)]
+            struct Pair<'a, T: Clone> where T: Copy {
+                left: &'a T,
+                right: [T; 2],
+            }
+        };
+        assert_eq!(
+            expanded,
+            r#"/// 注释会被还原成 ///
+#[derive(Clone)]
+struct Pair<'a, T: Clone> where T: Copy {
+    left: &'a T,
+    right: [T; 2],
+}
+impl<'a, T: Clone> Pair<'a, T> where T: Copy {
+    /// 字段名和字段类型，元组结构体的字段名是下标
+    pub const FIELDS: &'static [(&'static str, &'static str)] = &[("left", "&'a T"), ("right", "[T; 2]")];
+    /// 结构体的定义
+    pub fn describe() -> &'static str {
+        "struct Pair<'a, T: Clone> {\n    left: &'a T,\n    right: [T; 2],\n}"
+    }
+}"#
+        );
+
+        // 带 expand 参数的展开中包含了不带参数时的展开
+        let with_expand = expand! {
+            #[common::outer_print("标题", expand)]
+            fn f() {}
+        };
+        assert!(with_expand.contains(r#"println!("{}", "struct fGuard;"#));
+        assert!(with_expand.ends_with("let _guard = fGuard;\n    {}\n}"));
+    }
+}
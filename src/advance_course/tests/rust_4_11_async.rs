@@ -0,0 +1,141 @@
+#[cfg(test)]
+pub mod tests {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Waker};
+    use std::time::Duration;
+    use advance_course::rust_4_11_async::rust_4_11_1::{self, block_on, block_on_with, Countdown, ThreadWaker};
+    use advance_course::rust_4_11_async::rust_4_11_2::{self, new_executor_and_spawner, sleep};
+    use advance_course::rust_4_11_async::rust_4_11_3::{self, join_all, select, timeout, yield_now, Either, Elapsed};
+    use advance_course::rust_4_11_async::rust_4_11_4::{self, WordCount};
+
+    #[test]
+    fn test_async() {
+        rust_4_11_1::future_intro();
+        rust_4_11_1::waker_intro();
+        rust_4_11_2::timer_future();
+        rust_4_11_2::executor();
+        rust_4_11_3::join_lesson();
+        rust_4_11_3::select_lesson();
+        rust_4_11_4::pin_intro();
+        rust_4_11_4::self_ref_future();
+    }
+
+    #[test]
+    fn test_waker() {
+        let waker = ThreadWaker::current();
+        assert_eq!(block_on_with(Arc::clone(&waker), Countdown(0)), "发射!");
+        assert_eq!(waker.wakes(), 0);
+        block_on_with(Arc::clone(&waker), Countdown(10));
+        assert_eq!(waker.wakes(), 10);
+
+        // 被其它线程唤醒
+        let waker = ThreadWaker::current();
+        block_on_with(Arc::clone(&waker), sleep(Duration::from_millis(5)));
+        assert_eq!(waker.wakes(), 1);
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let w = rust_4_11_1::counting_waker(Arc::clone(&counter));
+        let clones: Vec<Waker> = (0..4).map(|_| w.clone()).collect();
+        assert_eq!(Arc::strong_count(&counter), 6);
+        w.wake_by_ref();
+        clones.into_iter().for_each(Waker::wake);
+        drop(w);
+        assert_eq!(counter.load(Ordering::Relaxed), 5);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn test_executor() {
+        let (executor, spawner) = new_executor_and_spawner();
+        let results = Arc::new(Mutex::new(Vec::new()));
+        for i in 0..5u64 {
+            let results = Arc::clone(&results);
+            spawner.spawn(async move {
+                Countdown(i as u32).await;
+                sleep(Duration::from_millis(1)).await;
+                results.lock().unwrap().push(i);
+            });
+        }
+        drop(spawner);
+        // 每个任务：Countdown(i) 需要 i + 1 次 poll，sleep 需要再被唤醒 1 次
+        let polls = executor.run();
+        assert_eq!(polls, (0..5).map(|i| i + 2).sum::<usize>());
+        let mut results = results.lock().unwrap().clone();
+        results.sort();
+        assert_eq!(results, [0, 1, 2, 3, 4]);
+
+        // 没有任务时立刻返回
+        let (executor, spawner) = new_executor_and_spawner();
+        drop(spawner);
+        assert_eq!(executor.run(), 0);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let (a, b) = block_on(advance_course::join!(async { 1 }, Countdown(3)));
+        assert_eq!((a, b), (1, "发射!"));
+        let (a, b, c) = block_on(advance_course::join!(async { 'a' }, yield_now(), sleep(Duration::from_millis(1))));
+        assert_eq!((a, b, c), ('a', (), ()));
+        assert_eq!(block_on(join_all((0..5).map(|i| async move { i * 2 }))), [0, 2, 4, 6, 8]);
+        assert!(block_on(join_all(Vec::<Countdown>::new())).is_empty());
+
+        // 同时就绪时左边优先
+        assert_eq!(block_on(select(async { 1 }, async { 2 })), Either::Left(1));
+        assert_eq!(block_on(select(Countdown(2), async { 2 })), Either::Right(2));
+        let branch = block_on(async {
+            advance_course::select! {
+                n = Countdown(5) => n.len(),
+                n = async { 3 } => n,
+            }
+        });
+        assert_eq!(branch, 3);
+
+        assert_eq!(block_on(timeout(Duration::from_secs(5), Countdown(2))), Ok("发射!"));
+        assert_eq!(block_on(timeout(Duration::from_millis(1), sleep(Duration::from_secs(1)))), Err(Elapsed));
+    }
+
+    #[test]
+    fn test_cancel() {
+        // select 返回后，输掉的 Future 被 drop
+        struct Guard(Arc<AtomicUsize>);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let guard = Guard(Arc::clone(&dropped));
+        let loser = async move {
+            let _guard = guard;
+            sleep(Duration::from_secs(1)).await;
+        };
+        assert_eq!(block_on(select(loser, async {})), Either::Right(()));
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_self_ref_future() {
+        assert_eq!(block_on(WordCount::new("")), 0);
+        assert_eq!(block_on(WordCount::new("  one  ")), 1);
+        assert_eq!(block_on(WordCount::new("a b\tc\nd 中文 单词")), 6);
+        assert_eq!(block_on(rust_4_11_4::borrow_across_await(vec![5, 6])), 7);
+
+        let mut future = Box::pin(WordCount::new("x y z"));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut pending = 0;
+        while Pin::as_mut(&mut future).poll(&mut cx).is_pending() {
+            pending += 1;
+            assert_eq!(future.words(), pending);
+        }
+        assert_eq!(pending, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "超过了")]
+    fn test_word_count_capacity() {
+        WordCount::new(&"a".repeat(rust_4_11_4::CAPACITY + 1));
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+pub mod tests {
+    use std::cell::RefCell;
+    use advance_course::rust_4_2_functional::rust_4_2_1::{self, Event, EventBus};
+    use advance_course::rust_4_2_functional::rust_4_2_2::{self, Fibonacci, IteratorExt};
+
+    #[test]
+    fn test_closure() {
+        rust_4_2_1::closure_intro();
+        rust_4_2_1::fn_traits();
+        rust_4_2_1::move_closure();
+        rust_4_2_1::closure_as_return();
+
+        assert_eq!(rust_4_2_1::multiplier(-2)(21), -42);
+        assert_eq!(rust_4_2_1::operation("sub").unwrap()(7, 3), 4);
+        assert!(rust_4_2_1::operation("div").is_none());
+    }
+
+    #[test]
+    fn test_event_bus() {
+        let log = RefCell::new(Vec::new());
+        let mut count = 0;
+        let mut once_called = 0;
+        {
+            let mut bus: EventBus<Event> = EventBus::default();
+            assert!(bus.is_empty());
+            bus.subscribe(|_| count += 1)
+                .subscribe_if(|e| matches!(e, Event::Logout(_)), |e| log.borrow_mut().push(format!("{:?}", e)))
+                .once(|_| once_called += 1);
+            assert_eq!(bus.len(), 3);
+            assert_eq!(bus.publish(&Event::Login("a".into())), 2);
+            assert_eq!(bus.len(), 2);
+            assert_eq!(bus.publish(&Event::Logout("a".into())), 2);
+            assert_eq!(bus.publish(&Event::Purchase { user: "a".into(), amount: 1 }), 1);
+        }
+        assert_eq!((count, once_called), (3, 1));
+        assert_eq!(*log.borrow(), ["Logout(\"a\")"]);
+    }
+
+    #[test]
+    fn test_iterator() {
+        rust_4_2_2::iterator_intro();
+        rust_4_2_2::adapters();
+        rust_4_2_2::custom_iterator();
+        rust_4_2_2::iterator_performance();
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        let all: Vec<u64> = Fibonacci::new().collect();
+        // F(93) 是 u64 能表示的最大斐波那契数
+        assert_eq!(all.len(), 94);
+        assert_eq!(*all.last().unwrap(), 12200160415121876738);
+        assert!(all.windows(3).all(|w| w[0] + w[1] == w[2]));
+    }
+
+    #[test]
+    fn test_windows() {
+        for len in 0..6 {
+            let data: Vec<i32> = (0..len).collect();
+            for size in 0..7 {
+                let lazy: Vec<Vec<i32>> = data.iter().copied().windows(size).collect();
+                let expected: Vec<Vec<i32>> = if size == 0 {
+                    Vec::new()
+                } else {
+                    data.windows(size).map(<[i32]>::to_vec).collect()
+                };
+                assert_eq!(lazy, expected, "len {} size {}", len, size);
+            }
+        }
+        // 无限序列上也可以使用
+        let w: Vec<Vec<u32>> = (1..).windows(2).take(2).collect();
+        assert_eq!(w, [vec![1, 2], vec![2, 3]]);
+        // 窗口比数据长时不产生元素，也不会按 size 预先分配内存
+        assert_eq!([1, 2, 3].into_iter().windows(usize::MAX).count(), 0);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let groups: Vec<(bool, Vec<i32>)> = [1, 3, 2, 4, 6, 5].into_iter().group_by(|x| x % 2 == 0).collect();
+        assert_eq!(groups, [(false, vec![1, 3]), (true, vec![2, 4, 6]), (false, vec![5])]);
+        assert_eq!(std::iter::empty::<i32>().group_by(|x| *x).count(), 0);
+        // 与切片的 chunk_by 结果一致
+        let words = ["apple", "avocado", "banana", "blueberry", "cherry", "apricot"];
+        let lazy: Vec<Vec<&str>> = words.iter().copied().group_by(|w| w.as_bytes()[0]).map(|(_, g)| g).collect();
+        let expected: Vec<Vec<&str>> = words.chunk_by(|a, b| a.as_bytes()[0] == b.as_bytes()[0]).map(<[&str]>::to_vec).collect();
+        assert_eq!(lazy, expected);
+    }
+
+    #[test]
+    fn test_bench_results_agree() {
+        for len in [0, 1, 1000] {
+            let report = rust_4_2_2::bench(len, 1);
+            assert_eq!(report.results[0], report.results[1]);
+            assert_eq!(report.results[0], report.results[2]);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+#[cfg(test)]
+pub mod tests {
+    use std::str::FromStr;
+    use advance_course::rust_4_3_type_conversion::checked_cast::{cast_as, checked_cast, in_range, CastError, Primitive, Repr};
+    use advance_course::rust_4_3_type_conversion::rust_4_3_2::{ColorError, Rgb};
+    use advance_course::rust_4_3_type_conversion::{rust_4_3_1, rust_4_3_2, rust_4_3_3};
+
+    #[test]
+    fn test_type_conversion() {
+        rust_4_3_1::integer_as();
+        rust_4_3_1::float_as();
+        rust_4_3_1::checked_cast_lesson();
+        rust_4_3_2::from_into();
+        rust_4_3_2::try_from();
+        rust_4_3_3::transmute_intro();
+        rust_4_3_3::transmute_danger();
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Kind {
+        Exact,
+        OutOfRange,
+        Precision,
+        NotANumber,
+    }
+
+    fn kind<T>(result: &Result<T, CastError>) -> Kind {
+        match result {
+            Ok(_) => Kind::Exact,
+            Err(CastError::OutOfRange { .. }) => Kind::OutOfRange,
+            Err(CastError::Precision { .. }) => Kind::Precision,
+            Err(CastError::NotANumber { .. }) => Kind::NotANumber,
+        }
+    }
+
+    const INT_SAMPLES: &[&str] = &[
+        "0", "1", "-1", "2", "-2", "100", "-100", "127", "128", "-128", "-129", "255", "256", "300", "-300",
+        "32767", "32768", "-32768", "-32769", "65535", "65536", "16777216", "16777217", "-16777217",
+        "2147483647", "2147483648", "-2147483648", "-2147483649", "4294967295", "4294967296",
+        "9007199254740992", "9007199254740993", "-9007199254740993",
+        "9223372036854775807", "9223372036854775808", "-9223372036854775808", "-9223372036854775809",
+        "18446744073709551615", "18446744073709551616",
+        "170141183460469231731687303715884105727", "-170141183460469231731687303715884105728",
+        "340282346638528859811704183484516925440", "340282366920938463463374607431768211455",
+    ];
+
+    const FLOAT_SAMPLES: &[&str] = &[
+        "0.0", "-0.0", "0.5", "-0.5", "1.0", "-1.0", "2.5", "-2.5", "127.0", "127.5", "128.0", "-128.0", "-128.5",
+        "255.0", "255.9", "256.0", "-1.5", "65535.0", "65536.0", "16777216.0", "16777217.0", "2147483647.0",
+        "2147483648.0", "-2147483648.0", "-2147483649.0", "4294967295.0", "4294967296.0", "9007199254740992.0",
+        "9007199254740993.0", "9223372036854775807.0", "-9223372036854775808.0", "18446744073709551615.0", "1e20",
+        "-1e20", "1.7014118346046923e38", "-1.7014118346046923e38", "3.4028234663852886e38", "3.4028235677973366e38",
+        "1e39", "1e300", "-1e300", "0.1", "1.1754943508222875e-38", "1e-45", "1e-300", "1.7976931348623157e308",
+        "NaN", "inf", "-inf",
+    ];
+
+    /// 源类型能表示的样本，浮点数的样本会被舍入到最近的值
+    fn samples<S: Primitive + FromStr>() -> Vec<S> {
+        let candidates = if S::FLOAT { FLOAT_SAMPLES } else { INT_SAMPLES };
+        let mut values: Vec<S> = candidates.iter().filter_map(|s| s.parse().ok()).collect();
+        values.extend([S::MIN, S::MAX]);
+        values
+    }
+
+    /// 不经过 Repr 的预期结果：整数用字符串解析，浮点数用 as 转成 f64(精确)后计算
+    fn expected<S: Primitive, D: Primitive + FromStr>(value: S, wide: f64) -> Kind {
+        let mantissa = if D::BITS == 32 { 24 } else { 53 };
+        match (S::FLOAT, D::FLOAT) {
+            (false, false) => match value.to_string().parse::<D>() {
+                Ok(_) => Kind::Exact,
+                Err(_) => Kind::OutOfRange,
+            },
+            (false, true) => {
+                let text = value.to_string();
+                let magnitude = text.parse::<i128>().map(i128::unsigned_abs).unwrap_or_else(|_| text.parse::<u128>().unwrap());
+                if magnitude == 0 || 128 - magnitude.leading_zeros() - magnitude.trailing_zeros() <= mantissa {
+                    Kind::Exact
+                } else if D::BITS == 32 && magnitude > f32::MAX as u128 {
+                    Kind::OutOfRange
+                } else {
+                    Kind::Precision
+                }
+            }
+            (true, false) => {
+                let low = D::MIN.to_string().parse::<f64>().unwrap();
+                // 2^53 以内 MAX 可以精确表示；更大时 MAX 会舍入成 2^BITS 或 2^(BITS-1)，范围是开区间
+                let high = D::MAX.to_string().parse::<f64>().unwrap();
+                let in_range = wide >= low && if D::BITS - D::SIGNED as u32 <= 53 { wide <= high } else { wide < high };
+                if wide.is_nan() {
+                    Kind::NotANumber
+                } else if !in_range {
+                    Kind::OutOfRange
+                } else if wide.fract() == 0.0 {
+                    Kind::Exact
+                } else {
+                    Kind::Precision
+                }
+            }
+            (true, true) => {
+                if D::BITS == 64 || S::BITS == 32 || !wide.is_finite() || wide as f32 as f64 == wide {
+                    Kind::Exact
+                } else if wide.abs() > f32::MAX as f64 {
+                    Kind::OutOfRange
+                } else {
+                    Kind::Precision
+                }
+            }
+        }
+    }
+
+    fn check<S: Primitive, D: Primitive + FromStr>(value: S, plain: D, wide: f64) -> usize {
+        let pair = format!("{:?} {} as {}", value, S::NAME, D::NAME);
+        let result = checked_cast::<S, D>(value);
+        assert_eq!(format!("{:?}", cast_as::<S, D>(value)), format!("{:?}", plain), "{}", pair);
+        if let Ok(v) = &result {
+            assert_eq!(format!("{:?}", v), format!("{:?}", plain), "{}", pair);
+        }
+        assert_eq!(kind(&result), expected::<S, D>(value, wide), "{} => {:?}", pair, result);
+        1
+    }
+
+    macro_rules! check_matrix {
+        ($($src:ty),+ => $dst:tt) => {{
+            let mut count = 0;
+            $( count += check_matrix!(@row $src => $dst); )+
+            count
+        }};
+        (@row $src:ty => [$($dst:ty),+]) => {{
+            let mut count = 0;
+            for value in samples::<$src>() {
+                $( count += check::<$src, $dst>(value, value as $dst, value as f64); )+
+            }
+            count
+        }};
+    }
+
+    #[test]
+    fn test_checked_cast_matrix() {
+        let count = check_matrix!(
+            i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+                => [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64]
+        );
+        println!("检查了 {} 次转换", count);
+        assert!(count > 196 * 10);
+    }
+
+    #[test]
+    fn test_cast_error() {
+        assert_eq!(checked_cast::<i8, u16>(-1), Err(CastError::OutOfRange { value: "-1".into(), target: "u16" }));
+        assert_eq!(
+            checked_cast::<i32, f32>(16_777_217),
+            Err(CastError::Precision { value: "16777217".into(), target: "f32", result: "16777216.0".into() })
+        );
+        assert_eq!(checked_cast::<f32, u64>(f32::NAN), Err(CastError::NotANumber { target: "u64" }));
+        assert_eq!(checked_cast::<f64, f32>(f64::NEG_INFINITY), Ok(f32::NEG_INFINITY));
+        assert!(checked_cast::<f64, f64>(f64::NAN).unwrap().is_nan());
+        assert_eq!(checked_cast::<f64, u8>(2.5).unwrap_err().to_string(), "2.5 无法用 u8 精确表示，会变成 2");
+        assert_eq!(checked_cast::<u64, i8>(200).unwrap_err().to_string(), "200 超出了 i8 的范围");
+
+        assert!(in_range::<f64, u8>(254.5));
+        assert!(!in_range::<f64, u8>(255.5));
+        assert!(!in_range::<f64, u8>(256.0));
+        assert!(!in_range::<f64, f32>(f64::INFINITY));
+        assert_eq!(Repr::Int(-1).compare(Repr::UInt(0)), Some(std::cmp::Ordering::Less));
+        assert_eq!(Repr::UInt(u128::MAX).compare(Repr::Float(3.4e38)), Some(std::cmp::Ordering::Greater));
+        assert_eq!(Repr::Float(f64::NAN).compare(Repr::Int(0)), None);
+    }
+
+    #[test]
+    fn test_show_bits() {
+        assert_eq!(rust_4_3_1::show_bits(300, 16, 44, 8), "0000000100101100 -> 00101100");
+        assert_eq!(rust_4_3_1::show_bits(-1, 8, -1, 16), "11111111 -> 1111111111111111");
+        assert!(rust_4_3_1::report::<i32, u8>(300).contains("超出了 u8 的范围"));
+        assert!(rust_4_3_1::report::<u64, f64>(1 << 53).ends_with("精确"));
+    }
+
+    #[test]
+    fn test_rgb() {
+        let color = Rgb::from((1, 2, 3));
+        assert_eq!(u32::from(color), 0x010203);
+        assert_eq!(Rgb::try_from(0x010203u32), Ok(color));
+        assert_eq!(Rgb::try_from("#010203"), Ok(color));
+        assert_eq!(Rgb::try_from("010203"), Err(ColorError::Format("010203".into())));
+        assert_eq!(Rgb::try_from("#0102034"), Err(ColorError::Format("#0102034".into())));
+        assert_eq!(Rgb::try_from("#一2"), Err(ColorError::Format("#一2".into())));
+        assert!(matches!(Rgb::try_from("#01020g"), Err(ColorError::Digit(_))));
+        assert_eq!(Rgb::try_from("#+10203"), Err(ColorError::Format("#+10203".into())));
+        assert_eq!(Rgb::try_from(0x1_000_000u32), Err(ColorError::Range(0x1_000_000)));
+    }
+
+    #[test]
+    fn test_bool_from_u8() {
+        assert_eq!(rust_4_3_3::bool_from_u8(0), Some(false));
+        assert_eq!(rust_4_3_3::bool_from_u8(1), Some(true));
+        assert!((2..=255).all(|b| rust_4_3_3::bool_from_u8(b).is_none()));
+    }
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+pub mod tests {
+    use std::rc::Rc;
+    use advance_course::rust_4_4_smart_pointer::rust_4_4_1::{self, Owned};
+    use advance_course::rust_4_4_smart_pointer::rust_4_4_2;
+    use advance_course::rust_4_4_smart_pointer::rust_4_4_3::{self, DropLog, DropProbe};
+    use advance_course::rust_4_4_smart_pointer::{rust_4_4_4, rust_4_4_5};
+
+    #[test]
+    fn test_smart_pointer() {
+        rust_4_4_1::box_intro();
+        rust_4_4_2::deref_intro();
+        rust_4_4_3::drop_order();
+        rust_4_4_4::rc_intro();
+        rust_4_4_4::arc_intro();
+        rust_4_4_5::cell();
+        rust_4_4_5::ref_cell();
+    }
+
+    #[test]
+    fn test_owned() {
+        let mut owned = Owned::new(vec![1, 2]);
+        owned.push(3);
+        assert_eq!(*owned, [1, 2, 3]);
+        assert_eq!(owned.into_inner(), [1, 2, 3]);
+
+        // 零大小类型不分配内存
+        let unit = Owned::new(());
+        assert_eq!(*unit, ());
+
+        let log = DropLog::default();
+        let outer = Owned::new(Owned::new(DropProbe::new("nested", &log)));
+        assert_eq!(outer.name, "nested");
+        drop(outer);
+        assert_eq!(*log.borrow(), ["nested"]);
+        assert_eq!(Rc::strong_count(&log), 1);
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(test)]
+pub mod tests {
+    use std::rc::Rc;
+    use advance_course::rust_4_4_smart_pointer::rust_4_4_3::{DropLog, DropProbe};
+    use advance_course::rust_4_5_circle_self_ref::rust_4_5_1::{self, DoublyLinkedList, TreeNode};
+    use advance_course::rust_4_5_circle_self_ref::rust_4_5_2::{self, Arena};
+
+    #[test]
+    fn test_circle_self_ref() {
+        rust_4_5_1::doubly_linked_list();
+        rust_4_5_1::parent_tree();
+        rust_4_5_1::cycle_leak();
+        rust_4_5_2::self_ref();
+        rust_4_5_2::arena_tree();
+    }
+
+    #[test]
+    fn test_doubly_linked_list() {
+        let mut list = DoublyLinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_back(), None);
+        for v in 1..=4 {
+            list.push_back(v);
+        }
+        list.push_front(0);
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.to_vec(), [0, 1, 2, 3, 4]);
+        assert_eq!(list.to_vec_rev(), [4, 3, 2, 1, 0]);
+
+        // 头节点只被 list.head 强引用，被后一个节点的 prev 弱引用；尾节点还被前一个节点的 next 强引用
+        assert_eq!(list.head_counts(), Some((1, 1)));
+        assert_eq!(list.tail_counts(), Some((2, 0)));
+
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!((list.head().as_deref(), list.tail().as_deref()), (Some(&1), Some(&3)));
+        assert_eq!(list.head_counts(), Some((1, 1)));
+        assert_eq!(list.tail_counts(), Some((2, 0)));
+
+        while list.pop_back().is_some() {}
+        assert!(list.is_empty() && list.head().is_none() && list.tail().is_none());
+        assert_eq!(list.head_counts(), None);
+        list.push_front(9);
+        assert_eq!(list.to_vec_rev(), [9]);
+    }
+
+    /// 读取头节点之后再弹出：head 返回的借用不会留下额外的强引用，弹出时也就不会 panic
+    #[test]
+    fn test_pop_after_head() {
+        let mut list = DoublyLinkedList::new();
+        for v in [String::from("a"), String::from("b")] {
+            list.push_back(v);
+        }
+        let head = list.head().unwrap();
+        let tail = list.tail().unwrap();
+        assert_eq!((head.as_str(), tail.as_str()), ("a", "b"));
+        drop((head, tail));
+        assert_eq!(list.pop_front().as_deref(), Some("a"));
+        // 只剩一个节点，list.head 和 list.tail 都持有它
+        assert_eq!(list.head_counts(), Some((2, 0)));
+        assert_eq!(list.pop_back().as_deref(), Some("b"));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_list_drop() {
+        let log = DropLog::default();
+        let mut list = DoublyLinkedList::new();
+        for name in ["x", "y", "z"] {
+            list.push_back(DropProbe::new(name, &log));
+        }
+        drop(list);
+        assert_eq!(*log.borrow(), ["x", "y", "z"]);
+        assert_eq!(Rc::strong_count(&log), 1);
+
+        // 很长的链表也不会在 drop 时栈溢出
+        let mut long = DoublyLinkedList::new();
+        for v in 0..200_000 {
+            long.push_back(v);
+        }
+        drop(long);
+    }
+
+    #[test]
+    fn test_parent_tree() {
+        let root = TreeNode::new(1);
+        let child = TreeNode::new(2);
+        let grandchild = TreeNode::new(3);
+        TreeNode::add_child(&child, Rc::clone(&grandchild));
+        TreeNode::add_child(&root, Rc::clone(&child));
+
+        assert_eq!(TreeNode::path(&grandchild), [1, 2, 3]);
+        assert_eq!(root.children().iter().map(|c| c.value).collect::<Vec<_>>(), [2]);
+        assert_eq!((Rc::strong_count(&root), Rc::weak_count(&root)), (1, 1));
+        assert_eq!((Rc::strong_count(&child), Rc::weak_count(&child)), (2, 1));
+
+        // 父节点被释放后，子节点只剩外部持有的强引用
+        drop(root);
+        assert!(child.parent().is_none());
+        assert_eq!(Rc::strong_count(&child), 1);
+        assert_eq!(TreeNode::path(&grandchild), [2, 3]);
+    }
+
+    #[test]
+    fn test_cycle_leak() {
+        let log = DropLog::default();
+        let (a, b) = rust_4_5_1::make_cycle(&log);
+        assert_eq!((a.strong_count(), a.weak_count()), (1, 1));
+        assert_eq!((b.strong_count(), b.weak_count()), (1, 1));
+        assert!(log.borrow().is_empty());
+
+        rust_4_5_1::break_cycle(&a);
+        assert_eq!((a.strong_count(), b.strong_count()), (0, 0));
+        assert_eq!(*log.borrow(), ["b", "a"]);
+        assert_eq!(Rc::strong_count(&log), 1);
+    }
+
+    #[test]
+    fn test_arena() {
+        let mut arena = Arena::new();
+        let root = arena.add_root(0);
+        let a = arena.add_child(root, 1);
+        let b = arena.add_child(root, 2);
+        let c = arena.add_child(a, 3);
+        let other = arena.add_root(10);
+
+        assert_eq!(arena.len(), 5);
+        assert_eq!(arena.get(root).children, [a, b]);
+        assert_eq!(arena.get(c).parent, Some(a));
+        assert_eq!(arena.ancestors(c).collect::<Vec<_>>(), [c, a, root]);
+        assert_eq!((arena.depth(root), arena.depth(c), arena.depth(other)), (0, 2, 0));
+        assert_eq!(arena.preorder(root), [root, a, c, b]);
+
+        arena.get_mut(b).value += 40;
+        let values: Vec<i32> = arena.preorder(root).into_iter().map(|id| arena.get(id).value).collect();
+        assert_eq!(values, [0, 1, 3, 42]);
+    }
+}
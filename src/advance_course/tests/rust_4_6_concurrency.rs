@@ -0,0 +1,128 @@
+#[cfg(test)]
+pub mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{mpsc, Arc, Barrier};
+    use std::thread;
+    use advance_course::rust_4_6_concurrency::rust_4_6_3::{self, BlockingQueue};
+    use advance_course::rust_4_6_concurrency::rust_4_6_4::{self, AtomicCounter};
+    use advance_course::rust_4_6_concurrency::rust_4_6_5::{self, ThreadPool};
+    use advance_course::rust_4_6_concurrency::{rust_4_6_1, rust_4_6_2};
+
+    #[test]
+    fn test_concurrency() {
+        rust_4_6_1::spawn_thread();
+        rust_4_6_1::scoped_thread();
+        rust_4_6_2::channel();
+        rust_4_6_2::work_distribution();
+        rust_4_6_3::mutex();
+        rust_4_6_3::rw_lock();
+        rust_4_6_3::condvar();
+        rust_4_6_4::atomic_counter();
+        rust_4_6_4::memory_ordering();
+        rust_4_6_5::thread_pool();
+    }
+
+    #[test]
+    fn test_parallel_sum() {
+        let data: Vec<u64> = (0..10_001).collect();
+        for parts in [1, 2, 3, 7, 64, 20_000] {
+            assert_eq!(rust_4_6_1::parallel_sum(&data, parts), 50_005_000);
+        }
+        assert_eq!(rust_4_6_1::parallel_sum(&[], 4), 0);
+    }
+
+    #[test]
+    fn test_distribute() {
+        let results = rust_4_6_2::distribute((0..100).collect(), 4, |n| n + 1);
+        assert_eq!(results.len(), 100);
+        assert!(results.iter().enumerate().all(|(i, &(worker, job, result))| worker < 4 && job == i as u64 && result == job + 1));
+        assert!(rust_4_6_2::distribute(Vec::new(), 2, |n| n).is_empty());
+    }
+
+    #[test]
+    fn test_blocking_queue() {
+        let queue = BlockingQueue::new(3);
+        let total: u64 = thread::scope(|s| {
+            for p in 0..4u64 {
+                let queue = &queue;
+                s.spawn(move || (0..250).for_each(|i| queue.push(p * 1000 + i)));
+            }
+            (0..1000).map(|_| queue.pop()).sum()
+        });
+        let expected: u64 = (0..4u64).map(|p| (0..250).map(|i| p * 1000 + i).sum::<u64>()).sum();
+        assert_eq!(total, expected);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_atomic_counter() {
+        let counter = AtomicCounter::new();
+        thread::scope(|s| {
+            for t in 0..4u64 {
+                let counter = &counter;
+                s.spawn(move || {
+                    for i in 0..10_000 {
+                        counter.increment();
+                        counter.record_max(i * 4 + t);
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.get(), 40_000);
+        assert_eq!(counter.max(), 39_999);
+        // 比当前最大值小的样本不会改变结果
+        assert_eq!(counter.record_max(5), 39_999);
+        assert_eq!(rust_4_6_4::publish(7), 7);
+    }
+
+    #[test]
+    fn test_thread_pool() {
+        let pool = ThreadPool::new(4);
+        assert_eq!(pool.size(), 4);
+        // 4 个任务在屏障处互相等待，只有 4 个线程真的同时运行时才能全部通过
+        let barrier = Arc::new(Barrier::new(4));
+        let (tx, rx) = mpsc::channel();
+        for i in 0..4 {
+            let (barrier, tx) = (Arc::clone(&barrier), tx.clone());
+            pool.execute(move || {
+                barrier.wait();
+                tx.send(i).unwrap();
+            });
+        }
+        drop(tx);
+        let mut ids: Vec<i32> = rx.iter().collect();
+        ids.sort();
+        assert_eq!(ids, [0, 1, 2, 3]);
+
+        // shutdown 会等待所有已提交的任务
+        let counter = Arc::new(AtomicUsize::new(0));
+        for _ in 0..100 {
+            let counter = Arc::clone(&counter);
+            pool.execute(move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        assert_eq!(pool.shutdown(), 104);
+        assert_eq!(counter.load(Ordering::Relaxed), 100);
+
+        // drop 同样会等待任务完成
+        let counter = Arc::new(AtomicUsize::new(0));
+        {
+            let pool = ThreadPool::new(2);
+            for _ in 0..10 {
+                let counter = Arc::clone(&counter);
+                pool.execute(move || {
+                    thread::sleep(std::time::Duration::from_millis(1));
+                    counter.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+        }
+        assert_eq!(counter.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "线程池大小不能为 0")]
+    fn test_thread_pool_zero() {
+        ThreadPool::new(0);
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashSet;
+    use std::thread;
+    use advance_course::rust_4_7_global_variable::{rust_4_7_1, rust_4_7_2, rust_4_7_3};
+    use lesson_runtime::config::{ColorChoice, Config, ConfigError, Sink, Verbosity};
+    use lesson_runtime::section::{self, Banner};
+
+    #[test]
+    fn test_global_variable() {
+        rust_4_7_1::const_and_static();
+        rust_4_7_1::static_mut();
+        rust_4_7_1::static_atomic();
+        rust_4_7_2::lazy_lock();
+        rust_4_7_2::once_lock();
+        rust_4_7_2::lesson_config();
+        rust_4_7_3::thread_local_intro();
+        rust_4_7_3::section_stack();
+    }
+
+    #[test]
+    fn test_next_id() {
+        let ids: Vec<u64> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8).map(|_| s.spawn(|| (0..50).map(|_| rust_4_7_1::next_id()).collect::<Vec<_>>())).collect();
+            handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 400);
+        assert!(ids.iter().all(|&id| id > 0));
+    }
+
+    #[test]
+    fn test_lazy_keywords() {
+        assert!(rust_4_7_2::is_keyword("impl"));
+        assert!(!rust_4_7_2::is_keyword("println"));
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| assert!(rust_4_7_2::is_keyword("dyn")));
+            }
+        });
+        assert_eq!(rust_4_7_2::keyword_inits(), 1);
+    }
+
+    #[test]
+    fn test_per_thread_counts() {
+        assert_eq!(rust_4_7_3::per_thread_counts(6), [1, 2, 3, 4, 5, 6]);
+        // 新线程中的计数总是从 0 开始
+        assert_eq!(thread::spawn(rust_4_7_3::bump).join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_section_stack() {
+        // 每个测试都运行在自己的线程中，栈一开始是空的
+        assert_eq!(section::depth(), 0);
+        let before = section::started();
+        let path = rust_4_7_3::nested_section();
+        assert_eq!(path, ["嵌套的课程", "内层小节"]);
+        assert_eq!(section::depth(), 0);
+        assert!(section::started() > before);
+
+        // panic 时 Guard 依然会 drop，栈也会恢复
+        let result = std::panic::catch_unwind(|| {
+            rust_4_7_3::section_stack();
+            #[common::outer_print("panic")]
+            fn panicking() {
+                panic!("课程中 panic");
+            }
+            panicking();
+        });
+        assert!(result.is_err());
+        assert_eq!(section::depth(), 0);
+    }
+
+    #[test]
+    fn test_config_parse() {
+        let lookup = |pairs: &'static [(&'static str, &'static str)]| {
+            Config::from_lookup(move |key| pairs.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string()))
+        };
+        assert_eq!(lookup(&[]), Ok(Config::default()));
+        assert_eq!(
+            lookup(&[("LESSON_SINK", "NULL"), ("LESSON_COLOR", " always "), ("LESSON_VERBOSITY", "quiet")]),
+            Ok(Config { sink: Sink::Null, color: ColorChoice::Always, verbosity: Verbosity::Quiet })
+        );
+        let err = lookup(&[("LESSON_VERBOSITY", "loud")]).unwrap_err();
+        assert_eq!(err, ConfigError { key: "LESSON_VERBOSITY", value: "loud".into() });
+        assert_eq!(err.to_string(), "无法识别的配置 LESSON_VERBOSITY=loud");
+    }
+
+    #[test]
+    fn test_render() {
+        let plain = Config { color: ColorChoice::Never, ..Config::default() };
+        assert_eq!(
+            section::render(&plain, Banner::Start, "标题", "#1").as_deref(),
+            Some("[start]==================================================[标题]")
+        );
+        assert_eq!(
+            section::render(&plain, Banner::End, "标题", "").as_deref(),
+            Some("[end]====================================================[标题]\n")
+        );
+
+        let verbose = Config { verbosity: Verbosity::Verbose, ..plain.clone() };
+        assert_eq!(
+            section::render(&verbose, Banner::Inner, "小节", "课程 > 小节").as_deref(),
+            Some("[inner_print]============================================[小节] 课程 > 小节")
+        );
+
+        let color = Config { color: ColorChoice::Always, ..plain.clone() };
+        assert_eq!(
+            section::render(&color, Banner::Inner, "小节", "").as_deref(),
+            Some("\x1b[33m[inner_print]============================================[小节]\x1b[0m")
+        );
+        assert!(section::render(&color, Banner::Start, "标题", "").unwrap().starts_with("\x1b[36m"));
+
+        for config in [Config { verbosity: Verbosity::Quiet, ..plain.clone() }, Config { sink: Sink::Null, ..plain }] {
+            assert_eq!(section::render(&config, Banner::Start, "标题", ""), None);
+        }
+    }
+}
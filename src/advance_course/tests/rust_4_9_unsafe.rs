@@ -1,7 +1,201 @@
+//! 除了汇编和 FFI 的测试外，其余测试都可以在 Miri 下运行，用来检查安全包装中没有未定义行为。在 src/advance_course 目录下执行：
+//! cargo +nightly miri test --test rust_4_9_unsafe
+#[cfg(test)]
+pub mod tests {
+    use std::ffi::CString;
+    use advance_course::rust_4_9_unsafe::{rust_4_9_1, rust_4_9_2, rust_4_9_3, rust_4_9_4, rust_4_9_5};
 
+    #[test]
+    fn test_raw_pointer() {
+        rust_4_9_1::create_raw_pointer();
+        rust_4_9_1::deref_raw_pointer();
+        rust_4_9_1::pointer_arithmetic();
 
-#[test]
-pub fn asm(){
+        assert_eq!(rust_4_9_1::sum_by_pointer(&[]), 0);
+        assert_eq!(rust_4_9_1::sum_by_pointer(&[i32::MAX, 1]), i32::MIN);
+        for len in 0..6 {
+            let mut values: Vec<String> = (0..len).map(|i| i.to_string()).collect();
+            let mut expected = values.clone();
+            expected.reverse();
+            rust_4_9_1::reverse(&mut values);
+            assert_eq!(values, expected);
+        }
+        let values = [1u8, 2, 3];
+        let other = [4u8];
+        assert_eq!(rust_4_9_1::distance(&values, &values[2], &values[0]), Some(-2));
+        assert_eq!(rust_4_9_1::distance(&values, &values[0], &other[0]), None);
+    }
 
+    #[test]
+    fn test_unsafe_function() {
+        rust_4_9_2::unsafe_function();
+        rust_4_9_2::safe_abstraction();
 
-}
\ No newline at end of file
+        let mut values: Vec<Box<i32>> = (0..5).map(Box::new).collect();
+        for mid in 0..=5 {
+            let (left, right) = rust_4_9_2::split_at_mut(&mut values, mid);
+            assert_eq!((left.len(), right.len()), (mid, 5 - mid));
+            for v in left.iter_mut().chain(right.iter_mut()) {
+                **v += 1;
+            }
+        }
+        assert_eq!(values.iter().map(|v| **v).collect::<Vec<_>>(), [6, 7, 8, 9, 10]);
+        assert_eq!(rust_4_9_2::get_checked(&[1, 2], 1), Some(2));
+        assert_eq!(rust_4_9_2::get_checked::<i32>(&[], 0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "mid 4 超出了切片长度 3")]
+    fn test_split_at_mut_out_of_range() {
+        rust_4_9_2::split_at_mut(&mut [1, 2, 3], 4);
+    }
+
+    #[test]
+    fn test_compare_callback() {
+        // 回调函数本身是纯 Rust 代码，可以在 Miri 下检查
+        let (a, b) = (1i32, 2i32);
+        let cmp = |x: &i32, y: &i32| rust_4_9_3::compare_i32(x as *const i32 as *const _, y as *const i32 as *const _);
+        assert_eq!((cmp(&a, &b), cmp(&b, &a), cmp(&a, &a)), (-1, 1, 0));
+        assert_eq!(rust_4_9_3::rust_course_add(i32::MAX, 1), i32::MIN);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri 无法执行 C 代码")]
+    fn test_ffi() {
+        rust_4_9_3::call_libc();
+        rust_4_9_3::export_to_c();
+
+        for s in ["", "a", "hello", "中文"] {
+            assert_eq!(rust_4_9_3::c_strlen(&CString::new(s).unwrap()), s.len());
+        }
+        for n in [0, 1, -1, 42, -42, i32::MAX, i32::MIN + 1] {
+            assert_eq!(rust_4_9_3::c_abs(n), Some(n.abs()));
+        }
+        assert_eq!(rust_4_9_3::c_abs(i32::MIN), None);
+
+        let mut values: Vec<i32> = (0..100).map(|i| (i * 37 % 101) - 50).collect();
+        let mut expected = values.clone();
+        expected.sort();
+        rust_4_9_3::c_sort(&mut values);
+        assert_eq!(values, expected);
+        rust_4_9_3::c_sort(&mut []);
+        assert_eq!(rust_4_9_3::c_getpid(), std::process::id());
+    }
+
+    #[test]
+    fn test_static_mut() {
+        rust_4_9_4::static_mut();
+        let table = rust_4_9_4::popcount_table();
+        for (i, &n) in table.iter().enumerate() {
+            assert_eq!(n as u32, (i as u8).count_ones());
+        }
+        // 多个线程同时触发初始化
+        let counts: Vec<u32> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4).map(|_| s.spawn(|| rust_4_9_4::count_ones(&[0xff, 0x0f, 0x01]))).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(counts, [13; 4]);
+        let before = rust_4_9_4::record_call();
+        assert!(rust_4_9_4::record_call() > before);
+    }
+
+    #[test]
+    fn test_unsafe_trait() {
+        rust_4_9_4::unsafe_trait();
+        for (len, threads) in [(0, 3), (1, 4), (7, 3), (8, 8), (5, 0)] {
+            let mut values = vec![String::from("old"); len];
+            rust_4_9_4::parallel_fill(&mut values, threads, |i| i.to_string());
+            let expected: Vec<String> = (0..len).map(|i| i.to_string()).collect();
+            assert_eq!(values, expected);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri 不支持内联汇编")]
+    pub fn asm() {
+        rust_4_9_5::asm_intro();
+        rust_4_9_5::register_operands();
+        rust_4_9_5::rdtsc_lesson();
+        rust_4_9_5::cpuid_lesson();
+        rust_4_9_5::global_asm_lesson();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri 不支持内联汇编")]
+    fn test_asm_arithmetic() {
+        let samples = [0, 1, 2, 7, 1 << 32, u64::MAX / 3, u64::MAX - 1, u64::MAX];
+        for a in samples {
+            assert_eq!(rust_4_9_5::mul6(a), a.wrapping_mul(6));
+            assert_eq!(rust_4_9_5::call_triple(a), a.wrapping_mul(3));
+            for b in samples {
+                assert_eq!(rust_4_9_5::add(a, b), a.wrapping_add(b));
+                let wide = a as u128 * b as u128;
+                assert_eq!(rust_4_9_5::mul_wide(a, b), ((wide >> 64) as u64, wide as u64));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri 不支持内联汇编")]
+    fn test_global_asm_gcd() {
+        fn gcd(a: u64, b: u64) -> u64 {
+            if b == 0 { a } else { gcd(b, a % b) }
+        }
+        let samples = [0, 1, 6, 18, 48, 1071, 462, 1 << 40, u64::MAX];
+        for a in samples {
+            for b in samples {
+                assert_eq!(rust_4_9_5::gcd(a, b), gcd(a, b), "gcd({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri 不支持内联汇编")]
+    fn test_rdtsc() {
+        #[cfg(target_arch = "x86_64")]
+        {
+            use std::arch::x86_64::_rdtsc;
+            let before = unsafe { _rdtsc() };
+            let value = rust_4_9_5::rdtsc();
+            let after = unsafe { _rdtsc() };
+            assert!(before <= value && value <= after, "{} {} {}", before, value, after);
+        }
+        let a = rust_4_9_5::rdtsc();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        assert!(rust_4_9_5::rdtsc() > a);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri 不支持内联汇编")]
+    fn test_cpuid() {
+        #[cfg(target_arch = "x86_64")]
+        {
+            use std::arch::x86_64::__cpuid_count;
+            for (leaf, sub_leaf) in [(0, 0), (1, 0), (7, 0)] {
+                let expected = __cpuid_count(leaf, sub_leaf);
+                let r = rust_4_9_5::cpuid(leaf, sub_leaf);
+                // leaf 1 的 ebx 高 8 位是当前核心的 APIC ID，线程可能在两次调用之间被调度到其它核心上
+                let ebx_mask = if leaf == 1 { 0x00ff_ffff } else { u32::MAX };
+                assert_eq!(
+                    (r.eax, r.ebx & ebx_mask, r.ecx, r.edx),
+                    (expected.eax, expected.ebx & ebx_mask, expected.ecx, expected.edx),
+                    "leaf {}", leaf
+                );
+            }
+            let features = rust_4_9_5::features();
+            assert_eq!(features.sse2, is_x86_feature_detected!("sse2"));
+            assert_eq!(features.sse3, is_x86_feature_detected!("sse3"));
+            assert_eq!(features.ssse3, is_x86_feature_detected!("ssse3"));
+            assert_eq!(features.sse4_1, is_x86_feature_detected!("sse4.1"));
+            assert_eq!(features.sse4_2, is_x86_feature_detected!("sse4.2"));
+            assert_eq!(features.popcnt, is_x86_feature_detected!("popcnt"));
+            let vendor = rust_4_9_5::cpu_vendor().unwrap();
+            assert_eq!(vendor.len(), 12);
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        {
+            assert_eq!(rust_4_9_5::cpu_vendor(), None);
+            assert_eq!(rust_4_9_5::features(), rust_4_9_5::Features::default());
+        }
+    }
+}
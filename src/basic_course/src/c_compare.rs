@@ -0,0 +1,70 @@
+//! # 与 C 语言对比
+//! 很多 Rust 的设计只有放在 C 的旁边才看得清楚：结构体的内存布局、字符串的结尾、悬垂指针……
+//! 本模块借助 inline-c 包，用本机的 C 编译器编译并运行一小段 C 程序，把它的输出与对应的 Rust 代码的输出并排打印出来。
+//!
+//! C 程序写在 inline_c::assert_c! 宏中，每段程序都用 `#inline_c_rs CFLAGS: "-Wall -Wextra -Werror"` 开启警告并把警告当作错误。
+//! 对比课程分别放在各自章节中，例如 struct_type::compare_struct_layout、string_slice::compare_nul_terminated、ownership::compare_use_after_free。
+
+use std::panic::UnwindSafe;
+use lesson_runtime::isolate::observe;
+use crate::rust_2_14_formatted_output::table::Table;
+
+/// C 程序的运行结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct COutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// 进程的退出码，被信号终止时为 None
+    pub code: Option<i32>,
+}
+
+impl COutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// 运行 assert_c! 生成的程序，例如 run(|| assert_c! { ... })。
+/// 找不到 C 编译器等错误时 assert_c! 会 panic，这里把它转换成 Err；
+/// C 代码编译失败时 inline-c 返回的是编译命令本身，此时 stderr 中是编译器的报错，退出码不为 0
+pub fn run(program: impl FnOnce() -> inline_c::Assert + UnwindSafe) -> Result<COutput, String> {
+    let mut program = observe(program).map_err(|e| format!("无法编译 C 程序: {}", e))?;
+    let output = program.assert().get_output().clone();
+    Ok(COutput {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        code: output.status.code(),
+    })
+}
+
+/// 把两段输出按行并排放进表格，行数不同时用空白补齐
+pub fn side_by_side(rust: &str, c: &str) -> Table {
+    let mut table = Table::new(vec!["Rust", "C"]);
+    let (rust, c): (Vec<&str>, Vec<&str>) = (rust.lines().collect(), c.lines().collect());
+    for i in 0..rust.len().max(c.len()) {
+        table.row(vec![rust.get(i).copied().unwrap_or(""), c.get(i).copied().unwrap_or("")]);
+    }
+    table
+}
+
+/// 用 -fsanitize=address 编译的程序出错时，从 AddressSanitizer 的报告中取出错误的种类，例如 heap-use-after-free。
+/// 报告中的地址和进程号每次运行都不同，课程只打印种类
+pub fn sanitizer_error(stderr: &str) -> Option<&str> {
+    let (_, rest) = stderr.split_once("ERROR: AddressSanitizer: ")?;
+    rest.split_whitespace().next()
+}
+
+/// 打印对比结果，C 程序无法运行时只打印 Rust 的输出
+pub fn print_comparison(rust: &str, c: &Result<COutput, String>) {
+    match c {
+        Ok(c) if c.success() => println!("{:#}", side_by_side(rust, &c.stdout)),
+        Ok(c) => {
+            println!("{:#}", side_by_side(rust, &c.stdout));
+            println!("C 程序的退出码: {:?}\n{}", c.code, c.stderr);
+        }
+        Err(e) => {
+            println!("{}", rust);
+            println!("{}", e);
+        }
+    }
+}
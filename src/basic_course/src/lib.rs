@@ -28,6 +28,8 @@ pub mod rust_2_12_crate_module;
 pub mod rust_2_13_comment;
 #[allow(unused)]
 pub mod rust_2_14_formatted_output;
+pub mod c_compare;
+pub mod utf8;
 
 
 pub fn add() {}
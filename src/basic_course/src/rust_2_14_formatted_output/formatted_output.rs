@@ -0,0 +1,219 @@
+//! # 格式化输出
+//! 提到格式化输出，可能很多人立刻就想到宏 println!，它的功能非常强大，但是格式化输出远不止 println! 一个：
+//! + print! 将格式化文本输出到标准输出，不带换行符
+//! + println! 同上，但是在行的末尾添加换行符
+//! + format! 将格式化文本输出到 String 字符串
+//! + eprint!、eprintln! 输出到标准错误输出 stderr
+//!
+//! 它们背后都是 format_args! 和 std::fmt::Formatter，本章最后用这些知识实现了一个小小的表格库，见 [table](super::table)。
+
+use std::fmt;
+use std::fmt::Write;
+use common::{inner_print, outer_print};
+use crate::rust_2_7_method::method::{Circle, Rectangle};
+use super::table::{Align, Table};
+
+#[outer_print("print!，println!，format!")]
+pub fn print_macros() {
+    let s = "hello";
+    println!("{}, world", s);
+    let s1 = format!("{}, world", s);
+    print!("{}", s1);
+    println!("!");
+    // eprintln! 输出到 stderr，仅应该用来输出错误信息和进度信息，其它场景都应该使用 print! 系列
+    eprintln!("Error: Could not complete task");
+    assert_eq!(s1, "hello, world");
+}
+
+#[outer_print("{} 与 {:?}")]
+/// 与其它语言常用的 %d，%s 不同，Rust 特立独行地选择了 {} 作为格式化占位符：
+/// + {} 适用于实现了 std::fmt::Display 特征的类型，用来以更优雅、更友好的方式格式化文本，例如展示给用户
+/// + {:?} 适用于实现了 std::fmt::Debug 特征的类型，用于调试场景
+/// + {:#?} 与 {:?} 几乎一样，唯一的区别在于它能更优美地输出内容
+pub fn placeholder() {
+    let v = vec![1, 2, 3];
+    assert_eq!(format!("{:?}", v), "[1, 2, 3]");
+    assert_eq!(format!("{:#?}", (1, "a")), "(\n    1,\n    \"a\",\n)");
+    // 字符串的 Debug 输出会带上引号并转义特殊字符
+    assert_eq!(format!("{:?}", "中\n"), "\"中\\n\"");
+
+    inner_print!("位置参数");
+    assert_eq!(format!("{1}{0}", 1, 2), "21");
+    assert_eq!(format!("{1}{}{0}{}", 1, 2), "2112");
+
+    inner_print!("具名参数");
+    assert_eq!(format!("{argument}", argument = "test"), "test");
+    assert_eq!(format!("{name} {}", 1, name = 2), "2 1");
+    // 具名参数必须放在其它参数的后面，下面的代码无法编译：
+    // println!("{abc} {1}", abc = "def", 2);
+
+    inner_print!("捕获环境中的值");
+    // Rust 1.58 起，可以直接在占位符里使用作用域中的变量
+    let person = "Rust";
+    assert_eq!(format!("Hello, {person}!"), "Hello, Rust!");
+}
+
+#[outer_print("宽度、填充与对齐")]
+/// 宽度用来指示输出目标的长度，如果长度不够，则进行填充和补齐：
+/// + 字符串默认左对齐，数字默认右对齐
+/// + < 左对齐，^ 居中，> 右对齐，对齐符号前面可以加一个填充字符
+/// + 宽度可以用 w$ 引用具名参数，或用 1$ 引用位置参数
+pub fn width_fill_align() {
+    inner_print!("字符串填充");
+    assert_eq!(format!("Hello {:5}!", "x"), "Hello x    !");
+    assert_eq!(format!("Hello {:1$}!", "x", 5), "Hello x    !");
+    assert_eq!(format!("Hello {1:0$}!", 5, "x"), "Hello x    !");
+    assert_eq!(format!("Hello {:width$}!", "x", width = 5), "Hello x    !");
+
+    inner_print!("数字填充");
+    assert_eq!(format!("Hello {:5}!", 5), "Hello     5!");
+    assert_eq!(format!("Hello {:+}!", 5), "Hello +5!");
+    assert_eq!(format!("Hello {:05}!", 5), "Hello 00005!");
+    assert_eq!(format!("Hello {:05}!", -5), "Hello -0005!");
+
+    inner_print!("对齐");
+    assert_eq!(format!("Hello {:<5}!", "x"), "Hello x    !");
+    assert_eq!(format!("Hello {:^5}!", "x"), "Hello   x  !");
+    assert_eq!(format!("Hello {:>5}!", "x"), "Hello     x!");
+    assert_eq!(format!("Hello {:&<5}!", "x"), "Hello x&&&&!");
+    assert_eq!(format!("{:*^9}", "中"), "****中****");
+    // 宽度按 char 的个数计算，而不是终端里显示的列数
+    println!("[{:>4}]", "中国");
+    println!("[{:>4}]", "ab");
+}
+
+#[outer_print("精度、符号与进制")]
+/// + 精度可以用于控制浮点数的精度或者字符串的长度
+/// + + 会显示正数的正号，# 会给进制输出加上 0x、0b、0o 前缀
+/// + {:e} 以科学计数法输出
+pub fn precision_sign_radix() {
+    let v = std::f64::consts::PI;
+    inner_print!("精度");
+    assert_eq!(format!("{:.2}", v), "3.14");
+    assert_eq!(format!("{:+.2}", v), "+3.14");
+    assert_eq!(format!("{:.0}", v), "3");
+    // .* 从参数列表中取两个值，第一个是精度，第二个是被格式化的值
+    assert_eq!(format!("{:.*}", 3, v), "3.142");
+    assert_eq!(format!("{:.1$}", v, 4), "3.1416");
+    // 宽度和精度可以一起使用
+    assert_eq!(format!("{:08.3}", -v), "-003.142");
+    // 对于字符串，精度表示截取的字符个数
+    let s = "hi我是Sunface孙飞";
+    assert_eq!(format!("{:.3}", s), "hi我");
+    assert_eq!(format!("Hello {:.*}!", 3, "abcdefg"), "Hello abc!");
+
+    inner_print!("进制");
+    assert_eq!(format!("{:#b}", 27), "0b11011");
+    assert_eq!(format!("{:#o}", 27), "0o33");
+    assert_eq!(format!("{}", 27), "27");
+    assert_eq!(format!("{:x}", 27), "1b");
+    assert_eq!(format!("{:#x}", 27), "0x1b");
+    assert_eq!(format!("{:#X}", 27), "0x1B");
+    // 带前缀时，0 填充的宽度把前缀也算在内
+    assert_eq!(format!("{:#010b}", 27), "0b00011011");
+    assert_eq!(format!("{:#06x}", 255u8), "0x00ff");
+    // 负数的十六进制输出的是补码
+    assert_eq!(format!("{:#x}", -1i8), "0xff");
+
+    inner_print!("指数");
+    assert_eq!(format!("{:e}", 1000000000), "1e9");
+    assert_eq!(format!("{:E}", 1.5e-7), "1.5E-7");
+
+    inner_print!("指针地址");
+    let v = Box::new(5);
+    println!("{:p}", v);
+}
+
+#[outer_print("format_args!")]
+/// print!、format!、write! 最终都会先调用 format_args!，它把格式化字符串和参数打包成一个 fmt::Arguments，
+/// 整个过程不会分配堆内存，真正的输出推迟到 Arguments 被写入某个目标(stdout、String、文件)的时候才发生。
+pub fn format_args_() {
+    // fmt::Arguments 实现了 Display，因此可以被当成普通的值传来传去
+    fn log(level: &str, args: fmt::Arguments) -> String {
+        format!("[{:<5}] {}", level, args)
+    }
+    let line = log("INFO", format_args!("{} + {} = {}", 1, 2, 1 + 2));
+    assert_eq!(line, "[INFO ] 1 + 2 = 3");
+
+    // 任何实现了 fmt::Write 的类型都能通过 write_fmt 接收 Arguments，write! 就是它的语法糖
+    let mut buf = String::new();
+    buf.write_fmt(format_args!("{:>3}|", 7)).unwrap();
+    write!(buf, "{:<3}|", 8).unwrap();
+    assert_eq!(buf, "  7|8  |");
+    println!("{}", buf);
+
+    // 由于 Arguments 借用了参数，它不能离开创建它的语句，下面的代码无法编译：
+    // let args = format_args!("{}", String::from("temp"));
+    // println!("{}", args);
+}
+
+/// 为矩形实现 Display，f.pad 会帮我们处理宽度、填充和对齐
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("矩形 {}×{}", self.width, self.height))
+    }
+}
+
+/// 为圆实现 Display，并通过 f.precision() 支持 {:.2} 这样的精度控制
+impl fmt::Display for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "圆 ({:.p$}, {:.p$}) r={:.p$}", self.x, self.y, self.radius, p = p),
+            None => write!(f, "圆 ({}, {}) r={}", self.x, self.y, self.radius),
+        }
+    }
+}
+
+/// 手动实现 Debug，debug_struct 会自动处理 {:?} 和 {:#?} 两种输出
+impl fmt::Debug for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Circle")
+            .field("center", &(self.x, self.y))
+            .field("radius", &self.radius)
+            .finish()
+    }
+}
+
+#[outer_print("为自定义类型实现 Display")]
+/// 如果你的类型是定义在当前作用域中的，那么可以为其实现 Display 特征，即可用于格式化输出：
+/// + 只需要实现 fmt 方法，把内容写进 Formatter 即可
+/// + 写入时可以读取 f.width()、f.precision()、f.alternate() 等格式参数，让自定义类型也支持 {:>10}、{:.2}、{:#}
+/// + 如果类型不在当前作用域，例如 Vec，需要使用 newtype 模式包装一层，见特征章节
+pub fn custom_display() {
+    let rect = Rectangle::new(30, 50);
+    let circle = Circle::new(1.0, 2.0, 3.5);
+    println!("{}", rect);
+    println!("{}", circle);
+    assert_eq!(format!("{}", rect), "矩形 30×50");
+    assert_eq!(format!("[{:>10}]", rect), "[  矩形 30×50]");
+    assert_eq!(format!("{}", circle), "圆 (1, 2) r=3.5");
+    assert_eq!(format!("{:.2}", circle), "圆 (1.00, 2.00) r=3.50");
+}
+
+#[outer_print("手动实现 Debug")]
+/// 大多数时候 #[derive(Debug)] 就够用了，但当需要隐藏或者合并某些字段时，可以借助 Formatter 提供的 debug_struct、debug_tuple、debug_list、debug_map 手动实现：
+pub fn custom_debug() {
+    let circle = Circle::new(1.0, 2.0, 3.5);
+    assert_eq!(
+        format!("{:?}", circle),
+        "Circle { center: (1.0, 2.0), radius: 3.5 }"
+    );
+    println!("{:#?}", circle);
+    // 派生出来的 Debug 与手写的一样支持 {:#?}
+    assert_eq!(
+        format!("{:#?}", Rectangle::new(30, 50)),
+        "Rectangle {\n    width: 30,\n    height: 50,\n}"
+    );
+}
+
+#[outer_print("格式化输出一张表格")]
+/// 把上面的知识组合起来，就能写出一个对齐输出表格的小工具，表格本身实现了 Display：{} 输出简洁样式，{:#} 输出带边框的样式
+pub fn table_output() {
+    let mut table = Table::new(["图形", "描述", "面积"]).align(2, Align::Right);
+    let rect = Rectangle::new(30, 50);
+    let circle = Circle::new(1.0, 2.0, 3.5);
+    table.row(["矩形".to_string(), rect.to_string(), format!("{:.2}", (rect.width * rect.height) as f64)]);
+    table.row(["圆".to_string(), format!("{:.1}", circle), format!("{:.2}", circle.area())]);
+    println!("{}", table);
+    println!("{:#}", table);
+}
@@ -0,0 +1,2 @@
+pub mod formatted_output;
+pub mod table;
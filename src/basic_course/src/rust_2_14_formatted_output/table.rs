@@ -0,0 +1,172 @@
+//! # 表格输出
+//! 一个很小的表格渲染库，本章的格式化技巧几乎都能在这里找到：
+//! + `{:<w$}` / `{:>w$}` / `{:^w$}` 控制宽度与对齐
+//! + `{:-<w$}` 用 `-` 作为填充字符画分隔线
+//! + `{:#}` 通过 `Formatter::alternate()` 切换成带边框的样式
+//!
+//! 中文、日文等宽字符在终端里占两列，而 `{:w$}` 只按 char 的个数补齐，所以渲染前会先计算每个单元格的显示宽度，再修正传给 `width$` 的值。
+
+use std::fmt;
+
+/// 单元格的对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// 表格：一行表头加若干行数据
+/// ```
+/// use basic_course::rust_2_14_formatted_output::table::{Align, Table};
+///
+/// let mut table = Table::new(["类型", "位数"]).align(1, Align::Right);
+/// table.row(["u8", "8"]);
+/// table.row(["i128", "128"]);
+/// assert_eq!(
+///     table.to_string(),
+///     "类型 | 位数\n-----+-----\nu8   |    8\ni128 |  128\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Table {
+    headers: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// 用表头创建表格，所有列默认左对齐
+    pub fn new<I, S>(headers: I) -> Table
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        let headers: Vec<String> = headers.into_iter().map(|h| h.to_string()).collect();
+        let aligns = vec![Align::Left; headers.len()];
+        Table {
+            headers,
+            aligns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// 设置某一列的对齐方式
+    pub fn align(mut self, column: usize, align: Align) -> Table {
+        self.aligns[column] = align;
+        self
+    }
+
+    /// 追加一行，单元格不足的部分补空，多出的部分丢弃
+    pub fn row<I, S>(&mut self, cells: I) -> &mut Table
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        let mut row: Vec<String> = cells.into_iter().map(|c| c.to_string()).collect();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+        self
+    }
+
+    /// 每一列的显示宽度，取该列所有单元格中的最大值
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| display_width(h)).collect();
+        for row in &self.rows {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(display_width(cell));
+            }
+        }
+        widths
+    }
+
+    fn write_row(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        cells: &[String],
+        widths: &[usize],
+        boxed: bool,
+    ) -> fmt::Result {
+        if boxed {
+            f.write_str("| ")?;
+        }
+        for (i, cell) in cells.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" | ")?;
+            }
+            // width$ 按 char 个数补齐，宽字符要少补一格
+            let w = widths[i] - (display_width(cell) - cell.chars().count());
+            let last = i + 1 == cells.len();
+            match self.aligns[i] {
+                // 最后一列左对齐时不补尾随空格
+                Align::Left if last && !boxed => f.write_str(cell)?,
+                Align::Left => write!(f, "{:<w$}", cell, w = w)?,
+                Align::Right => write!(f, "{:>w$}", cell, w = w)?,
+                Align::Center => write!(f, "{:^w$}", cell, w = w)?,
+            }
+        }
+        if boxed {
+            f.write_str(" |")?;
+        }
+        f.write_str("\n")
+    }
+
+    fn write_rule(&self, f: &mut fmt::Formatter<'_>, widths: &[usize], boxed: bool) -> fmt::Result {
+        if boxed {
+            f.write_str("+-")?;
+        }
+        for (i, w) in widths.iter().enumerate() {
+            if i > 0 {
+                f.write_str("-+-")?;
+            }
+            write!(f, "{:-<w$}", "", w = w)?;
+        }
+        if boxed {
+            f.write_str("-+")?;
+        }
+        f.write_str("\n")
+    }
+}
+
+impl fmt::Display for Table {
+    /// `{}` 输出简洁样式，`{:#}` 输出带边框的样式
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.widths();
+        let boxed = f.alternate();
+        if boxed {
+            self.write_rule(f, &widths, boxed)?;
+        }
+        self.write_row(f, &self.headers, &widths, boxed)?;
+        self.write_rule(f, &widths, boxed)?;
+        for row in &self.rows {
+            self.write_row(f, row, &widths, boxed)?;
+        }
+        if boxed && !self.rows.is_empty() {
+            self.write_rule(f, &widths, boxed)?;
+        }
+        Ok(())
+    }
+}
+
+/// 字符串在等宽终端中占用的列数：东亚宽字符和 emoji 记 2 列，其余记 1 列
+pub fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if is_wide(c) { 2 } else { 1 }).sum()
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD)
+}
@@ -103,6 +103,7 @@ pub fn destructuring_assignments() {
 /// ## 与不可变变量一样，常量也是绑定到一个常量名且不允许更改的值，但是常量和变量之间存在一些差异：
 /// + 常量不允许使用 mut。常量不仅仅默认不可变，而且自始至终不可变，因为常量在编译完成后，已经确定它的值。
 /// + 常量使用 const 关键字而不是 let 关键字来声明，并且值的类型必须标注。
+/// + 需要在运行时才能初始化、或者需要修改的全局值要用 static、OnceLock 和 LazyLock，见进阶课程的 4.7 全局变量。
 #[outer_print("解构式赋值")]
 pub fn diff_var_const() {
     ///下面是一个常量声明的例子，其常量名为 MAX_POINTS，值设置为 100,000。（Rust 常量的命名约定是全部字母都使用大写，并使用下划线分隔单词，另外对数字字面量可插入下划线以提高可读性）：
@@ -66,17 +66,6 @@ pub fn function_non_return() {
 /// 表示该函数不能正常返回
 #[outer_print("永不返回的发散函数")]
 pub fn function_diverge() {
-    fn dead_end() -> ! {
-        panic!("你已经到了穷途末路，崩溃吧！");
-    }
-
-    fn forever() -> ! {
-        let mut x=1;
-        loop {
-            x+=1;
-            println!("loop_");
-        };
-    }
     fn f(a:i32,b:i32)->bool{
         if a > b {
             true
@@ -98,6 +87,30 @@ pub fn function_diverge() {
     }
     let a:() = bar();
     println!("在此可以看到函数 bar 的返回 {:?}",a);
-    // dead_end();
-    // forever();
+    // dead_end() 和 forever() 会让程序崩溃或者卡住，放在下面单独的课程中，在子进程里执行：
+    // cargo run -- check diverge --timeout 1000
+}
+
+pub fn dead_end() -> ! {
+    panic!("你已经到了穷途末路，崩溃吧！");
+}
+
+pub fn forever() -> ! {
+    let mut x = 1u64;
+    loop {
+        x = x.wrapping_add(1);
+        println!("loop_{}", x);
+    }
+}
+
+/// panic 也是一种发散：dead_end 永远不会返回，这节课也就不会正常结束，[end] 依然会由 Guard 输出
+#[outer_print("发散函数 panic")]
+pub fn diverge_panic() {
+    dead_end();
+}
+
+/// 死循环同样永远不会返回，只能由外部终止，在子进程中执行时会超时
+#[outer_print("发散函数死循环")]
+pub fn diverge_forever() {
+    forever();
 }
\ No newline at end of file
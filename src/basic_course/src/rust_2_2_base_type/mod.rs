@@ -1,4 +1,5 @@
 pub mod numbers;
+pub mod overflow;
 pub mod char_bool;
 pub mod statement_expression;
 pub mod function;
@@ -2,7 +2,13 @@
 //! Rust 使用一个相对传统的语法来创建整数（1，2，...）和浮点数（1.0，1.1，...）。整数、浮点数的运算和你在其它语言上见过的一致，都是通过常见的运算符来完成。
 //! 不仅仅是数值类型，Rust 也允许在复杂类型上定义运算符，例如在自定义类型上定义 + 运算符，这种行为被称为运算符重载，Rust 具体支持的可重载运算符见附录 B。
 
+use std::cmp::Ordering;
+use num::bigint::BigUint;
+use num::complex::Complex;
+use num::rational::{BigRational, Ratio};
+use num::{BigInt, One, Zero};
 use common::{inner_print, outer_print};
+use crate::rust_2_14_formatted_output::table::{Align, Table};
 
 
 
@@ -35,6 +41,9 @@ use common::{inner_print, outer_print};
 /// + 使用 saturating_* 方法使值达到最小值或最大值
 #[outer_print("整数类型")]
 pub fn int_type() {
+    // 上面的整数类型表，用格式化输出章节的表格库打印出来：
+    println!("{}", int_type_table());
+
     let a: u8 = 255;
     let b = a.wrapping_add(200);
     println!("{}", b);  // 199
@@ -47,6 +56,50 @@ pub fn int_type() {
 
     let b = a.saturating_add(100);
     println!("{:?}", b);  // 255
+    // 更多类型和运算在边界值上的表现，见下一节的整型溢出探索 overflow::overflow_explorer
+}
+
+/// # 整型溢出的默认行为
+/// 不使用上面的方法，直接用运算符计算 200 + 100：
+/// + dev 构建（cargo run）检查溢出，程序 panic：attempt to add with overflow
+/// + release 构建（cargo run --release）不检查溢出，得到回绕后的 44
+///
+/// 操作数如果是字面量，编译器在编译期就能发现溢出并直接报错 arithmetic_overflow，所以这里用 black_box 把它们变成运行时的值。
+/// 用 `cargo run -- profiles overflow_default` 可以分别构建 dev 和 release 并对比这节课的结果。
+#[outer_print("整型溢出的默认行为", profile_dependent)]
+pub fn overflow_default() {
+    let a: u8 = std::hint::black_box(200);
+    let b: u8 = std::hint::black_box(100);
+    println!("{} + {} 超出了 u8 的范围", a, b);
+    let sum = a + b;
+    println!("{} + {} = {}", a, b, sum);
+}
+
+/// Rust 内置整数类型的位数和取值范围，数值列右对齐
+pub fn int_type_table() -> Table {
+    let mut table = Table::new(["长度", "有符号类型", "最小值", "最大值", "无符号类型", "最大值"])
+        .align(2, Align::Right)
+        .align(3, Align::Right)
+        .align(5, Align::Right);
+    macro_rules! int_row {
+        ($len:expr, $i:ty, $u:ty) => {
+            table.row([
+                $len.to_string(),
+                stringify!($i).to_string(),
+                <$i>::MIN.to_string(),
+                <$i>::MAX.to_string(),
+                stringify!($u).to_string(),
+                <$u>::MAX.to_string(),
+            ]);
+        };
+    }
+    int_row!("8 位", i8, u8);
+    int_row!("16 位", i16, u16);
+    int_row!("32 位", i32, u32);
+    int_row!("64 位", i64, u64);
+    int_row!("128 位", i128, u128);
+    int_row!(format!("视架构而定({} 位)", usize::BITS), isize, usize);
+    table
 }
 
 /// # 浮点类型
@@ -92,6 +145,15 @@ pub fn float_type() {
 
     assert_eq!(abc.0 + abc.1, abc.2);
     // assert_eq!(xyz.0 + xyz.1, xyz.2); //断言失败
+    // 多打印几位小数就能看出误差
+    println!("0.1 + 0.2 = {:.20}", xyz.0 + xyz.1);
+    println!("0.3       = {:.20}", xyz.2);
+    /// 如果一定要比较浮点数，应该比较两者之差是否小于某个可以接受的误差，例如 f64::EPSILON：
+    assert!((xyz.0 + xyz.1 - xyz.2).abs() < f64::EPSILON);
+    /// 如果需要精确的十进制运算，就不要用浮点数，可以使用 num 库中的有理数 Ratio，它用分子和分母表示一个数，0.1 就是 1/10：
+    let sum = Ratio::new(1_i64, 10) + Ratio::new(2, 10);
+    assert_eq!(sum, Ratio::new(3, 10));
+    println!("Ratio: 1/10 + 2/10 = {}", sum);
 
     /// ## NaN
     /// 对于数学上未定义的结果，例如对负数取平方根 -42.1.sqrt() ，会产生一个特殊的结果：Rust 的浮点数类型使用 NaN (not a number)来处理这些情况。
@@ -104,6 +166,23 @@ pub fn float_type() {
     if x.is_nan() {
         println!("未定义的数学行为")
     }
+    /// NaN 与任何值比较，包括它自己，结果都是 false，partial_cmp 返回 None，这就是浮点数只实现了 PartialEq、PartialOrd 的原因：
+    let nan = f64::NAN;
+    let same = nan;
+    println!("NaN == NaN: {}", nan == same);
+    println!("NaN < 1.0: {}, NaN > 1.0: {}, NaN != NaN: {}", nan < 1.0, nan > 1.0, nan != same);
+    println!("NaN.partial_cmp(1.0): {:?}", nan.partial_cmp(&1.0));
+    assert!(nan != same);
+    assert_eq!(nan.partial_cmp(&1.0), None);
+    // max、min 会忽略 NaN，返回另一个数
+    assert_eq!(nan.max(1.0), 1.0);
+    /// 因此不能直接用 sort() 给浮点数排序，需要用 total_cmp 提供的全序：负 NaN < -∞ < ... < -0.0 < 0.0 < ... < +∞ < NaN
+    let mut v = [2.5, f64::NAN, -1.0, f64::INFINITY, 0.0];
+    v.sort_by(|a, b| a.total_cmp(b));
+    println!("{:?}", v);
+    assert_eq!(v[..4], [-1.0, 0.0, 2.5, f64::INFINITY]);
+    assert!(v[4].is_nan());
+    assert_eq!(f64::NAN.total_cmp(&f64::NAN), Ordering::Equal);
 }
 
 ///# 数字运算
@@ -200,22 +279,54 @@ pub fn range() {
 /// + 有理数和复数
 /// + 任意大小的整数和任意精度的浮点数
 /// + 固定精度的十进制小数，常用于货币相关的场景
+///
+/// 好在社区已经开发出高质量的 Rust 数值库：num，在 Cargo.toml 中的 [dependencies] 下添加一行 num = "0.4.0" 即可使用
 #[outer_print("有理数和复数")]
 pub fn rational_complex() {
-    ///```
-    ///let a = Complex { re: 2.1, im: -1.2 };
-    ///    let b = Complex::new(11.1, 22.2);
-    ///    let result = a + b;
-    ///
-    ///   println!("{} + {}i", result.re, result.im)
-    /// ```
-    return;
+    inner_print!("复数");
+    let a = Complex { re: 2.1, im: -1.2 };
+    let b = Complex::new(11.1, 22.2);
+    let result = a + b;
+    println!("{} + {}i", result.re, result.im);
+    // Complex 实现了 Display，i * i = -1
+    let i = Complex::<i64>::i();
+    assert_eq!(i * i, Complex::new(-1, 0));
+    assert_eq!(Complex::new(1, 2) + Complex::new(3, -4), Complex::new(4, -2));
+    assert_eq!(Complex::new(3.0, 4.0).norm(), 5.0);
+    println!("(1+2i)(3-4i) = {}", Complex::new(1, 2) * Complex::new(3, -4));
+
+    inner_print!("有理数");
+    // Ratio 会自动约分
+    assert_eq!(Ratio::new(2_i64, 4), Ratio::new(1, 2));
+    assert_eq!((Ratio::new(1_i64, 3) * 3).to_integer(), 1);
+    // 浮点数的 0.1 其实是一个分母为 2 的幂的分数，from_float 能把它精确地还原出来
+    let tenth = BigRational::from_float(0.1).unwrap();
+    println!("0.1_f64 的精确值 = {}", tenth);
+    assert_ne!(tenth, BigRational::new(1.into(), 10.into()));
+    println!("H(10) = 1 + 1/2 + ... + 1/10 = {}", harmonic(10));
+
+    inner_print!("大整数");
+    // u64 最多只能存下 20!，再往上就溢出了
+    assert_eq!((1..=20_u64).product::<u64>(), 2432902008176640000);
+    assert_eq!((1..=21_u64).try_fold(1_u64, |acc, n| acc.checked_mul(n)), None);
+    println!("30! = {}", factorial(30));
+    println!("2^100 = {}", BigInt::from(2).pow(100));
+}
+
+/// 使用 BigUint 计算 n!，不会溢出
+pub fn factorial(n: u32) -> BigUint {
+    (1..=n).fold(BigUint::one(), |acc, i| acc * i)
+}
+
+/// 调和数 H(n) = 1 + 1/2 + ... + 1/n 的精确值
+pub fn harmonic(n: u32) -> BigRational {
+    (1..=n).fold(BigRational::zero(), |acc, i| acc + BigRational::new(BigInt::one(), i.into()))
 }
 
 /// # 总结
 /// 之前提到了过 Rust 的数值类型和运算跟其他语言较为相似，但是实际上，除了语法上的不同之外，还是存在一些差异点：
 /// + Rust 拥有相当多的数值类型. 因此你需要熟悉这些类型所占用的字节数，这样就知道该类型允许的大小范围以及你选择的类型是否能表达负数
-/// + 类型转换必须是显式的. Rust 永远也不会偷偷把你的 16bit 整数转换成 32bit 整数
+/// + 类型转换必须是显式的. Rust 永远也不会偷偷把你的 16bit 整数转换成 32bit 整数，as、From、TryFrom 等转换方式见进阶课程的 4.3 类型转换
 /// + Rust 的数值上可以使用方法. 例如你可以用以下方法来将 13.14 取整：13.14_f32.round()，在这里我们使用了类型后缀，因为编译器需要知道 13.14 的具体类型
 #[outer_print("总结")]
 pub fn summary() {}
@@ -0,0 +1,447 @@
+//! # 整型溢出
+//! 数值类型章节提到：debug 模式下整型溢出会 panic，release 模式下按补码循环溢出，
+//! 标准库还为每个整数类型提供了 wrapping_*、checked_*、overflowing_*、saturating_* 四组方法来显式地处理溢出。
+//!
+//! 本节把这些规则做成一个可以交互的小工具：任选一种整数类型和一种运算，在类型的边界值上把四种处理方式和直接使用运算符的结果并排列出来。
+//! 运算符那一列是在当前进程中真正执行出来的，debug 构建会看到 panic，release 构建会看到回绕后的值。
+//! 是否检查溢出实际上由 overflow-checks 配置决定，它默认跟随 debug_assertions，但可以单独打开或关闭，
+//! 所以表头显示的是运行时探测到的结果。`cargo run -- profiles overflow_explorer` 可以把 dev 和 release 的输出并排对比。
+//! 也可以通过命令行运行：`cargo run -- overflow i16 mul 300 200`
+
+use std::fmt;
+use std::panic::UnwindSafe;
+use std::str::FromStr;
+use common::{inner_print, outer_print};
+use lesson_runtime::isolate::observe;
+use crate::rust_2_14_formatted_output::table::{Align, Table};
+
+/// 支持的运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Shl,
+    Neg,
+    Abs,
+    Pow,
+}
+
+impl Op {
+    pub const ALL: [Op; 7] = [Op::Add, Op::Sub, Op::Mul, Op::Shl, Op::Neg, Op::Abs, Op::Pow];
+
+    /// 方法名中的运算名，例如 wrapping_add 中的 add
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Sub => "sub",
+            Op::Mul => "mul",
+            Op::Shl => "shl",
+            Op::Neg => "neg",
+            Op::Abs => "abs",
+            Op::Pow => "pow",
+        }
+    }
+
+    /// 一元运算只有左操作数
+    pub fn is_unary(self) -> bool {
+        matches!(self, Op::Neg | Op::Abs)
+    }
+
+    /// shl 和 pow 的右操作数是 u32
+    pub fn takes_u32(self) -> bool {
+        matches!(self, Op::Shl | Op::Pow)
+    }
+}
+
+impl FromStr for Op {
+    type Err = OverflowError;
+
+    fn from_str(s: &str) -> Result<Op, OverflowError> {
+        Op::ALL
+            .into_iter()
+            .find(|op| op.name() == s)
+            .ok_or_else(|| OverflowError::UnknownOp(s.to_string()))
+    }
+}
+
+/// 四种显式处理溢出的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Wrapping,
+    Checked,
+    Overflowing,
+    Saturating,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 4] = [Mode::Wrapping, Mode::Checked, Mode::Overflowing, Mode::Saturating];
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Mode::Wrapping => "wrapping",
+            Mode::Checked => "checked",
+            Mode::Overflowing => "overflowing",
+            Mode::Saturating => "saturating",
+        }
+    }
+}
+
+/// 一次运算的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// wrapping_*、saturating_* 以及没有溢出的运算符
+    Value(String),
+    /// checked_* 的结果
+    Checked(Option<String>),
+    /// overflowing_* 的结果
+    Overflowing(String, bool),
+    /// 运算符在当前构建中 panic 了，里面是 panic 信息
+    Panicked(String),
+    /// 该类型没有这个方法，例如无符号整数没有 abs，也没有 saturating_shl
+    Unsupported,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Value(v) => f.write_str(v),
+            Outcome::Checked(Some(v)) => write!(f, "Some({})", v),
+            Outcome::Checked(None) => f.write_str("None"),
+            Outcome::Overflowing(v, o) => write!(f, "({}, {})", v, o),
+            Outcome::Panicked(msg) => write!(f, "panic: {}", msg),
+            Outcome::Unsupported => f.write_str("-"),
+        }
+    }
+}
+
+/// 右操作数：一元运算没有，shl/pow 是 u32，其它运算与左操作数同类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rhs<T> {
+    None,
+    Same(T),
+    Bits(u32),
+}
+
+/// 运算表达式，例如 `127 + 1`、`-(-128)`、`2.pow(8)`
+pub fn expression<T: fmt::Display>(op: Op, lhs: T, rhs: Rhs<T>) -> String {
+    let rhs = match rhs {
+        Rhs::None => String::new(),
+        Rhs::Same(v) => v.to_string(),
+        Rhs::Bits(v) => v.to_string(),
+    };
+    match op {
+        Op::Add => format!("{} + {}", lhs, rhs),
+        Op::Sub => format!("{} - {}", lhs, rhs),
+        Op::Mul => format!("{} * {}", lhs, rhs),
+        Op::Shl => format!("{} << {}", lhs, rhs),
+        Op::Neg => format!("-({})", lhs),
+        Op::Abs => format!("({}).abs()", lhs),
+        Op::Pow => format!("({}).pow({})", lhs, rhs),
+    }
+}
+
+/// 可以被本工具探索的整数类型，为全部 12 种原生整数类型实现
+pub trait OverflowInt: Copy + fmt::Display + FromStr + UnwindSafe + 'static {
+    const NAME: &'static str;
+    const BITS: u32;
+    const SIGNED: bool;
+
+    /// 类型的边界值：MIN、-1、0、1、MAX 等，已去重
+    fn boundaries() -> Vec<Self>;
+
+    /// 使用某种方式计算，不会 panic
+    fn eval(self, op: Op, rhs: Rhs<Self>, mode: Mode) -> Outcome;
+
+    /// 直接使用运算符计算：是否 panic 取决于构建时是否开启了 overflow-checks
+    fn plain(self, op: Op, rhs: Rhs<Self>) -> Option<Self>;
+}
+
+macro_rules! binary {
+    ($lhs:ident, $rhs:ident, $mode:ident, $wrapping:ident, $checked:ident, $overflowing:ident, $saturating:ident) => {
+        match $mode {
+            Mode::Wrapping => Outcome::Value($lhs.$wrapping($rhs).to_string()),
+            Mode::Checked => Outcome::Checked($lhs.$checked($rhs).map(|v| v.to_string())),
+            Mode::Overflowing => {
+                let (v, o) = $lhs.$overflowing($rhs);
+                Outcome::Overflowing(v.to_string(), o)
+            }
+            Mode::Saturating => Outcome::Value($lhs.$saturating($rhs).to_string()),
+        }
+    };
+    // 没有 saturating 版本的运算，例如 shl
+    ($lhs:ident, $rhs:ident, $mode:ident, $wrapping:ident, $checked:ident, $overflowing:ident) => {
+        match $mode {
+            Mode::Wrapping => Outcome::Value($lhs.$wrapping($rhs).to_string()),
+            Mode::Checked => Outcome::Checked($lhs.$checked($rhs).map(|v| v.to_string())),
+            Mode::Overflowing => {
+                let (v, o) = $lhs.$overflowing($rhs);
+                Outcome::Overflowing(v.to_string(), o)
+            }
+            Mode::Saturating => Outcome::Unsupported,
+        }
+    };
+}
+
+macro_rules! unary {
+    ($lhs:ident, $mode:ident, $wrapping:ident, $checked:ident, $overflowing:ident, $saturating:ident) => {
+        match $mode {
+            Mode::Wrapping => Outcome::Value($lhs.$wrapping().to_string()),
+            Mode::Checked => Outcome::Checked($lhs.$checked().map(|v| v.to_string())),
+            Mode::Overflowing => {
+                let (v, o) = $lhs.$overflowing();
+                Outcome::Overflowing(v.to_string(), o)
+            }
+            Mode::Saturating => Outcome::Value($lhs.$saturating().to_string()),
+        }
+    };
+}
+
+/// 共用的二元运算部分，shl 和 pow 的右操作数是 u32
+macro_rules! eval_common {
+    ($lhs:ident, $op:ident, $rhs:ident, $mode:ident) => {
+        match ($op, $rhs) {
+            (Op::Add, Rhs::Same(r)) => binary!($lhs, r, $mode, wrapping_add, checked_add, overflowing_add, saturating_add),
+            (Op::Sub, Rhs::Same(r)) => binary!($lhs, r, $mode, wrapping_sub, checked_sub, overflowing_sub, saturating_sub),
+            (Op::Mul, Rhs::Same(r)) => binary!($lhs, r, $mode, wrapping_mul, checked_mul, overflowing_mul, saturating_mul),
+            (Op::Shl, Rhs::Bits(r)) => binary!($lhs, r, $mode, wrapping_shl, checked_shl, overflowing_shl),
+            (Op::Pow, Rhs::Bits(r)) => binary!($lhs, r, $mode, wrapping_pow, checked_pow, overflowing_pow, saturating_pow),
+            _ => Outcome::Unsupported,
+        }
+    };
+}
+
+macro_rules! plain_common {
+    ($lhs:ident, $op:ident, $rhs:ident) => {
+        match ($op, $rhs) {
+            (Op::Add, Rhs::Same(r)) => Some($lhs + r),
+            (Op::Sub, Rhs::Same(r)) => Some($lhs - r),
+            (Op::Mul, Rhs::Same(r)) => Some($lhs * r),
+            (Op::Shl, Rhs::Bits(r)) => Some($lhs << r),
+            (Op::Pow, Rhs::Bits(r)) => Some($lhs.pow(r)),
+            _ => None,
+        }
+    };
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl OverflowInt for $t {
+            const NAME: &'static str = stringify!($t);
+            const BITS: u32 = <$t>::BITS;
+            const SIGNED: bool = true;
+
+            fn boundaries() -> Vec<$t> {
+                vec![<$t>::MIN, <$t>::MIN + 1, -1, 0, 1, <$t>::MAX - 1, <$t>::MAX]
+            }
+
+            fn eval(self, op: Op, rhs: Rhs<$t>, mode: Mode) -> Outcome {
+                let lhs = self;
+                match op {
+                    Op::Neg => unary!(lhs, mode, wrapping_neg, checked_neg, overflowing_neg, saturating_neg),
+                    Op::Abs => unary!(lhs, mode, wrapping_abs, checked_abs, overflowing_abs, saturating_abs),
+                    _ => eval_common!(lhs, op, rhs, mode),
+                }
+            }
+
+            fn plain(self, op: Op, rhs: Rhs<$t>) -> Option<$t> {
+                let lhs = std::hint::black_box(self);
+                let rhs = std::hint::black_box(rhs);
+                match op {
+                    Op::Neg => Some(-lhs),
+                    Op::Abs => Some(lhs.abs()),
+                    _ => plain_common!(lhs, op, rhs),
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl OverflowInt for $t {
+            const NAME: &'static str = stringify!($t);
+            const BITS: u32 = <$t>::BITS;
+            const SIGNED: bool = false;
+
+            fn boundaries() -> Vec<$t> {
+                vec![0, 1, 2, <$t>::MAX / 2, <$t>::MAX - 1, <$t>::MAX]
+            }
+
+            fn eval(self, op: Op, rhs: Rhs<$t>, mode: Mode) -> Outcome {
+                let lhs = self;
+                match op {
+                    // 无符号整数有 wrapping_neg 等方法，但没有 saturating_neg
+                    Op::Neg => match mode {
+                        Mode::Saturating => Outcome::Unsupported,
+                        _ => unary!(lhs, mode, wrapping_neg, checked_neg, overflowing_neg, wrapping_neg),
+                    },
+                    // 无符号整数没有 abs
+                    Op::Abs => Outcome::Unsupported,
+                    _ => eval_common!(lhs, op, rhs, mode),
+                }
+            }
+
+            fn plain(self, op: Op, rhs: Rhs<$t>) -> Option<$t> {
+                let lhs = std::hint::black_box(self);
+                let rhs = std::hint::black_box(rhs);
+                // 无符号整数不能写 -x，也没有 x.abs()，编译都通不过
+                plain_common!(lhs, op, rhs)
+            }
+        }
+    )*};
+}
+
+impl_signed!(i8, i16, i32, i64, i128, isize);
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// 本工具的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverflowError {
+    UnknownType(String),
+    UnknownOp(String),
+    BadOperand(String),
+    MissingOperand,
+    TooManyOperands,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverflowError::UnknownType(t) => write!(f, "未知的整数类型: {}", t),
+            OverflowError::UnknownOp(op) => write!(f, "未知的运算: {}，可选 add/sub/mul/shl/neg/abs/pow", op),
+            OverflowError::BadOperand(v) => write!(f, "无法解析操作数: {}", v),
+            OverflowError::MissingOperand => f.write_str("缺少操作数"),
+            OverflowError::TooManyOperands => f.write_str("操作数太多"),
+        }
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+/// 当前构建是否检查整型溢出，即 overflow-checks 是否打开
+///
+/// cfg!(debug_assertions) 只反映 debug-assertions 配置，两者可以分别设置，这里直接做一次会溢出的加法来探测
+pub fn overflow_checks() -> bool {
+    observe(|| std::hint::black_box(u8::MAX) + std::hint::black_box(1)).is_err()
+}
+
+/// 运算符在当前进程中的实际结果
+pub fn plain_outcome<T: OverflowInt>(op: Op, lhs: T, rhs: Rhs<T>) -> Outcome {
+    match observe(move || lhs.plain(op, rhs)) {
+        Ok(Some(v)) => Outcome::Value(v.to_string()),
+        Ok(None) => Outcome::Unsupported,
+        Err(msg) => Outcome::Panicked(msg),
+    }
+}
+
+/// 边界值上的所有测试用例：一元运算取每个边界值，二元运算取边界值的两两组合，shl/pow 的右操作数取 0、1、BITS - 1、BITS
+pub fn boundary_cases<T: OverflowInt>(op: Op) -> Vec<(T, Rhs<T>)> {
+    let values = T::boundaries();
+    if op.is_unary() {
+        return values.into_iter().map(|v| (v, Rhs::None)).collect();
+    }
+    if op.takes_u32() {
+        let bits = [0, 1, T::BITS - 1, T::BITS];
+        return values
+            .into_iter()
+            .flat_map(|v| bits.into_iter().map(move |b| (v, Rhs::Bits(b))))
+            .collect();
+    }
+    values
+        .iter()
+        .flat_map(|&l| values.iter().map(move |&r| (l, Rhs::Same(r))))
+        .collect()
+}
+
+/// 把一组用例的结果渲染成表格：表达式、四种方法、直接使用运算符
+pub fn overflow_table<T: OverflowInt>(op: Op, cases: &[(T, Rhs<T>)]) -> Table {
+    let profile = if overflow_checks() { "检查溢出" } else { "不检查溢出" };
+    let mut headers = vec![format!("{} 表达式", T::NAME)];
+    headers.extend(Mode::ALL.iter().map(|m| format!("{}_{}", m.prefix(), op.name())));
+    headers.push(format!("运算符({})", profile));
+    let mut table = Table::new(headers).align(0, Align::Right);
+    for &(lhs, rhs) in cases {
+        let mut row = vec![expression(op, lhs, rhs)];
+        row.extend(Mode::ALL.iter().map(|&m| lhs.eval(op, rhs, m).to_string()));
+        row.push(plain_outcome(op, lhs, rhs).to_string());
+        table.row(row);
+    }
+    table
+}
+
+fn parse_operand<T: OverflowInt>(s: &str) -> Result<T, OverflowError> {
+    s.parse().map_err(|_| OverflowError::BadOperand(s.to_string()))
+}
+
+/// 按参数构造用例，没有给出操作数时使用边界值
+pub fn parse_cases<T: OverflowInt>(op: Op, operands: &[&str]) -> Result<Vec<(T, Rhs<T>)>, OverflowError> {
+    let arity = if op.is_unary() { 1 } else { 2 };
+    match operands.len() {
+        0 => return Ok(boundary_cases(op)),
+        n if n < arity => return Err(OverflowError::MissingOperand),
+        n if n > arity => return Err(OverflowError::TooManyOperands),
+        _ => {}
+    }
+    let lhs = parse_operand::<T>(operands[0])?;
+    let rhs = if op.is_unary() {
+        Rhs::None
+    } else if op.takes_u32() {
+        Rhs::Bits(operands[1].parse().map_err(|_| OverflowError::BadOperand(operands[1].to_string()))?)
+    } else {
+        Rhs::Same(parse_operand(operands[1])?)
+    };
+    Ok(vec![(lhs, rhs)])
+}
+
+fn explore_as<T: OverflowInt>(op: Op, operands: &[&str]) -> Result<Table, OverflowError> {
+    Ok(overflow_table(op, &parse_cases::<T>(op, operands)?))
+}
+
+/// 通过类型名和运算名探索溢出行为，供命令行使用，例如 explore("i16", "mul", &["300", "200"])
+pub fn explore(ty: &str, op: &str, operands: &[&str]) -> Result<Table, OverflowError> {
+    let op: Op = op.parse()?;
+    match ty {
+        "i8" => explore_as::<i8>(op, operands),
+        "i16" => explore_as::<i16>(op, operands),
+        "i32" => explore_as::<i32>(op, operands),
+        "i64" => explore_as::<i64>(op, operands),
+        "i128" => explore_as::<i128>(op, operands),
+        "isize" => explore_as::<isize>(op, operands),
+        "u8" => explore_as::<u8>(op, operands),
+        "u16" => explore_as::<u16>(op, operands),
+        "u32" => explore_as::<u32>(op, operands),
+        "u64" => explore_as::<u64>(op, operands),
+        "u128" => explore_as::<u128>(op, operands),
+        "usize" => explore_as::<usize>(op, operands),
+        _ => Err(OverflowError::UnknownType(ty.to_string())),
+    }
+}
+
+#[outer_print("整型溢出探索", profile_dependent)]
+/// 在类型的边界值上对比四种溢出处理方式：
+/// + wrapping_* 按补码回绕，结果与 release 模式下直接使用运算符相同
+/// + checked_* 溢出时返回 None
+/// + overflowing_* 返回回绕后的值以及是否溢出
+/// + saturating_* 溢出时停在 MIN 或 MAX
+///
+/// 最后一列是直接使用运算符的结果：debug 模式下溢出会 panic，release 模式下与 wrapping_* 相同，
+/// 因此这节课标记为 profile_dependent，测试会验证它在两种构建下的输出确实不同
+pub fn overflow_explorer() {
+    inner_print!("u8 add");
+    println!("{}", overflow_table::<u8>(Op::Add, &[(255, Rhs::Same(1)), (255, Rhs::Same(200)), (100, Rhs::Same(100))]));
+
+    inner_print!("i8 的边界取反与绝对值");
+    println!("{}", overflow_table::<i8>(Op::Neg, &boundary_cases(Op::Neg)));
+    println!("{}", overflow_table::<i8>(Op::Abs, &boundary_cases(Op::Abs)));
+
+    inner_print!("i16 mul 300 200");
+    println!("{}", explore("i16", "mul", &["300", "200"]).unwrap());
+
+    inner_print!("移位");
+    // 左移溢出指的是移位的位数大于等于类型的位数，而不是把 1 移出了最高位
+    println!("{}", overflow_table::<u32>(Op::Shl, &[(1, Rhs::Bits(31)), (1, Rhs::Bits(32)), (3, Rhs::Bits(33))]));
+
+    inner_print!("pow");
+    println!("{}", overflow_table::<i32>(Op::Pow, &[(2, Rhs::Bits(30)), (2, Rhs::Bits(31)), (-2, Rhs::Bits(31))]));
+}
@@ -1,5 +1,6 @@
 use std::fs::canonicalize;
 use common::{inner_print, outer_print};
+use super::tracked::Tracked;
 
 /// # 引用与借用
 /// 如果仅仅支持通过转移所有权的方式获取一个值，那会让程序变得复杂。 Rust 能否像其它编程语言一样，使用某个变量的指针或者引用呢？答案是可以。
@@ -45,13 +46,16 @@ pub fn ref_and_deref(){
 
 #[outer_print("不可变引用")]
 pub fn immut_ref(){
-    let s1=String::from("hello");
-    let len=calculate_length(&s1);
+    let s1 = Tracked::new("s1", String::from("hello"));
+    let len = calculate_length(s1.borrow("s"));
     println!("The length of '{}' is {}.", s1, len);
 
+    // 这里特意写成 &String 而不是 &str，和 s1 的类型对应
+    #[allow(clippy::ptr_arg)]
     fn calculate_length(s: &String) -> usize {
         s.len()
     }
+    // 输出中只有“借用 s1 -> s”，没有移动：calculate_length 返回后 s1 依然有效，直到函数结束才被丢弃
 
 }
 
@@ -103,12 +107,13 @@ error[E0596]: cannot borrow `*some_string` as mutable, as it is behind a `&` ref
 
 #[outer_print("可变引用")]
 pub fn mut_ref(){
-    let mut s = String::from("hello");
-    change(&mut s);
+    let mut s = Tracked::new("s", String::from("hello"));
+    change(s.borrow_mut("some_string"));
 
     fn change(some_string: &mut String) {
         some_string.push_str(", world");
     }
+    println!("{}", s);
     //声明 s 是可变类型，其次创建一个可变的引用 &mut s 和接受可变引用参数 some_string: &mut String 的函数。
 
 
@@ -231,5 +236,6 @@ pub fn mut_ref(){
     /// 总的来说，借用规则如下：
     /// 1.同一时刻，你只能拥有要么一个可变引用, 要么任意多个不可变引用
     /// 2.引用必须总是有效的
+    // 函数结束时 s 被丢弃，输出中丢弃时的值是 "hello, world"，可变借用对它的修改保留了下来
     ();
 }
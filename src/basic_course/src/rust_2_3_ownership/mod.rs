@@ -1,2 +1,3 @@
 pub mod ownership;
-pub mod borrowing;
\ No newline at end of file
+pub mod borrowing;
+pub mod tracked;
\ No newline at end of file
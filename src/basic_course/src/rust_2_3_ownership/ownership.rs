@@ -1,5 +1,18 @@
-use inline_c::predicates::predicate::le;
-use common::{inner_print, outer_print};
+use std::rc::Rc;
+use inline_c::assert_c;
+use common::{inner_print, outer_print, Layout};
+use lesson_runtime::layout;
+use crate::c_compare::{self, COutput};
+use super::tracked::Tracked;
+
+/// 和 String 形状相同的结构体：堆指针、容量、长度。String 的字段是私有的，不能直接测量，用它来画内存图
+#[allow(dead_code)]
+#[derive(Layout)]
+pub struct StringRepr {
+    pub ptr: *mut u8,
+    pub cap: usize,
+    pub len: usize,
+}
 
 /// # 所有权
 /// 所有的程序都必须和计算机内存打交道，如何从内存中申请空间来存放程序的运行内容，如何在不需要的时候释放这些空间，成了重中之重，也是所有编程语言设计的难点之一。在计算机语言不断演变过程中，出现了三种流派：
@@ -30,6 +43,11 @@ pub fn ownership_rule() {
         let s = "hello";  // 从此处起，s 是有效的
         // 使用 s
     }                           // 此作用域已结束，s不再有效
+    // 用 Tracked 可以看到 s 在哪里被创建、在哪里被丢弃，输出中以 ~ 开头的行就是所有权事件
+    {
+        let s = Tracked::new("s", String::from("hello"));
+        println!("使用 {}", s);
+    }                           // 输出：离开作用域 丢弃 s = "hello"
 
     // 简而言之，s 从创建开始就有效，然后有效期持续到它离开作用域为止，可以看出，就作用域来说，Rust 语言跟其他编程语言没有区别。
 
@@ -62,9 +80,11 @@ pub fn variable_bind() {
     // 实际上，这种栈上的数据足够简单，而且拷贝非常非常快，只需要复制一个整数大小（i32，4 个字节）的内存即可，
     // 因此在这种情况下，拷贝的速度远比在堆上创建内存来得快的多。
     // 实际上，上一章我们讲到的 Rust 基本类型都是通过自动拷贝的方式来赋值的，就像上面代码一样。
-    let s1 = String::from("hello");
-    let s2 = s1;
+    let s1 = Tracked::new("s1", String::from("hello"));
+    let s2 = s1.move_to("s2");
     println!("{}",s2);
+    // 移动本身不会产生任何输出，这里用 move_to 显式地记录下来：输出中先是“创建 s1”，然后是“移动 s1 -> s2”，
+    // 之后再也不会看到“丢弃 s1”，s1 已经无效了，只有 s2 会在函数结束时被丢弃。
     // 把 s1 的内容拷贝一份赋值给 s2，实际上，并不是这样。之前也提到了，对于基本类型（存储在栈上），Rust 会自动拷贝，
     // 但是 String 不是基本类型，而且是存储在堆上的，因此不能自动拷贝。
     /// 实际上， String 类型是一个复杂类型，由存储在栈中的堆指针、字符串长度、字符串容量共同组成，其中堆指针是最重要的，它指向了真实存储字符串内容的堆内存，
@@ -73,6 +93,9 @@ pub fn variable_bind() {
     /// 总之 String 类型指向了一个堆上的空间，这里存储着它的真实数据，下面对上面代码中的 let s2 = s1 分成两种情况讨论：
     /// 1. 拷贝 String 和存储在堆上的字节数组 如果该语句是拷贝所有数据(深拷贝)，那么无论是 String 本身还是底层的堆上数据，都会被全部拷贝，这对于性能而言会造成非常大的影响
     /// 2. 只拷贝 String 本身 这样的拷贝非常快，因为在 64 位机器上就拷贝了 8字节的指针、8字节的长度、8字节的容量，总计 24 字节，但是带来了新的问题，还记得我们之前提到的所有权规则吧？其中有一条就是：一个值只允许有一个所有者，而现在这个值（堆上的真实字符串数据）有了两个所有者：s1 和 s2。
+    // 栈上的这 24 字节的内存图，大小和 String 完全相同，32 位机器上则是 12 字节：
+    println!("{}", layout::of::<StringRepr>());
+    println!("size_of::<String>() = {}", size_of::<String>());
 
     // 好吧，就假定一个值可以拥有两个所有者，会发生什么呢？
     /// 当变量离开作用域后，Rust 会自动调用 drop 函数并清理变量的堆内存。
@@ -108,10 +131,12 @@ pub fn variable_bind() {
     inner_print!("克隆(浅拷贝)");
     /// 首先，Rust 永远也不会自动创建数据的 “深拷贝”。因此，任何自动的复制都不是深拷贝，可以被认为对运行时性能影响较小。
     ///  如果我们确实需要深度复制 String 中堆上的数据，而不仅仅是栈上的数据，可以使用一个叫做 clone 的方法。
-    let s1= String::from("hello");
-    let s2=s1.clone();
+    let s1 = Tracked::new("s1", String::from("hello"));
+    let s2 = s1.clone_as("s2");
     println!("s1 = {}, s2 = {}", s1, s2);
     // 这段代码能够正常运行，因此说明 s2 确实完整的复制了 s1 的数据。
+    // 输出中 s1 和 s2 各被丢弃一次，它们拥有各自的堆内存。丢弃的顺序和声明的顺序相反：先丢弃克隆出来的 s2，再丢弃 s1，
+    // 最后是前面移动得到的 s2，被同名变量遮蔽(shadowing)的值并不会提前丢弃，同样要等到函数结束。
     // 如果代码性能无关紧要，例如初始化程序时，或者在某段时间只会执行一次时，你可以使用 clone 来简化编程。但是对于执行较为频繁的代码(热点路径)，使用 clone 会极大的降低程序性能，需要小心使用！
 
     inner_print!("克隆(深拷贝)");
@@ -170,29 +195,110 @@ pub fn function_args_return() {
     ```
      */
 
+    // 下面用 Tracked 重写这段代码，每次移动都用 move_to 记录新所有者的名字，输出就是上面注释描述的过程
     {
-        let s1 = gives_ownership();         // gives_ownership 将返回值
+        let s1 = gives_ownership().move_to("s1");         // gives_ownership 将返回值
         // 移给 s1
 
-        let s2 = String::from("hello");     // s2 进入作用域
+        let s2 = Tracked::new("s2", String::from("hello"));     // s2 进入作用域
 
-        let s3 = takes_and_gives_back(s2);  // s2 被移动到
+        let s3 = takes_and_gives_back(s2.move_to("a_string")).move_to("s3");  // s2 被移动到
         // takes_and_gives_back 中,
         // 它也将返回值移给 s3
     } // 这里, s3 移出作用域并被丢弃。s2 也移出作用域，但已被移走，
     // 所以什么也不会发生。s1 移出作用域并被丢弃
 
-    fn gives_ownership() -> String {             // gives_ownership 将返回值移动给
+    fn gives_ownership() -> Tracked<String> {             // gives_ownership 将返回值移动给
         // 调用它的函数
 
-        let some_string = String::from("hello"); // some_string 进入作用域.
+        let some_string = Tracked::new("some_string", String::from("hello")); // some_string 进入作用域.
 
         some_string                              // 返回 some_string 并移出给调用的函数
     }
 
     // takes_and_gives_back 将传入字符串并返回该值
-    fn takes_and_gives_back(a_string: String) -> String { // a_string 进入作用域
+    fn takes_and_gives_back(a_string: Tracked<String>) -> Tracked<String> { // a_string 进入作用域
 
         a_string  // 返回 a_string 并移出给调用的函数
     }
-}
\ No newline at end of file
+}
+
+/// Rust 版本：缓存借用了 score，在缓存还在使用时释放 score 会被借用检查器拒绝；
+/// 真的需要共享时，用 Rc 让缓存也成为所有者之一
+pub fn use_after_free_rust() -> String {
+    struct Cache<'a> {
+        value: &'a i32,
+    }
+    let mut out = String::new();
+    let score = Box::new(42);
+    let cache = Cache { value: &score };
+    out += &format!("before free: {}\n", cache.value);
+    // 释放 score 之后再使用缓存，无法通过编译，所以没有 after free 这一行输出：
+    // drop(score);
+    // println!("after free: {}", cache.value);
+    // error[E0505]: cannot move out of `score` because it is borrowed
+
+    struct SharedCache {
+        value: Rc<i32>,
+    }
+    let score = Rc::new(42);
+    let cache = SharedCache { value: Rc::clone(&score) };
+    drop(score);
+    out += &format!("Rc after drop: {}, strong_count = {}\n", cache.value, Rc::strong_count(&cache.value));
+    out
+}
+
+/// C 版本：缓存中留下了悬垂指针，释放之后仍然通过它读取。
+/// 读到的值是未定义的，所以程序用 AddressSanitizer 编译，读取已释放的内存时立即终止并报告错误，输出不再依赖运气；
+/// 标准输出不带缓冲，终止之前打印的内容不会丢失。
+/// release 没有内联，是为了避开 GCC 12 的 -Wuse-after-free 检查：一旦释放和使用不在同一个函数中，编译器就看不出来了
+pub fn use_after_free_c() -> Result<COutput, String> {
+    c_compare::run(|| assert_c! {
+        #inline_c_rs CFLAGS: "-Wall -Wextra -Werror -fsanitize=address -g"
+        #include <stdio.h>
+        #include <stdlib.h>
+
+        struct Cache {
+            int *value;
+        };
+
+        __attribute__((noinline)) void release(int *value) {
+            free(value);
+        }
+
+        int main() {
+            setvbuf(stdout, NULL, _IONBF, 0);
+            struct Cache cache;
+            int *score = malloc(sizeof(int));
+            *score = 42;
+            cache.value = score;
+            printf("before free: %d\n", *cache.value);
+            release(score);
+            printf("after free: %d\n", *cache.value);
+            return 0;
+        }
+    })
+}
+
+#[outer_print("与 C 对比：释放后使用")]
+/// C 中释放一块内存后，指向它的指针并不会失效，继续读写就是释放后使用(use after free)：
+/// 这块内存可能已经分配给了别人，读到的是别人的数据或者分配器的内部数据，写入则会破坏别人的数据，这类 bug 是大量安全漏洞的根源。
+/// 不加检查时这段 C 程序能正常编译运行，读到什么全凭运气，这正是未定义行为的样子；这里只打印 AddressSanitizer 报告的错误种类。
+///
+/// Rust 中，引用的生命周期不能超过它指向的值，借用检查器会在编译期拒绝这样的代码；需要多个所有者时使用 Rc，最后一个所有者离开作用域时才会释放。
+pub fn compare_use_after_free() {
+    let rust = use_after_free_rust();
+    match use_after_free_c() {
+        Ok(c) => {
+            println!("{:#}", c_compare::side_by_side(&rust, &c.stdout));
+            match c_compare::sanitizer_error(&c.stderr) {
+                Some(error) => println!("C 程序在释放后读取时被 AddressSanitizer 终止: {}", error),
+                None => println!("C 程序没有被 AddressSanitizer 终止，释放后读到的值是未定义的"),
+            }
+        }
+        Err(e) => {
+            println!("{}", rust);
+            println!("{}", e);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! # 所有权追踪
+//! 移动、借用和 drop 都发生在编译器生成的代码里，程序的输出中看不到它们，只能靠注释说明“s1 不再有效”“s 离开作用域，内存被释放”。
+//!
+//! [Tracked] 给值加上一个名字，把创建、克隆、移动、借用和 drop 记录下来并立即打印，课程的输出就是所有权事件的实际顺序：
+//! + 创建、克隆、借用通过 [Tracked::new]、[Tracked::clone_as]、[Tracked::borrow]、[Tracked::borrow_mut]，会记录调用所在的文件和行号
+//! + 移动是按位复制，没有办法拦截，需要显式调用 [Tracked::move_to] 给新的所有者起名字，例如 `let s2 = s1.move_to("s2")`、`takes(s.move_to("a_string"))`
+//! + drop 由 Drop 实现记录，编译器在所有者离开作用域时调用它，被移走的值不会再被 drop 一次，这里没有行号
+//!
+//! 事件记录在当前线程中，[trace] 执行一段代码并取出这期间的事件，测试用它来检查事件的顺序。
+
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::Location;
+use std::path::Path;
+
+/// 所有权事件的种类
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// 名字和创建时的值
+    Create { name: String, value: String },
+    Clone { from: String, to: String },
+    Move { from: String, to: String },
+    /// by 是引用的名字
+    Borrow { name: String, by: String },
+    BorrowMut { name: String, by: String },
+    /// 名字和 drop 时的值
+    Drop { name: String, value: String },
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventKind::Create { name, value } => write!(f, "创建 {} = {}", name, value),
+            EventKind::Clone { from, to } => write!(f, "克隆 {} -> {}", from, to),
+            EventKind::Move { from, to } => write!(f, "移动 {} -> {}", from, to),
+            EventKind::Borrow { name, by } => write!(f, "借用 {} -> {}", name, by),
+            EventKind::BorrowMut { name, by } => write!(f, "可变借用 {} -> {}", name, by),
+            EventKind::Drop { name, value } => write!(f, "丢弃 {} = {}", name, value),
+        }
+    }
+}
+
+/// 一个所有权事件，location 是代码中的位置，drop 没有位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub location: Option<(&'static str, u32)>,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((file, line)) => write!(f, "{}:{} {}", file, line, self.kind),
+            None => write!(f, "离开作用域 {}", self.kind),
+        }
+    }
+}
+
+thread_local! {
+    static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(kind: EventKind, location: Option<&'static Location<'static>>) {
+    let location = location.map(|loc| {
+        // 只保留文件名，完整路径太长
+        let file = Path::new(loc.file()).file_name().and_then(|name| name.to_str()).unwrap_or(loc.file());
+        (file, loc.line())
+    });
+    let event = Event { kind, location };
+    println!("  ~ {}", event);
+    // 线程结束时线程局部变量可能已经销毁，这时只打印不记录
+    let _ = EVENTS.try_with(|events| events.borrow_mut().push(event));
+}
+
+/// 取出当前线程记录的所有事件
+pub fn take_events() -> Vec<Event> {
+    EVENTS.with(|events| events.take())
+}
+
+/// 执行 f，返回它的结果和执行期间的事件，f 中创建但没有返回的值都已经 drop 了
+pub fn trace<R>(f: impl FnOnce() -> R) -> (R, Vec<Event>) {
+    take_events();
+    let result = f();
+    (result, take_events())
+}
+
+/// 带名字的值，所有权的变化会被记录下来
+pub struct Tracked<T: fmt::Debug> {
+    name: String,
+    value: T,
+}
+
+impl<T: fmt::Debug> Tracked<T> {
+    #[track_caller]
+    pub fn new(name: &str, value: T) -> Tracked<T> {
+        record(EventKind::Create { name: name.to_string(), value: format!("{:?}", value) }, Some(Location::caller()));
+        Tracked { name: name.to_string(), value }
+    }
+
+    /// 当前所有者的名字
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 把所有权交给名为 to 的新所有者，包括赋值给另一个变量、作为参数传给函数、从函数返回
+    #[track_caller]
+    pub fn move_to(mut self, to: &str) -> Tracked<T> {
+        record(EventKind::Move { from: self.name.clone(), to: to.to_string() }, Some(Location::caller()));
+        self.name = to.to_string();
+        self
+    }
+
+    /// 不可变借用，by 是引用的名字
+    #[track_caller]
+    pub fn borrow(&self, by: &str) -> &T {
+        record(EventKind::Borrow { name: self.name.clone(), by: by.to_string() }, Some(Location::caller()));
+        &self.value
+    }
+
+    /// 可变借用，by 是引用的名字
+    #[track_caller]
+    pub fn borrow_mut(&mut self, by: &str) -> &mut T {
+        record(EventKind::BorrowMut { name: self.name.clone(), by: by.to_string() }, Some(Location::caller()));
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Debug + Clone> Tracked<T> {
+    /// 深拷贝出一个名为 to 的新值，原来的值依然有效
+    #[track_caller]
+    pub fn clone_as(&self, to: &str) -> Tracked<T> {
+        record(EventKind::Clone { from: self.name.clone(), to: to.to_string() }, Some(Location::caller()));
+        Tracked { name: to.to_string(), value: self.value.clone() }
+    }
+}
+
+impl<T: fmt::Debug> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        record(EventKind::Drop { name: self.name.clone(), value: format!("{:?}", self.value) }, None);
+    }
+}
+
+/// 打印时只显示值，不产生借用事件
+impl<T: fmt::Debug + fmt::Display> fmt::Display for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.value, f)
+    }
+}
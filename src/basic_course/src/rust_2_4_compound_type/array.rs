@@ -1,5 +1,5 @@
-use std::io;
 use common::{inner_print, outer_print};
+use lesson_runtime::input;
 
 #[outer_print("创建数组")]
 pub fn create_array(){
@@ -38,11 +38,8 @@ pub fn access_array(){
 
     println!("Please enter an array index.");
 
-    let mut index = String::new();
-    // 读取控制台的输出
-    io::stdin()
-        .read_line(&mut index)
-        .expect("Failed to read line");
+    // 读取一行输入：命令行中可以用 --input 提供，测试中用 input::with_script 提供，都没有时读取控制台
+    let index = input::read_line().expect("Failed to read line");
 
     let index: usize = index
         .trim()
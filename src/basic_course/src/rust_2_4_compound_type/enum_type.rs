@@ -1,4 +1,67 @@
-use common::{inner_print, outer_print};
+use std::cmp::Ordering;
+use std::io::Write;
+use common::{inner_print, outer_print, Layout};
+use lesson_runtime::layout::{self, TypeLayout};
+use crate::rust_2_14_formatted_output::table::Table;
+use super::ip_addr::IpAddr;
+use super::poker::{self, Deck, HandRank, PokerCard, Rank, Rng};
+use super::stream::{self, Stream, XorStream};
+use crate::rust_2_7_method::message;
+
+/// 本章几个枚举的内存布局，顺序是：PokerSuit、PokerCard(u8)、PokerCard(u8/char)、Message
+pub fn enum_layouts() -> Vec<TypeLayout> {
+    #[allow(dead_code)]
+    #[derive(Layout)]
+    enum PokerSuit {
+        Clubs,
+        Spades,
+        Diamonds,
+        Hearts,
+    }
+    #[allow(dead_code)]
+    #[derive(Layout)]
+    enum PokerCard {
+        Clubs(u8),
+        Spades(u8),
+        Diamonds(u8),
+        Hearts(u8),
+    }
+    #[allow(dead_code)]
+    #[derive(Layout)]
+    enum MixedCard {
+        Clubs(u8),
+        Spades(u8),
+        Diamonds(char),
+        Hearts(char),
+    }
+    #[allow(dead_code)]
+    #[derive(Layout)]
+    enum Message {
+        Quit,
+        Move { x: i32, y: i32 },
+        Write(String),
+        ChangeColor(i32, i32, i32),
+    }
+    vec![layout::of::<PokerSuit>(), layout::of::<PokerCard>(), layout::of::<MixedCard>(), layout::of::<Message>()]
+}
+
+/// Option<T> 在不同的 T 下的内存布局，顺序是：Option<u8>、Option<i32>、Option<&i32>、Option<Box<i32>>、Option<char>
+pub fn option_layouts() -> Vec<TypeLayout> {
+    // 和标准库的 Option 定义相同
+    #[allow(dead_code)]
+    #[derive(Layout)]
+    enum Option<T> {
+        None,
+        Some(T),
+    }
+    vec![
+        layout::of::<Option<u8>>(),
+        layout::of::<Option<i32>>(),
+        layout::of::<Option<&i32>>(),
+        layout::of::<Option<Box<i32>>>(),
+        layout::of::<Option<char>>(),
+    ]
+}
 
 #[outer_print("枚举值")]
 pub fn enum_value() {
@@ -68,17 +131,21 @@ pub fn enum_value() {
 
     //再来看一个来自标准库中的例子：
     {
-        struct Ipv4Addr {
-            // --snip--
-        }
-
-        struct Ipv6Addr {
-            // --snip--
-        }
-
-        enum IpAddr {
-            V4(Ipv4Addr),
-            V6(Ipv6Addr),
+        // ip_addr 模块中的定义和标准库相同：
+        // pub struct Ipv4Addr(pub [u8; 4]);
+        // pub struct Ipv6Addr(pub [u16; 8]);
+        // pub enum IpAddr {
+        //     V4(Ipv4Addr),
+        //     V6(Ipv6Addr),
+        // }
+        // 两种地址的文本格式不同，解析时先根据有没有 : 决定是哪个成员，再交给成员自己的 parse
+        for text in ["127.0.0.1", "::1", "2001:db8:0:0:1:0:0:1", "::ffff:192.168.0.1", "256.0.0.1", "1::2::3"] {
+            match text.parse::<IpAddr>() {
+                Ok(addr @ IpAddr::V4(v4)) => println!("{:<22} => V4 {:?}，回环 {}", text, v4.octets(), addr.is_loopback()),
+                // IPv6 格式化时把最长的一段 0 写成 ::，所以输出可能和输入不同
+                Ok(addr @ IpAddr::V6(_)) => println!("{:<22} => V6 {}，回环 {}", text, addr, addr.is_loopback()),
+                Err(e) => println!("{:<22} => 错误：{}", text, e),
+            }
         }
     }
     ///从这些例子可以看出，任何类型的数据都可以放入枚举成员中: 例如字符串、数值、结构体甚至另一个枚举。
@@ -99,6 +166,81 @@ pub fn enum_value() {
         // Write 包含一个 String 字符串
         // ChangeColor 包含三个 i32
     }
+    {
+        // 方法一章的 message 模块实现了同样的 Message，它可以从文本命令解析出来，每种命令对应一个成员
+        for text in ["quit", "move 1 1", "write hello world", "color 255 255 0", "move 1", "jump"] {
+            match text.parse::<message::Message>() {
+                Ok(m) => println!("{:<18} => {:?}", text, m),
+                Err(e) => println!("{:<18} => 错误：{}", text, e),
+            }
+        }
+    }
+
+    inner_print!("枚举的内存布局");
+    // 上面几个枚举的内存图。枚举的大小是最大的成员加上判别值(discriminant)，判别值用来记录当前是哪个成员：
+    // + PokerSuit 没有字段，整个值就是 1 字节的判别值
+    // + PokerCard 的每个成员都是一个 u8，再加 1 字节判别值，共 2 字节
+    // + char 的合法值只到 0x10FFFF，剩下的位模式就是 niche，但 u8 的成员没有 niche，所以判别值仍然需要单独的空间
+    // + Message 中最大的是 Write(String)，String 的容量不会超过 isize::MAX，编译器把判别值放进了这些不可能出现的容量值中，整个枚举和 String 一样大
+    for layout in enum_layouts() {
+        println!("{}", layout);
+    }
+}
+
+#[outer_print("用枚举实现一副扑克牌")]
+/// 枚举值一节的 PokerSuit 和 PokerCard 只是打印出来，poker 模块把它们补充成了一副能玩的牌，
+/// 牌型 HandRank 同样是枚举，判断牌型、比较大小都是对枚举的 match 和比较。
+pub fn poker_game() {
+    inner_print!("一副牌");
+    // 52 张牌由两层循环生成：4 个花色 × 13 个点数，花色和点数都是没有字段的枚举，ALL 常量列出了全部成员
+    let deck = Deck::new();
+    let cards: Vec<String> = deck.cards().map(|card| card.to_string()).collect();
+    for row in cards.chunks(13) {
+        println!("{}", row.join(" "));
+    }
+    // 一张牌是 PokerCard::Spades(Rank::Ace) 这样的枚举值，花色和点数需要 match 取出来
+    let ace = PokerCard::Spades(Rank::Ace);
+    println!("{:?}：花色 {:?}，点数 {:?} = {}", ace, ace.suit(), ace.rank(), ace.rank().value());
+
+    inner_print!("洗牌和发牌");
+    // 种子固定，每次运行洗出的牌都一样，换一个种子就是另一局
+    const SEED: u64 = 2024;
+    let mut deck = Deck::new();
+    deck.shuffle(&mut Rng::new(SEED));
+    let hands = deck.deal_hands(4).unwrap();
+    println!("种子 {}，4 个人每人 5 张，还剩 {} 张", SEED, deck.len());
+    let ranks: Vec<HandRank> = hands.iter().map(poker::evaluate).collect();
+    let mut table = Table::new(["玩家", "手牌", "牌型"]);
+    for (i, (hand, rank)) in hands.iter().zip(&ranks).enumerate() {
+        // 派生的 Ord 先比较成员的顺序，也就是按花色整理手牌
+        let mut sorted = *hand;
+        sorted.sort();
+        let cards: Vec<String> = sorted.iter().map(|card| format!("{:>3}", card)).collect();
+        table.row([format!("玩家 {}", i + 1), cards.join(" "), rank.to_string()]);
+    }
+    println!("{}", table);
+    // 比较牌型就是比较两个枚举值
+    let winners: Vec<String> = poker::winners(&ranks).iter().map(|i| format!("玩家 {}", i + 1)).collect();
+    println!("赢家：{}", winners.join("、"));
+
+    inner_print!("牌型的大小就是枚举的大小");
+    // HandRank 的成员按从小到大的顺序声明，派生的 PartialOrd 先比较成员的先后，成员相同再依次比较字段
+    let pairs = [
+        ("2♣ 3♦ 5♥ 7♠ 9♣", "2♦ 2♠ 3♣ 4♥ 5♦"),
+        ("J♣ J♦ 4♥ 4♠ A♣", "J♥ J♠ 9♣ 9♥ 2♦"),
+        ("A♣ 2♦ 3♥ 4♠ 5♣", "2♠ 3♠ 4♦ 5♥ 6♣"),
+        ("K♥ K♦ K♠ 3♣ 3♦", "2♥ 7♥ 9♥ J♥ Q♥"),
+        ("10♠ J♠ Q♠ K♠ A♠", "9♥ 10♥ J♥ Q♥ K♥"),
+    ];
+    for (a, b) in pairs {
+        let (x, y) = (poker::evaluate(&poker::parse_hand(a).unwrap()), poker::evaluate(&poker::parse_hand(b).unwrap()));
+        let op = match x.cmp(&y) {
+            Ordering::Less => "<",
+            Ordering::Equal => "=",
+            Ordering::Greater => ">",
+        };
+        println!("{} {} {}", x, op, y);
+    }
 }
 
 #[outer_print("同一化类型")]
@@ -124,6 +266,30 @@ pub fn uniform_type() {
     //     Tcp(Websocket<TcpStream>),
     //     Tls(Websocket<native_tls::TlsStream<TcpStream>>),
     // }
+
+    inner_print!("Stream 枚举");
+    // 真正的 TLS 需要证书，stream 模块用一个异或加密的 XorStream 代替 TlsStream：
+    // pub enum Stream {
+    //     Tcp(TcpStream),
+    //     Tls(XorStream<TcpStream>),
+    // }
+    // XorStream 可以包装任何流，包装一个 Vec 就能看到写出去的字节已经不是明文了
+    let mut tls = XorStream::new(Vec::new(), b"key");
+    tls.write_all(b"hello").unwrap();
+    println!("明文 {:?}，线路上 {:?}", b"hello", tls.get_ref());
+
+    inner_print!("同一个函数处理两种连接");
+    // 服务端 accept 之后根据握手得到 Stream::Tcp 或 Stream::Tls，之后都交给 stream::echo 处理，
+    // echo 只知道 Stream 实现了 Read 和 Write，match 藏在这两个 trait 的实现里
+    let (addr, server) = stream::echo_server(2).unwrap();
+    for key in [None, Some(&b"secret"[..])] {
+        let mut stream = Stream::connect(addr, key).unwrap();
+        let reply = stream::round_trip(&mut stream, "你好，枚举".as_bytes()).unwrap();
+        println!("{} 连接收到回显: {}", stream.kind(), String::from_utf8_lossy(&reply));
+    }
+    for (kind, bytes) in server.join().unwrap().unwrap() {
+        println!("服务端：{} 连接回显了 {} 字节", kind, bytes);
+    }
 }
 
 #[outer_print("Option 枚举用于处理空值")]
@@ -133,8 +299,8 @@ pub fn option_enum() {
     /// 有鉴于此，Rust 吸取了众多教训，决定抛弃 null，而改为使用 Option 枚举变量来表述这种结果。
     /// Option 枚举包含两个成员，一个成员表示含有值：Some(T), 另一个表示没有值：None，定义如下：
     enum Option<T> {
-        Some(T),
         None,
+        Some(T),
     }
     ///其中 T 是泛型参数，Some(T)表示该枚举成员的数据类型是 T，换句话说，Some 可以包含任何类型的数据。
     /// Option<T> 枚举是如此有用以至于它被包含在了 prelude，你不需要将其显式引入作用域。另外，它的成员 Some 和 None 也是如此，无需使用 Option:: 前缀就可直接使用 Some 和 None。
@@ -164,6 +330,14 @@ pub fn option_enum() {
             Some(i) => Some(i + 1),
         }
     }
+
+    inner_print!("Option 的内存布局");
+    // Option 不是没有代价的：Option<u8>、Option<i32> 需要额外的判别值。
+    // 但引用和 Box 永远不会是空指针，None 直接用空指针表示，Option<&i32>、Option<Box<i32>> 和指针一样大，这就是 niche 优化，
+    // 所以用 Option 代替 null 没有任何额外的开销。
+    for layout in option_layouts() {
+        println!("{}", layout);
+    }
 }
 
 
@@ -0,0 +1,242 @@
+//! # 查看字符串和动态数组的内存
+//! String、&str 和 Vec<T> 在栈上都只是几个字长：指针、长度，String 和 Vec 还有容量，真正的数据在指针指向的地方。
+//! 本节的 [Inspect] 把这些看不见的东西打印出来：
+//! + 指针、长度和容量
+//! + 指针指向哪里：可执行文件的只读数据(rodata)、堆、匿名映射，还是没有分配内存的悬垂指针
+//! + 数据的十六进制内容，字符串按 UTF-8 字符、Vec 按元素用 | 分隔，下面一行是对应的字符或者元素
+//!
+//! 判断指针指向哪里需要读取 /proc/self/maps，只支持 Linux，其它系统显示为未知。
+
+use std::fmt;
+use std::ops::Range;
+use crate::rust_2_14_formatted_output::table::display_width;
+
+/// 每行最多显示的字节数，一个字符或者元素不会被拆到两行
+const BYTES_PER_ROW: usize = 16;
+
+/// 指针指向的内存区域
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Region {
+    /// 可执行文件中的只读数据，字符串字面量就在这里
+    ReadOnly,
+    /// 可执行文件中可写的数据，static mut 等
+    Static,
+    /// 主线程的堆，也就是 /proc/self/maps 中的 [heap]
+    Heap,
+    /// 没有对应文件的匿名映射：分配器用 mmap 申请的大块内存、其它线程的堆(arena)、线程栈等，
+    /// 单看地址无法区分是哪一种
+    Anonymous,
+    /// 主线程的栈
+    Stack,
+    /// 容量为 0 时不分配内存，指针是一个对齐的非空地址
+    Dangling,
+    /// 其它文件的映射，例如动态库
+    Mapped(String),
+    Unknown,
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Region::ReadOnly => write!(f, "可执行文件的只读数据(rodata)"),
+            Region::Static => write!(f, "可执行文件的可写数据"),
+            Region::Heap => write!(f, "堆"),
+            Region::Anonymous => write!(f, "堆或线程栈等匿名映射"),
+            Region::Stack => write!(f, "栈"),
+            Region::Dangling => write!(f, "悬垂指针，没有分配内存"),
+            Region::Mapped(path) => write!(f, "{} 的映射", path),
+            Region::Unknown => write!(f, "未知"),
+        }
+    }
+}
+
+/// 按 /proc/self/maps 判断地址所在的区域
+#[cfg(target_os = "linux")]
+pub fn region_of(addr: usize) -> Region {
+    use std::fs;
+    let Ok(maps) = fs::read_to_string("/proc/self/maps") else { return Region::Unknown };
+    let exe = std::env::current_exe().ok().and_then(|path| fs::canonicalize(path).ok());
+    for line in maps.lines() {
+        // 每一行形如：55d0c8a00000-55d0c8a21000 r--p 00000000 08:01 1234    /path/to/exe
+        let parts: Vec<&str> = line.splitn(6, ' ').collect();
+        let Some((start, end)) = parts[0].split_once('-') else { continue };
+        let (Ok(start), Ok(end)) = (usize::from_str_radix(start, 16), usize::from_str_radix(end, 16)) else { continue };
+        if !(start..end).contains(&addr) {
+            continue;
+        }
+        let perms = parts.get(1).copied().unwrap_or("");
+        let path = parts.get(5).map_or("", |path| path.trim());
+        return match path {
+            "[heap]" => Region::Heap,
+            "" => Region::Anonymous,
+            "[stack]" => Region::Stack,
+            _ if exe.as_deref().is_some_and(|exe| exe.as_os_str() == path) => {
+                if perms.contains('w') {
+                    Region::Static
+                } else {
+                    Region::ReadOnly
+                }
+            }
+            _ => Region::Mapped(path.to_string()),
+        };
+    }
+    // 第一页永远不会被映射，空的 String 和 Vec 的指针就落在这里
+    if addr < 4096 {
+        Region::Dangling
+    } else {
+        Region::Unknown
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn region_of(addr: usize) -> Region {
+    if addr < 4096 {
+        Region::Dangling
+    } else {
+        Region::Unknown
+    }
+}
+
+/// 一次查看的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inspection {
+    /// 类型名，例如 String、&str、Vec<u32>
+    pub kind: String,
+    pub ptr: usize,
+    /// 元素个数，字符串是字节数
+    pub len: usize,
+    /// &str 没有容量
+    pub capacity: Option<usize>,
+    pub region: Region,
+    /// 数据的副本
+    pub bytes: Vec<u8>,
+    /// 每个字符或者元素占用的字节范围和显示的文本
+    pub units: Vec<(Range<usize>, String)>,
+}
+
+impl Inspection {
+    /// 按 UTF-8 字符分组
+    fn of_str(kind: &str, s: &str, capacity: Option<usize>) -> Inspection {
+        let units = s
+            .char_indices()
+            .map(|(i, c)| (i..i + c.len_utf8(), c.escape_debug().to_string()))
+            .collect();
+        let ptr = s.as_ptr() as usize;
+        Inspection {
+            kind: kind.to_string(),
+            ptr,
+            len: s.len(),
+            capacity,
+            region: region_of(ptr),
+            bytes: s.as_bytes().to_vec(),
+            units,
+        }
+    }
+
+    /// 十六进制内容，每行的第一个数字是偏移，| 是字符或者元素的边界
+    pub fn hex_dump(&self) -> String {
+        let mut out = String::new();
+        let mut rows: Vec<&[(Range<usize>, String)]> = Vec::new();
+        let mut start = 0;
+        for (i, (range, _)) in self.units.iter().enumerate() {
+            if range.end - self.units[start].0.start > BYTES_PER_ROW && i > start {
+                rows.push(&self.units[start..i]);
+                start = i;
+            }
+        }
+        if start < self.units.len() {
+            rows.push(&self.units[start..]);
+        }
+        for row in rows {
+            let (mut hex, mut text) = (String::new(), String::new());
+            for (range, label) in row {
+                let bytes: Vec<String> = self.bytes[range.clone()].iter().map(|b| format!("{:02x}", b)).collect();
+                let bytes = bytes.join(" ");
+                let width = bytes.len().max(display_width(label));
+                hex.push_str(&format!("{}{}|", bytes, " ".repeat(width - bytes.len())));
+                text.push_str(&format!("{}{}|", label, " ".repeat(width - display_width(label))));
+            }
+            out.push_str(&format!("{:>6}  |{}\n        |{}\n", row[0].0.start, hex, text));
+        }
+        out
+    }
+
+    /// 第一行：类型、指针、长度、容量和位置
+    pub fn summary(&self) -> String {
+        let capacity = self.capacity.map(|capacity| format!(", capacity = {}", capacity)).unwrap_or_default();
+        format!("{}: ptr = {:#x}, len = {}{}, 位于{}", self.kind, self.ptr, self.len, capacity, self.region)
+    }
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.summary(), self.hex_dump().trim_end())
+    }
+}
+
+/// 可以按字节查看的元素类型
+///
+/// # Safety
+/// 实现的类型不能有填充字节，每个字节都必须是初始化过的
+pub unsafe trait Plain: Copy + fmt::Debug {}
+
+macro_rules! plain {
+    ($($t:ty)*) => { $(unsafe impl Plain for $t {})* };
+}
+
+plain!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64 char bool);
+
+/// 查看 String、&str 或者 Vec<T> 的内存
+pub trait Inspect {
+    fn inspect(&self) -> Inspection;
+}
+
+impl Inspect for str {
+    fn inspect(&self) -> Inspection {
+        Inspection::of_str("&str", self, None)
+    }
+}
+
+impl Inspect for String {
+    fn inspect(&self) -> Inspection {
+        Inspection::of_str("String", self, Some(self.capacity()))
+    }
+}
+
+impl<T: Plain> Inspect for Vec<T> {
+    fn inspect(&self) -> Inspection {
+        let size = size_of::<T>();
+        // SAFETY: Plain 保证 T 没有填充，len 个元素的每个字节都是初始化过的
+        let bytes = unsafe { std::slice::from_raw_parts(self.as_ptr() as *const u8, self.len() * size) };
+        let units = self.iter().enumerate().map(|(i, item)| (i * size..(i + 1) * size, format!("{:?}", item))).collect();
+        let ptr = self.as_ptr() as usize;
+        Inspection {
+            kind: format!("Vec<{}>", std::any::type_name::<T>()),
+            ptr,
+            len: self.len(),
+            capacity: Some(self.capacity()),
+            region: region_of(ptr),
+            bytes: bytes.to_vec(),
+            units,
+        }
+    }
+}
+
+/// 一次追加之后的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Growth {
+    pub len: usize,
+    pub capacity: usize,
+    pub ptr: usize,
+}
+
+/// 从空的 String 开始反复 push_str(piece)，记录每次之后的长度、容量和指针
+pub fn push_str_growth(piece: &str, times: usize) -> Vec<Growth> {
+    let mut s = String::new();
+    let mut steps = vec![Growth { len: s.len(), capacity: s.capacity(), ptr: s.as_ptr() as usize }];
+    for _ in 0..times {
+        s.push_str(piece);
+        steps.push(Growth { len: s.len(), capacity: s.capacity(), ptr: s.as_ptr() as usize });
+    }
+    steps
+}
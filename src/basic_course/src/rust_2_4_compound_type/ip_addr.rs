@@ -0,0 +1,231 @@
+//! # IP 地址
+//! 枚举值一节中 IpAddr 的两个成员分别持有 Ipv4Addr 和 Ipv6Addr，这里把它们补充完整：从字符串解析，再格式化回字符串。
+//! 标准库的 std::net 中有同名的类型，行为相同，测试中用它来对照。
+//!
+//! + IPv4：4 个用 . 分隔的 0 到 255 的十进制数，不允许多余的前导 0，例如 01 容易被误解为八进制
+//! + IPv6：8 组用 : 分隔的 16 位十六进制数，连续的全 0 组可以用 :: 省略一次，最后两组也可以写成 IPv4 的形式，例如 ::ffff:192.168.0.1
+//! + 格式化 IPv6 时按 RFC 5952：去掉每组的前导 0，用小写字母，把最长的一段连续 0 组（至少两组，一样长时取靠前的）写成 ::
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ipv4Addr(pub [u8; 4]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ipv6Addr(pub [u16; 8]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IpAddr {
+    V4(Ipv4Addr),
+    V6(Ipv6Addr),
+}
+
+/// 解析地址的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddrParseError {
+    Empty,
+    /// IPv4 不是 4 段
+    OctetCount(usize),
+    /// 不是 0 到 255 的十进制数，或者有多余的前导 0
+    BadOctet(String),
+    /// IPv6 的组数不对，:: 至少要代替一组
+    GroupCount(usize),
+    /// 不是 1 到 4 位的十六进制数
+    BadGroup(String),
+    /// :: 只能出现一次
+    MultipleDoubleColon,
+    /// 内嵌的 IPv4 只能放在最后
+    MisplacedIpv4(String),
+}
+
+impl fmt::Display for AddrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddrParseError::Empty => f.write_str("地址为空"),
+            AddrParseError::OctetCount(n) => write!(f, "IPv4 地址需要 4 段，实际是 {} 段", n),
+            AddrParseError::BadOctet(s) => write!(f, "无法解析的 IPv4 段: {:?}", s),
+            AddrParseError::GroupCount(n) => write!(f, "IPv6 地址的组数不对: {} 组", n),
+            AddrParseError::BadGroup(s) => write!(f, "无法解析的 IPv6 组: {:?}", s),
+            AddrParseError::MultipleDoubleColon => f.write_str(":: 只能出现一次"),
+            AddrParseError::MisplacedIpv4(s) => write!(f, "内嵌的 IPv4 地址只能放在最后: {:?}", s),
+        }
+    }
+}
+
+impl std::error::Error for AddrParseError {}
+
+impl Ipv4Addr {
+    pub const LOCALHOST: Ipv4Addr = Ipv4Addr([127, 0, 0, 1]);
+
+    pub fn octets(&self) -> [u8; 4] {
+        self.0
+    }
+}
+
+fn parse_octet(s: &str) -> Result<u8, AddrParseError> {
+    let bad = || AddrParseError::BadOctet(s.to_string());
+    // parse 允许开头的 +，这里只接受数字
+    if s.is_empty() || s.len() > 3 || !s.bytes().all(|b| b.is_ascii_digit()) || (s.len() > 1 && s.starts_with('0')) {
+        return Err(bad());
+    }
+    s.parse().map_err(|_| bad())
+}
+
+impl FromStr for Ipv4Addr {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Ipv4Addr, AddrParseError> {
+        if s.is_empty() {
+            return Err(AddrParseError::Empty);
+        }
+        let parts: Vec<&str> = s.split('.').collect();
+        let [a, b, c, d] = parts[..] else { return Err(AddrParseError::OctetCount(parts.len())) };
+        Ok(Ipv4Addr([parse_octet(a)?, parse_octet(b)?, parse_octet(c)?, parse_octet(d)?]))
+    }
+}
+
+impl fmt::Display for Ipv4Addr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d] = self.0;
+        f.pad(&format!("{}.{}.{}.{}", a, b, c, d))
+    }
+}
+
+impl Ipv6Addr {
+    pub const LOCALHOST: Ipv6Addr = Ipv6Addr([0, 0, 0, 0, 0, 0, 0, 1]);
+    pub const UNSPECIFIED: Ipv6Addr = Ipv6Addr([0; 8]);
+
+    pub fn segments(&self) -> [u16; 8] {
+        self.0
+    }
+
+    /// ::ffff:a.b.c.d 形式的地址，用 IPv6 表示的 IPv4 地址
+    pub fn to_ipv4_mapped(&self) -> Option<Ipv4Addr> {
+        match self.0 {
+            [0, 0, 0, 0, 0, 0xffff, high, low] => {
+                let [a, b] = high.to_be_bytes();
+                let [c, d] = low.to_be_bytes();
+                Some(Ipv4Addr([a, b, c, d]))
+            }
+            _ => None,
+        }
+    }
+
+    /// 最长的一段连续 0 组的起点和长度，不到两组返回 None，一样长时取靠前的
+    pub fn longest_zero_run(&self) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        let mut i = 0;
+        while i < 8 {
+            let len = self.0[i..].iter().take_while(|&&group| group == 0).count();
+            if len >= 2 && best.is_none_or(|(_, best_len)| len > best_len) {
+                best = Some((i, len));
+            }
+            i += len.max(1);
+        }
+        best
+    }
+}
+
+/// 把用 : 分隔的一串组解析成 16 位的数，最后一个元素是 IPv4 时展开成两组
+fn parse_groups(s: &str, ipv4_allowed: bool) -> Result<Vec<u16>, AddrParseError> {
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+    let parts: Vec<&str> = s.split(':').collect();
+    let mut groups = Vec::with_capacity(8);
+    for (i, part) in parts.iter().enumerate() {
+        if part.contains('.') {
+            if !ipv4_allowed || i + 1 != parts.len() {
+                return Err(AddrParseError::MisplacedIpv4(part.to_string()));
+            }
+            let [a, b, c, d] = part.parse::<Ipv4Addr>()?.0;
+            groups.push(u16::from_be_bytes([a, b]));
+            groups.push(u16::from_be_bytes([c, d]));
+        } else if !part.is_empty() && part.len() <= 4 && part.bytes().all(|b| b.is_ascii_hexdigit()) {
+            groups.push(u16::from_str_radix(part, 16).map_err(|_| AddrParseError::BadGroup(part.to_string()))?);
+        } else {
+            return Err(AddrParseError::BadGroup(part.to_string()));
+        }
+    }
+    Ok(groups)
+}
+
+impl FromStr for Ipv6Addr {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Ipv6Addr, AddrParseError> {
+        if s.is_empty() {
+            return Err(AddrParseError::Empty);
+        }
+        let pieces: Vec<&str> = s.split("::").collect();
+        let groups = match pieces[..] {
+            // 没有 ::，必须正好 8 组
+            [all] => {
+                let groups = parse_groups(all, true)?;
+                if groups.len() != 8 {
+                    return Err(AddrParseError::GroupCount(groups.len()));
+                }
+                groups
+            }
+            // :: 前后的组数加起来最多 7 组，中间用 0 补齐到 8 组
+            [head, tail] => {
+                let head = parse_groups(head, false)?;
+                let tail = parse_groups(tail, true)?;
+                if head.len() + tail.len() > 7 {
+                    return Err(AddrParseError::GroupCount(head.len() + tail.len()));
+                }
+                let zeros = 8 - head.len() - tail.len();
+                head.into_iter().chain(std::iter::repeat_n(0, zeros)).chain(tail).collect()
+            }
+            _ => return Err(AddrParseError::MultipleDoubleColon),
+        };
+        Ok(Ipv6Addr(groups.try_into().expect("正好 8 组")))
+    }
+}
+
+impl fmt::Display for Ipv6Addr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(v4) = self.to_ipv4_mapped() {
+            return f.pad(&format!("::ffff:{}", v4));
+        }
+        let hex = |groups: &[u16]| groups.iter().map(|group| format!("{:x}", group)).collect::<Vec<_>>().join(":");
+        let text = match self.longest_zero_run() {
+            Some((start, len)) => format!("{}::{}", hex(&self.0[..start]), hex(&self.0[start + len..])),
+            None => hex(&self.0),
+        };
+        f.pad(&text)
+    }
+}
+
+impl FromStr for IpAddr {
+    type Err = AddrParseError;
+
+    /// 含有 : 的是 IPv6，否则是 IPv4
+    fn from_str(s: &str) -> Result<IpAddr, AddrParseError> {
+        if s.contains(':') {
+            s.parse().map(IpAddr::V6)
+        } else {
+            s.parse().map(IpAddr::V4)
+        }
+    }
+}
+
+impl fmt::Display for IpAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpAddr::V4(addr) => fmt::Display::fmt(addr, f),
+            IpAddr::V6(addr) => fmt::Display::fmt(addr, f),
+        }
+    }
+}
+
+impl IpAddr {
+    pub fn is_loopback(&self) -> bool {
+        match self {
+            IpAddr::V4(Ipv4Addr([127, ..])) => true,
+            IpAddr::V4(_) => false,
+            IpAddr::V6(addr) => *addr == Ipv6Addr::LOCALHOST,
+        }
+    }
+}
@@ -1,4 +1,8 @@
 pub mod string_slice;
+pub mod heap_inspect;
+pub mod ip_addr;
+pub mod poker;
+pub mod stream;
 pub mod tuple;
 pub mod struct_type;
 pub mod enum_type;
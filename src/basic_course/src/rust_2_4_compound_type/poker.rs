@@ -0,0 +1,443 @@
+//! # 扑克牌
+//! 把枚举一节中的 PokerSuit 和 PokerCard 扩展成一个能玩的小程序：
+//! + [PokerSuit] 和 [Rank] 是没有字段的枚举，派生的 PartialOrd/Ord 按成员的声明顺序比较，A 最大
+//! + [PokerCard] 沿用“花色作为成员、点数作为字段”的写法，[PokerCard::suit] 和 [PokerCard::rank] 都是穷尽的 match
+//! + [Deck] 是一副 52 张的牌，用 [Rng] 洗牌，[Rng] 是本模块自带的可以指定种子的伪随机数生成器，种子相同洗出的牌就相同
+//! + [evaluate] 判断 5 张牌的牌型，结果 [HandRank] 也是枚举，成员按牌型从小到大声明，比较两手牌就是比较两个枚举值
+
+use std::fmt;
+use std::str::FromStr;
+
+/// 花色，沿用枚举一节的声明顺序，派生的 Ord 只在整理手牌时用到，比较牌型时花色不分大小
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PokerSuit {
+    Clubs,
+    Spades,
+    Diamonds,
+    Hearts,
+}
+
+impl PokerSuit {
+    pub const ALL: [PokerSuit; 4] = [PokerSuit::Clubs, PokerSuit::Spades, PokerSuit::Diamonds, PokerSuit::Hearts];
+
+    pub fn symbol(self) -> char {
+        match self {
+            PokerSuit::Clubs => '♣',
+            PokerSuit::Spades => '♠',
+            PokerSuit::Diamonds => '♦',
+            PokerSuit::Hearts => '♥',
+        }
+    }
+}
+
+/// 点数，判别值就是牌面的数值，A 是 14
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rank {
+    Two = 2,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// 牌面的数值，2 到 14
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_value(value: u8) -> Option<Rank> {
+        Rank::ALL.iter().copied().find(|rank| rank.value() == value)
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        }
+    }
+}
+
+/// 一张牌，花色是成员，点数是字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PokerCard {
+    Clubs(Rank),
+    Spades(Rank),
+    Diamonds(Rank),
+    Hearts(Rank),
+}
+
+impl PokerCard {
+    pub fn new(suit: PokerSuit, rank: Rank) -> PokerCard {
+        match suit {
+            PokerSuit::Clubs => PokerCard::Clubs(rank),
+            PokerSuit::Spades => PokerCard::Spades(rank),
+            PokerSuit::Diamonds => PokerCard::Diamonds(rank),
+            PokerSuit::Hearts => PokerCard::Hearts(rank),
+        }
+    }
+
+    pub fn suit(self) -> PokerSuit {
+        match self {
+            PokerCard::Clubs(_) => PokerSuit::Clubs,
+            PokerCard::Spades(_) => PokerSuit::Spades,
+            PokerCard::Diamonds(_) => PokerSuit::Diamonds,
+            PokerCard::Hearts(_) => PokerSuit::Hearts,
+        }
+    }
+
+    pub fn rank(self) -> Rank {
+        // 四个成员的字段类型相同，可以用 | 合并成一个分支
+        match self {
+            PokerCard::Clubs(rank) | PokerCard::Spades(rank) | PokerCard::Diamonds(rank) | PokerCard::Hearts(rank) => rank,
+        }
+    }
+}
+
+impl fmt::Display for PokerCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{}{}", self.rank().symbol(), self.suit().symbol()))
+    }
+}
+
+/// 解析牌的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    BadRank(String),
+    BadSuit(String),
+    /// 一手牌需要 5 张
+    WrongCount(usize),
+    /// 一副牌中每张牌只有一张
+    Duplicate(PokerCard),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCardError::BadRank(s) => write!(f, "无法识别的点数: {}", s),
+            ParseCardError::BadSuit(s) => write!(f, "无法识别的花色: {}", s),
+            ParseCardError::WrongCount(n) => write!(f, "一手牌需要 5 张，实际是 {} 张", n),
+            ParseCardError::Duplicate(card) => write!(f, "重复的牌: {}", card),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// 点数在前花色在后，例如 A♠、10♥，花色也可以写成字母 C/S/D/H，10 也可以写成 T
+impl FromStr for PokerCard {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<PokerCard, ParseCardError> {
+        let s = s.trim();
+        let suit_char = s.chars().last().ok_or_else(|| ParseCardError::BadRank(s.to_string()))?;
+        let rank = &s[..s.len() - suit_char.len_utf8()];
+        let suit = match suit_char.to_ascii_uppercase() {
+            '♣' | 'C' => PokerSuit::Clubs,
+            '♠' | 'S' => PokerSuit::Spades,
+            '♦' | 'D' => PokerSuit::Diamonds,
+            '♥' | 'H' => PokerSuit::Hearts,
+            _ => return Err(ParseCardError::BadSuit(s.to_string())),
+        };
+        let rank = match rank.to_ascii_uppercase().as_str() {
+            "T" => Rank::Ten,
+            "J" => Rank::Jack,
+            "Q" => Rank::Queen,
+            "K" => Rank::King,
+            "A" => Rank::Ace,
+            number => number.parse().ok().and_then(Rank::from_value).ok_or_else(|| ParseCardError::BadRank(s.to_string()))?,
+        };
+        Ok(PokerCard::new(suit, rank))
+    }
+}
+
+/// 解析用空白分隔的 5 张牌，例如 "A♠ K♠ Q♠ J♠ 10♠"，同一张牌出现两次是错误
+pub fn parse_hand(s: &str) -> Result<[PokerCard; 5], ParseCardError> {
+    let cards = s.split_whitespace().map(str::parse).collect::<Result<Vec<PokerCard>, _>>()?;
+    if let Some((_, card)) = cards.iter().enumerate().find(|&(i, card)| cards[..i].contains(card)) {
+        return Err(ParseCardError::Duplicate(*card));
+    }
+    let count = cards.len();
+    cards.try_into().map_err(|_| ParseCardError::WrongCount(count))
+}
+
+/// SplitMix64 伪随机数生成器，只有一个 u64 的状态，种子相同产生的序列就相同
+///
+/// 不能用于密码学，这里只用来洗牌
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// [0, n) 中均匀分布的整数，直接取余会让较小的数多出现一点，这里丢弃多余的部分
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "n 不能为 0");
+        // 2^64 除以 n 的余数，小于它的值丢弃后，剩下的个数正好是 n 的倍数
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let x = self.next_u64();
+            if x >= threshold {
+                return x % n;
+            }
+        }
+    }
+
+    /// Fisher-Yates 洗牌，每种排列出现的概率相同
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// 一副牌，最后一张是牌顶
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deck {
+    cards: Vec<PokerCard>,
+}
+
+impl Deck {
+    /// 按花色、点数排好的 52 张牌
+    pub fn new() -> Deck {
+        let mut cards = Vec::with_capacity(52);
+        for suit in PokerSuit::ALL {
+            for rank in Rank::ALL {
+                cards.push(PokerCard::new(suit, rank));
+            }
+        }
+        // 发牌从末尾取，反过来让第一张是 2♣
+        cards.reverse();
+        Deck { cards }
+    }
+
+    pub fn shuffle(&mut self, rng: &mut Rng) {
+        rng.shuffle(&mut self.cards);
+    }
+
+    /// 按发牌的顺序列出剩下的牌
+    pub fn cards(&self) -> impl Iterator<Item = PokerCard> + '_ {
+        self.cards.iter().rev().copied()
+    }
+
+    /// 还剩多少张
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// 从牌顶取 n 张，不够时返回 None，牌堆不变
+    pub fn deal(&mut self, n: usize) -> Option<Vec<PokerCard>> {
+        let rest = self.cards.len().checked_sub(n)?;
+        let mut cards = self.cards.split_off(rest);
+        cards.reverse();
+        Some(cards)
+    }
+
+    /// 像真人发牌一样轮流给 players 个人每人发一张，共发 5 轮，牌不够时返回 None，牌堆不变
+    pub fn deal_hands(&mut self, players: usize) -> Option<Vec<[PokerCard; 5]>> {
+        let cards = self.deal(players.checked_mul(5)?)?;
+        let hands = (0..players)
+            .map(|player| {
+                let hand: Vec<PokerCard> = cards.iter().skip(player).step_by(players).copied().collect();
+                hand.try_into().expect("每人 5 张")
+            })
+            .collect();
+        Some(hands)
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Deck {
+        Deck::new()
+    }
+}
+
+/// 牌型，按从小到大的顺序声明，派生的 Ord 先比较成员的顺序，牌型相同再依次比较字段
+///
+/// 字段是决定大小的点数，从大到小排列，例如两对是 (大对子, 小对子, 单张)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandRank {
+    HighCard([Rank; 5]),
+    OnePair(Rank, [Rank; 3]),
+    TwoPair(Rank, Rank, Rank),
+    ThreeOfAKind(Rank, [Rank; 2]),
+    /// 顺子最大的一张，A2345 的最大一张是 5
+    Straight(Rank),
+    Flush([Rank; 5]),
+    FullHouse(Rank, Rank),
+    FourOfAKind(Rank, Rank),
+    StraightFlush(Rank),
+}
+
+impl HandRank {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandRank::HighCard(_) => "高牌",
+            HandRank::OnePair(..) => "一对",
+            HandRank::TwoPair(..) => "两对",
+            HandRank::ThreeOfAKind(..) => "三条",
+            HandRank::Straight(_) => "顺子",
+            HandRank::Flush(_) => "同花",
+            HandRank::FullHouse(..) => "葫芦",
+            HandRank::FourOfAKind(..) => "四条",
+            HandRank::StraightFlush(Rank::Ace) => "皇家同花顺",
+            HandRank::StraightFlush(_) => "同花顺",
+        }
+    }
+}
+
+impl fmt::Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols = |ranks: &[Rank]| ranks.iter().map(|rank| rank.symbol()).collect::<Vec<_>>().join(" ");
+        match self {
+            HandRank::HighCard(ranks) | HandRank::Flush(ranks) => write!(f, "{} {}", self.name(), symbols(ranks)),
+            HandRank::OnePair(pair, kickers) => write!(f, "一对 {}，单张 {}", pair.symbol(), symbols(kickers)),
+            HandRank::TwoPair(high, low, kicker) => write!(f, "两对 {} 和 {}，单张 {}", high.symbol(), low.symbol(), kicker.symbol()),
+            HandRank::ThreeOfAKind(trips, kickers) => write!(f, "三条 {}，单张 {}", trips.symbol(), symbols(kickers)),
+            HandRank::StraightFlush(Rank::Ace) => f.write_str(self.name()),
+            HandRank::Straight(high) | HandRank::StraightFlush(high) => write!(f, "{}，最大 {}", self.name(), high.symbol()),
+            HandRank::FullHouse(trips, pair) => write!(f, "葫芦 {} 带 {}", trips.symbol(), pair.symbol()),
+            HandRank::FourOfAKind(quads, kicker) => write!(f, "四条 {}，单张 {}", quads.symbol(), kicker.symbol()),
+        }
+    }
+}
+
+/// 顺子最大的一张，不是顺子返回 None，ranks 已经从大到小排好且没有重复
+fn straight_high(ranks: &[Rank; 5]) -> Option<Rank> {
+    match ranks.map(Rank::value) {
+        // A 也可以当作 1，和 2345 组成最小的顺子
+        [14, 5, 4, 3, 2] => Some(Rank::Five),
+        [high, .., low] if high - low == 4 => Some(ranks[0]),
+        _ => None,
+    }
+}
+
+/// 判断 5 张牌的牌型。直接构造的数组可能有重复的牌，5 张点数相同时按四条计算，不会 panic
+pub fn evaluate(cards: &[PokerCard; 5]) -> HandRank {
+    // 按点数分组，(张数, 点数)，张数多的在前，张数相同点数大的在前
+    let mut groups: Vec<(usize, Rank)> = Vec::new();
+    for rank in cards.map(PokerCard::rank) {
+        match groups.iter_mut().find(|(_, r)| *r == rank) {
+            Some((count, _)) => *count += 1,
+            None => groups.push((1, rank)),
+        }
+    }
+    groups.sort_by(|a, b| b.cmp(a));
+    let flush = cards.iter().all(|card| card.suit() == cards[0].suit());
+    let ranks: [Rank; 5] = cards.map(PokerCard::rank);
+    let mut sorted = ranks;
+    sorted.sort_by(|a, b| b.cmp(a));
+    let straight = if groups.len() == 5 { straight_high(&sorted) } else { None };
+
+    // 分组的张数只可能是 5、4+1、3+2、3+1+1、2+2+1、2+1+1+1、1+1+1+1+1 这几种，其中 5 只在有重复的牌时出现，
+    // 用切片模式把前面几种写出来，编译器不知道只有这几种，最后一种 1+1+1+1+1 就用兜底的分支
+    match (groups.as_slice(), flush, straight) {
+        (_, true, Some(high)) => HandRank::StraightFlush(high),
+        (&[(4, quads), (1, kicker)], ..) => HandRank::FourOfAKind(quads, kicker),
+        (&[(5, rank)], ..) => HandRank::FourOfAKind(rank, rank),
+        (&[(3, trips), (2, pair)], ..) => HandRank::FullHouse(trips, pair),
+        (_, true, None) => HandRank::Flush(sorted),
+        (_, false, Some(high)) => HandRank::Straight(high),
+        (&[(3, trips), (1, a), (1, b)], ..) => HandRank::ThreeOfAKind(trips, [a, b]),
+        (&[(2, high), (2, low), (1, kicker)], ..) => HandRank::TwoPair(high, low, kicker),
+        (&[(2, pair), (1, a), (1, b), (1, c)], ..) => HandRank::OnePair(pair, [a, b, c]),
+        _ => HandRank::HighCard(sorted),
+    }
+}
+
+/// 从 5 张以上的牌中挑出最大的 5 张，例如德州扑克的 2 张手牌加 5 张公共牌，少于 5 张返回 None
+pub fn best_hand(cards: &[PokerCard]) -> Option<(HandRank, [PokerCard; 5])> {
+    let n = cards.len();
+    let mut best: Option<(HandRank, [PokerCard; 5])> = None;
+    // 依次枚举 5 个下标 a < b < c < d < e
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let hand = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                        let rank = evaluate(&hand);
+                        if best.as_ref().is_none_or(|(best_rank, _)| rank > *best_rank) {
+                            best = Some((rank, hand));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// 多手牌中最大的下标，平局时返回所有并列的下标
+pub fn winners(ranks: &[HandRank]) -> Vec<usize> {
+    let Some(best) = ranks.iter().max() else { return Vec::new() };
+    ranks.iter().enumerate().filter(|(_, rank)| *rank == best).map(|(i, _)| i).collect()
+}
+
+/// 电子扑克 Jacks or Better 的赔率表：押 1 赔多少，一对 J 以下不赔
+pub fn payout(rank: &HandRank) -> u32 {
+    match rank {
+        HandRank::StraightFlush(Rank::Ace) => 250,
+        HandRank::StraightFlush(_) => 50,
+        HandRank::FourOfAKind(..) => 25,
+        HandRank::FullHouse(..) => 9,
+        HandRank::Flush(_) => 6,
+        HandRank::Straight(_) => 4,
+        HandRank::ThreeOfAKind(..) => 3,
+        HandRank::TwoPair(..) => 2,
+        HandRank::OnePair(pair, _) if *pair >= Rank::Jack => 1,
+        HandRank::OnePair(..) | HandRank::HighCard(_) => 0,
+    }
+}
@@ -0,0 +1,217 @@
+//! # 两种连接
+//! 同一化类型一节想用一个枚举同时表示 TcpStream 和 TlsStream，真正的 TLS 需要证书和第三方库，
+//! 这里用 [XorStream] 代替：它包装另一个流，读写时把数据和密钥做异或，线路上的字节和明文不同，作用和 TLS 类似（当然毫无安全性可言）。
+//!
+//! + [Stream] 有 Tcp 和 Tls 两个成员，为它实现 Read 和 Write 之后，处理连接的代码不需要关心是哪一种
+//! + 连接建立后客户端先发送一个字节说明是哪一种，Tls 再跟上密钥的长度和密钥，服务端据此构造对应的成员
+//! + [echo_server] 在 127.0.0.1 上启动一个回显服务，课程和测试都用它
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread::{self, JoinHandle};
+
+/// 握手的第一个字节：明文
+const PLAIN: u8 = b'P';
+/// 握手的第一个字节：异或，之后是 1 字节的密钥长度和密钥
+const XOR: u8 = b'X';
+
+/// 读写时和密钥逐字节异或的流，读和写各自记录用到了密钥的哪一位
+#[derive(Debug)]
+pub struct XorStream<S> {
+    inner: S,
+    key: Vec<u8>,
+    read_pos: usize,
+    write_pos: usize,
+}
+
+impl<S> XorStream<S> {
+    /// 密钥不能为空
+    pub fn new(inner: S, key: &[u8]) -> XorStream<S> {
+        assert!(!key.is_empty(), "密钥不能为空");
+        XorStream { inner, key: key.to_vec(), read_pos: 0, write_pos: 0 }
+    }
+
+    /// 底层的流，从这里读写的是异或之后的字节
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// 从第 pos 个字节开始，把 buf 和密钥异或
+    fn apply(&self, buf: &mut [u8], pos: usize) {
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b ^= self.key[(pos + i) % self.key.len()];
+        }
+    }
+}
+
+impl<S: Read> Read for XorStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.apply(&mut buf[..n], self.read_pos);
+        self.read_pos += n;
+        Ok(n)
+    }
+}
+
+impl<S: Write> Write for XorStream<S> {
+    /// 底层只写入了一部分时，只有这一部分算作已经发送，剩下的下次从正确的密钥位置重新编码
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut encoded = buf.to_vec();
+        self.apply(&mut encoded, self.write_pos);
+        let n = self.inner.write(&encoded)?;
+        self.write_pos += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 握手中的密钥长度只占 1 个字节，且不能为 0
+fn key_len(key: &[u8]) -> io::Result<u8> {
+    u8::try_from(key.len())
+        .ok()
+        .filter(|&len| len > 0)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "密钥长度必须在 1 到 255 之间"))
+}
+
+impl XorStream<TcpStream> {
+    /// 客户端：发送握手和密钥
+    pub fn connect(mut tcp: TcpStream, key: &[u8]) -> io::Result<XorStream<TcpStream>> {
+        let len = key_len(key)?;
+        tcp.write_all(&[XOR, len])?;
+        tcp.write_all(key)?;
+        Ok(XorStream::new(tcp, key))
+    }
+}
+
+/// 一个连接，明文的 Tcp 或者加密的 Tls
+#[derive(Debug)]
+pub enum Stream {
+    Tcp(TcpStream),
+    /// 代替 native_tls::TlsStream<TcpStream>
+    Tls(XorStream<TcpStream>),
+}
+
+impl Stream {
+    /// 连接 addr，key 为 None 时是明文连接，否则用 key 加密。密钥不合法时不会建立连接
+    pub fn connect(addr: impl ToSocketAddrs, key: Option<&[u8]>) -> io::Result<Stream> {
+        if let Some(key) = key {
+            key_len(key)?;
+        }
+        let mut tcp = TcpStream::connect(addr)?;
+        match key {
+            None => {
+                tcp.write_all(&[PLAIN])?;
+                Ok(Stream::Tcp(tcp))
+            }
+            Some(key) => XorStream::connect(tcp, key).map(Stream::Tls),
+        }
+    }
+
+    /// 服务端：读取握手，决定连接是哪一种
+    pub fn accept(mut tcp: TcpStream) -> io::Result<Stream> {
+        let mut mode = [0; 1];
+        tcp.read_exact(&mut mode)?;
+        match mode[0] {
+            PLAIN => Ok(Stream::Tcp(tcp)),
+            XOR => {
+                let mut len = [0; 1];
+                tcp.read_exact(&mut len)?;
+                let mut key = vec![0; len[0] as usize];
+                tcp.read_exact(&mut key)?;
+                if key.is_empty() {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "密钥为空"));
+                }
+                Ok(Stream::Tls(XorStream::new(tcp, &key)))
+            }
+            other => Err(io::Error::new(io::ErrorKind::InvalidData, format!("未知的握手字节: {:#04x}", other))),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Stream::Tcp(_) => "Tcp",
+            Stream::Tls(_) => "Tls",
+        }
+    }
+
+    /// 底层的 TcpStream，可以用来设置超时、查看地址
+    pub fn tcp(&self) -> &TcpStream {
+        match self {
+            Stream::Tcp(tcp) => tcp,
+            Stream::Tls(tls) => tls.get_ref(),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(tcp) => tcp.read(buf),
+            Stream::Tls(tls) => tls.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(tcp) => tcp.write(buf),
+            Stream::Tls(tls) => tls.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(tcp) => tcp.flush(),
+            Stream::Tls(tls) => tls.flush(),
+        }
+    }
+}
+
+/// 把收到的数据原样发回去，直到对方关闭连接，返回回显的字节数。两种连接都走这一个函数
+pub fn echo(stream: &mut Stream) -> io::Result<u64> {
+    let mut buf = [0; 1024];
+    let mut total = 0;
+    loop {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            return Ok(total);
+        }
+        stream.write_all(&buf[..n])?;
+        total += n as u64;
+    }
+}
+
+/// 发送 message，读回同样长度的回显
+pub fn round_trip(stream: &mut Stream, message: &[u8]) -> io::Result<Vec<u8>> {
+    stream.write_all(message)?;
+    let mut reply = vec![0; message.len()];
+    stream.read_exact(&mut reply)?;
+    Ok(reply)
+}
+
+/// 回显服务的线程，结束时返回每个连接的类型和回显的字节数
+pub type EchoServer = JoinHandle<io::Result<Vec<(&'static str, u64)>>>;
+
+/// 在 127.0.0.1 的随机端口上启动回显服务，依次处理 connections 个连接后退出
+pub fn echo_server(connections: usize) -> io::Result<(SocketAddr, EchoServer)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let handle = thread::spawn(move || {
+        let mut served = Vec::with_capacity(connections);
+        for tcp in listener.incoming().take(connections) {
+            let mut stream = Stream::accept(tcp?)?;
+            let bytes = echo(&mut stream)?;
+            served.push((stream.kind(), bytes));
+        }
+        Ok(served)
+    });
+    Ok((addr, handle))
+}
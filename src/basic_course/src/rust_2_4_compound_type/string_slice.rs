@@ -1,6 +1,11 @@
 use std::ops::Add;
-use inline_c::predicates::predicate::le;
+use std::ffi::CString;
+use inline_c::assert_c;
 use common::{inner_print, outer_print};
+use crate::c_compare::{self, COutput};
+use crate::rust_2_14_formatted_output::table::{Align, Table};
+use super::heap_inspect::{self, Inspect, Region};
+use crate::utf8::{self, Unit};
 
 #[outer_print("复合类型")]
 pub fn compound_type() {
@@ -87,6 +92,21 @@ pub fn str_literal() {
     //实际上，s 的类型是 &str，因此你也可以这样声明：
     let s: &str = "Hello, world!";
     ///该切片指向了程序可执行文件中的某个点，这也是为什么字符串字面量是不可变的，因为 &str 是一个不可变引用。
+    // 打印出来看看，位置是可执行文件的只读数据：
+    println!("{}", s.inspect());
+    // 把 s 赋值给 t 只复制了指针和长度，两者指向同一块内存，字面量本身没有被复制
+    let t = s;
+    println!("s 和 t 指向同一个地址：{}", s.as_ptr() == t.as_ptr());
+    // 切片同样只是指向原来那块内存中间的某个位置
+    let world = &s[7..12];
+    println!("{}", world.inspect());
+    println!("world 的地址 = s 的地址 + 7：{}", world.as_ptr() as usize == s.as_ptr() as usize + 7);
+    // 内容相同的两个字面量通常会被编译器合并成一份，但这不是语言的保证
+    let u = "Hello, world!";
+    println!("另一个相同的字面量 u 和 s 的地址相同：{}", s.as_ptr() == u.as_ptr());
+    // String::from 会把字面量复制到堆上，从此和字面量再无关系
+    let owned = String::from(s);
+    println!("{}", owned.inspect());
     ();
 }
 
@@ -105,9 +125,58 @@ pub fn define_str() {
 
     /// str：它是一个不可变的、原始的字符串 slice，不拥有其内部数据的所有权。str 通常是通过引用 (&str) 来使用的，它指向一个固定大小的、UTF-8 编码的字符序列，该序列可以位于堆栈或堆中。
     /// String：它是一个可变的、 growable 的字符串类型，拥有其内部数据的所有权。String 存储在堆上，并且可以动态地改变其内容和长度。
+
+    // 同样的内容，&str 在可执行文件里，String 在堆上，两者的字节完全相同。
+    // 下面 | 分隔的是一个个 UTF-8 字符：英文字母 1 字节，汉字 3 字节，字符(char)本身则总是 4 字节
+    let literal = "hello中国";
+    let owned = String::from(literal);
+    println!("{}", literal.inspect());
+    println!("{}", owned.inspect());
+    let chars: Vec<char> = literal.chars().collect();
+    println!("{}", chars.inspect());
     ();
 }
 
+/// Rust 版本：&str 是 指针 + 长度，中间的 '\0' 只是一个普通字符
+pub fn nul_terminated_rust() -> String {
+    let s = "hello\0world";
+    format!(
+        "length = {}\nbytes = {}\nprint = {:?}\nCString::new = {:?}\n",
+        s.len(),
+        std::mem::size_of_val(s),
+        s,
+        CString::new(s).map_err(|e| e.nul_position()),
+    )
+}
+
+/// C 版本：字符串是以 '\0' 结尾的字符数组，strlen 遇到第一个 '\0' 就停下
+pub fn nul_terminated_c() -> Result<COutput, String> {
+    c_compare::run(|| assert_c! {
+        #inline_c_rs CFLAGS: "-Wall -Wextra -Werror"
+        #include <stdio.h>
+        #include <string.h>
+
+        int main() {
+            char s[] = "hello\0world";
+            printf("length = %zu\n", strlen(s));
+            printf("bytes = %zu\n", sizeof(s));
+            printf("print = %s\n", s);
+            return 0;
+        }
+    })
+}
+
+#[outer_print("与 C 对比：字符串的结尾")]
+/// C 的字符串只是一个指向字符的指针，长度要靠末尾的 '\0' 来确定：
+/// + strlen 需要从头扫描，复杂度 O(n)，而 &str 和 String 自带长度，len() 是 O(1)
+/// + 字符串中不能包含 '\0'，否则后面的内容就"消失"了；忘记写结尾的 '\0'，strlen 就会一直读到越界
+/// + C 的 sizeof 还把结尾的 '\0' 也算了进去，所以是 12 而不是 11
+///
+/// 需要把 Rust 字符串传给 C 时，使用 std::ffi::CString，它会在末尾补上 '\0'，并拒绝中间含有 '\0' 的字符串。
+pub fn compare_nul_terminated() {
+    c_compare::print_comparison(&nul_terminated_rust(), &nul_terminated_c());
+}
+
 #[outer_print("String 与 &str 的转换")]
 pub fn convert_str() {
     //将 str转换成String
@@ -301,8 +370,52 @@ pub fn utf8_str() {
     }
 
     inner_print!("获取子串");
-    //想要准确的从 UTF-8 字符串中获取子串是较为复杂的事情，例如想要从 hello中国人这种变长的字符串中取出某一个子串，使用标准库你是做不到的。 你需要在 crates.io 上搜索 utf8 来寻找想要的功能。
-
+    // 想要准确的从 UTF-8 字符串中获取子串是较为复杂的事情：&s[..] 的下标是字节，hello中国人 中的汉字每个占 3 个字节，
+    // &s[5..7] 会落在“中”的中间而 panic，s.get(5..7) 只能返回 None。
+    let s = "hello中国人";
+    println!("{:?} 有 {} 个字节，{} 个 char", s, s.len(), utf8::char_len(s));
+    println!("s.get(5..7) = {:?}", s.get(5..7));
+    // 按 char 计数就不用关心每个字符占几个字节了，越界时返回 None，不会 panic
+    println!("substring(s, 5..7) = {:?}", utf8::substring(s, 5..7));
+    println!("substring(s, 5..) = {:?}", utf8::substring(s, 5..));
+    println!("substring(s, 7..9) = {:?}", utf8::substring(s, 7..9));
+    // 它其实是先把 char 下标换算成字节下标，再用 &s[..]
+    let (start, end) = (utf8::byte_offset(s, 5, Unit::Char).unwrap(), utf8::byte_offset(s, 7, Unit::Char).unwrap());
+    println!("第 5 个 char 从第 {} 个字节开始，第 7 个从第 {} 个字节开始，&s[{}..{}] = {:?}", start, end, start, end, &s[start..end]);
+
+    inner_print!("字素簇");
+    // char 也不总是用户看到的“一个字”：é 可以写成 e 加上组合用的重音符 U+0301，国旗是两个区域指示符，
+    // 👨‍👩‍👧 是三个 emoji 和两个零宽连接符。Unicode 把用户看到的一个字叫做扩展字素簇，断开的规则见 utf8::grapheme
+    let s = "café 🇨🇳👨‍👩‍👧";
+    let s = s.replace('é', "e\u{301}");
+    println!("{:?}：{} 个字节，{} 个 char，{} 个字素簇", s, s.len(), utf8::char_len(&s), utf8::grapheme_len(&s));
+    let mut table = Table::new(["字素簇", "字节", "char", "包含的 char"]).align(1, Align::Right).align(2, Align::Right);
+    for (byte, grapheme) in utf8::grapheme_indices(&s) {
+        let chars: Vec<String> = grapheme.chars().map(|c| format!("U+{:04X}", c as u32)).collect();
+        let char_index = utf8::index_at(&s, byte, Unit::Char).unwrap();
+        table.row([format!("{:?}", grapheme), byte.to_string(), char_index.to_string(), chars.join(" ")]);
+    }
+    println!("{}", table);
+    // 按 char 截取会把重音符和 emoji 序列拆开，按字素簇截取不会
+    println!("substring(s, 3..4) = {:?}", utf8::substring(&s, 3..4));
+    println!("grapheme_substring(s, 3..4) = {:?}", utf8::grapheme_substring(&s, 3..4));
+    println!("grapheme_substring(s, 6..) = {:?}", utf8::grapheme_substring(&s, 6..));
+    // 字素簇下标和 char 下标之间也可以换算，落在一个字素簇中间的 char 没有对应的字素簇下标
+    println!("第 6 个字素簇是第 {:?} 个 char", utf8::convert(&s, 6, Unit::Grapheme, Unit::Char));
+    println!("第 10 个 char 是第 {:?} 个字素簇", utf8::convert(&s, 10, Unit::Char, Unit::Grapheme));
+
+    inner_print!("截断");
+    // String::truncate 的参数是字节数，不在 char 边界上会 panic，truncate_chars 按 char 计数
+    let mut title = String::from("hello中国人");
+    utf8::truncate_chars(&mut title, 6);
+    println!("truncate_chars(6) = {:?}", title);
+    // 截断显示的文本时按字素簇，才不会留下半个表情或者没有重音的 e
+    let mut title = s.clone();
+    utf8::truncate_chars(&mut title, 9);
+    println!("truncate_chars(9) = {:?}", title);
+    let mut title = s.clone();
+    utf8::truncate_graphemes(&mut title, 7);
+    println!("truncate_graphemes(7) = {:?}", title);
 }
 
 #[outer_print("字符串深度剖析")]
@@ -325,4 +438,31 @@ pub fn str_analyze() {
     // s 不再有效，内存被释放
     //与其它系统编程语言的 free 函数相同，Rust 也提供了一个释放内存的函数： drop，但是不同的是，其它语言要手动调用 free 来释放每一个变量占用的内存，而 Rust 则在变量离开作用域时，自动调用 drop 函数: 上面代码中，Rust 在结尾的 } 处自动调用 drop。
 
+    inner_print!("可增长：push_str 与重新分配");
+    // String::new() 不分配内存，指针是悬垂的；第一次追加时才向分配器申请，容量不够时再申请一块更大的内存，把内容搬过去，释放旧的。
+    // 容量每次大约翻倍，所以追加 n 个字节总共只需要 O(log n) 次重新分配。
+    // 重新分配时分配器有时可以原地扩大，这时容量变了，地址却没有变。
+    let steps = heap_inspect::push_str_growth("rust", 10);
+    let mut table = Table::new(["len", "capacity", "ptr", "说明"]).align(0, Align::Right).align(1, Align::Right);
+    for (i, step) in steps.iter().enumerate() {
+        let note = match i.checked_sub(1).map(|prev| steps[prev]) {
+            None => format!("String::new()，{}", heap_inspect::region_of(step.ptr)),
+            Some(prev) if prev.capacity == step.capacity => String::new(),
+            Some(prev) if prev.capacity == 0 => "第一次分配".to_string(),
+            Some(prev) if prev.ptr == step.ptr => "容量变大，原地扩展".to_string(),
+            Some(_) => "容量变大，重新分配并搬迁".to_string(),
+        };
+        table.row([step.len.to_string(), step.capacity.to_string(), format!("{:#x}", step.ptr), note]);
+    }
+    println!("{}", table);
+    // 预先知道大小时用 with_capacity 一次分配到位，之后的追加不会再重新分配
+    let mut s = String::with_capacity(40);
+    let before = s.as_ptr();
+    for _ in 0..10 {
+        s.push_str("rust");
+    }
+    println!("with_capacity(40) 追加 10 次后：{}", s.inspect().summary());
+    println!("地址没有变化：{}", before == s.as_ptr());
+    assert_ne!(heap_inspect::region_of(s.as_ptr() as usize), Region::ReadOnly);
+
 }
\ No newline at end of file
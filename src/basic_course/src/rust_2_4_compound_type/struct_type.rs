@@ -1,5 +1,10 @@
-use inline_c::Assert;
-use common::{inner_print, outer_print};
+use std::mem::{offset_of, size_of};
+use inline_c::assert_c;
+use common::{inner_print, outer_print, Layout};
+use lesson_runtime::layout;
+use crate::c_compare::{self, COutput};
+use crate::rust_2_3_ownership::ownership::StringRepr;
+use crate::rust_2_3_ownership::tracked::Tracked;
 
 #[outer_print("结构体语法")]
 pub fn struct_syntax() {
@@ -71,30 +76,32 @@ pub fn struct_syntax() {
     println!("{}", user2.active);
     // println!("{:?}",user2); //borrow of partially moved value: `user2`
     inner_print!("结构体整体所有权和部分字段所有权");
+    // 字段用 Tracked 包装，可以看到每个字段各自的所有权：u1.pass 被移动到 u2 之后，u1 剩下的 uname 依然会在作用域结束时被丢弃
     #[derive(Debug)]
     struct U {
-        uname: String,
-        pass: String,
+        uname: Tracked<String>,
+        pass: Tracked<String>,
     }
     let u1 = U {
-        uname: "uname1".to_string(),
-        pass: "123abc".to_string(),
+        uname: Tracked::new("u1.uname", "uname1".to_string()),
+        pass: Tracked::new("u1.pass", "123abc".to_string()),
     };
     println!("{:?},{},{}", u1,u1.uname,u1.pass);
     let u2=U {
-        uname: "uname2".to_string(),
-        pass: u1.pass
+        uname: Tracked::new("u2.uname", "uname2".to_string()),
+        pass: u1.pass.move_to("u2.pass")
     };
     println!("{}", u1.uname);
     println!("{:?}", u2);
     // println!("{}", u1.pass); //------- value moved here
     // println!("{:?}", u1);   //borrow of partially moved value: `u1`
+    // 作用域结束时先丢弃 u2 的两个字段，再丢弃 u1 剩下的 uname，"123abc" 只被丢弃了一次
 
 }
 
 #[outer_print("结构体内存排列")]
 pub fn struct_mem() {
-    #[derive(Debug)]
+    #[derive(Debug, Layout)]
     struct File {
         name: String,
         data: Vec<u8>,
@@ -113,14 +120,111 @@ pub fn struct_mem() {
         println!("{} is {} bytes long", f1_name, f1_length);
     }
     main();
-    //上面定义的 File 结构体在内存中的排列如下图所示：
-    // https://course.rs/basic/compound-type/struct.html#%E7%AE%80%E5%8C%96%E7%BB%93%E6%9E%84%E4%BD%93%E5%88%9B%E5%BB%BA
-    // 从图中可以清晰地看出 File 结构体两个字段 name 和 data 分别拥有底层两个 [u8] 数组的所有权(String 类型的底层也是 [u8] 数组)，
+    //上面定义的 File 结构体在内存中的排列，由 #[derive(Layout)] 在运行时测量出来：
+    println!("{}", layout::of::<File>());
+    // String 和 Vec<u8> 的字段是私有的，不能直接测量。它们都由 指针 + 容量 + 长度 三个字长组成，所有权一节中的 StringRepr 就是这个形状：
+    let raw = layout::of::<StringRepr>();
+    println!("{}", raw);
+    assert_eq!(raw.size, size_of::<String>());
+    assert_eq!(raw.size, size_of::<Vec<u8>>());
+    // File 结构体两个字段 name 和 data 分别拥有底层两个 [u8] 数组的所有权(String 类型的底层也是 [u8] 数组)，
     // 通过 ptr 指针指向底层数组的内存地址，这里你可以把 ptr 指针理解为 Rust 中的引用类型。
-    ///该图片也侧面印证了：把结构体中具有所有权的字段转移出去后，将无法再访问该字段，但是可以正常访问其它的字段。
+    // 堆上的数组不属于 File 本身，所以 File 的大小与字符串、数组的长度无关，永远是两个 String/Vec 的大小之和。
+    ///这也侧面印证了：把结构体中具有所有权的字段转移出去后，将无法再访问该字段，但是可以正常访问其它的字段。
     ();
 }
 
+/// 默认的布局(repr(Rust))下，编译器可以重排字段以减少填充
+#[allow(dead_code)]
+#[derive(Layout)]
+pub struct Meta {
+    readonly: bool,
+    size: u64,
+    mode: u16,
+}
+
+/// repr(C) 按声明顺序排列字段，与 C 完全一致
+#[allow(dead_code)]
+#[derive(Layout)]
+#[repr(C)]
+pub struct MetaC {
+    readonly: bool,
+    size: u64,
+    mode: u16,
+}
+
+/// Rust 版本的 File 与 Meta 的内存布局
+pub fn struct_layout_rust() -> String {
+    #[allow(dead_code)]
+    struct File {
+        name: String,
+        data: Vec<u8>,
+    }
+    format!(
+        "File: size {}, name@{}, data@{}\n\
+         Meta: size {}, readonly@{}, size@{}, mode@{}\n\
+         MetaC: size {}, readonly@{}, size@{}, mode@{}\n",
+        size_of::<File>(), offset_of!(File, name), offset_of!(File, data),
+        size_of::<Meta>(), offset_of!(Meta, readonly), offset_of!(Meta, size), offset_of!(Meta, mode),
+        size_of::<MetaC>(), offset_of!(MetaC, readonly), offset_of!(MetaC, size), offset_of!(MetaC, mode),
+    )
+}
+
+/// C 版本：String 和 Vec<u8> 都是 指针 + 容量 + 长度 三个字长
+pub fn struct_layout_c() -> Result<COutput, String> {
+    c_compare::run(|| assert_c! {
+        #inline_c_rs CFLAGS: "-Wall -Wextra -Werror"
+        #include <stdbool.h>
+        #include <stddef.h>
+        #include <stdint.h>
+        #include <stdio.h>
+
+        struct String {
+            uint8_t *ptr;
+            size_t cap;
+            size_t len;
+        };
+
+        struct Vec {
+            uint8_t *ptr;
+            size_t cap;
+            size_t len;
+        };
+
+        struct File {
+            struct String name;
+            struct Vec data;
+        };
+
+        struct Meta {
+            bool readonly;
+            uint64_t size;
+            uint16_t mode;
+        };
+
+        int main() {
+            printf("File: size %zu, name@%zu, data@%zu\n",
+                   sizeof(struct File), offsetof(struct File, name), offsetof(struct File, data));
+            printf("Meta: size %zu, readonly@%zu, size@%zu, mode@%zu\n",
+                   sizeof(struct Meta), offsetof(struct Meta, readonly),
+                   offsetof(struct Meta, size), offsetof(struct Meta, mode));
+            return 0;
+        }
+    })
+}
+
+#[outer_print("与 C 对比：结构体内存布局")]
+/// File 在 C 中也可以写成两个 指针 + 容量 + 长度 的结构体，两者的大小完全相同；区别在于 Rust 的 String 和 Vec 拥有堆上数据的所有权，离开作用域时会自动释放。
+///
+/// 字段的排列则不同：C 严格按声明顺序排列字段，并在中间插入填充字节以满足对齐；Rust 默认不保证字段顺序，编译器会重排字段来减少填充，
+/// 因此同样的 Meta 在 C 中占 24 字节，在 Rust 中只占 16 字节。需要与 C 交互时，用 #[repr(C)] 让 Rust 按 C 的规则布局。
+pub fn compare_struct_layout() {
+    c_compare::print_comparison(&struct_layout_rust(), &struct_layout_c());
+    // 两种布局的内存图，MetaC 中 readonly 后面有 7 字节填充，末尾还有 6 字节
+    println!("{}", layout::of::<Meta>());
+    println!("{}", layout::of::<MetaC>());
+}
+
 #[outer_print("元组结构体")]
 pub fn tuple_struct() {
     //结构体必须要有名称，但是结构体的字段可以没有名称，这种结构体长得很像元组，因此被称为元组结构体，例如：
@@ -1,4 +1,5 @@
 use common::{inner_print, outer_print};
+use crate::rust_2_4_compound_type::poker::{self, Deck, ParseCardError, PokerCard, Rng};
 
 #[outer_print("匹配字面值")]
 pub fn match_literal() {
@@ -354,3 +355,66 @@ pub fn at_bind() {
     println!("{}",x+y);
 }
 
+
+#[outer_print("实战：扑克牌局中的模式匹配")]
+/// 用 rust_2_4_compound_type::poker 中的牌和牌型，把前面的模式用在真实的牌局上
+pub fn poker_patterns() {
+    inner_print!("电子扑克的赔率");
+    // poker::payout 是一个穷尽的 match：成员中嵌套常量 StraightFlush(Rank::Ace)、匹配守卫 OnePair(pair, _) if *pair >= Rank::Jack、用 | 合并的分支，
+    // 少写任何一种牌型都无法编译
+    for hand in ["10♠ J♠ Q♠ K♠ A♠", "3♦ 3♣ 3♥ 9♠ 9♦", "Q♣ Q♦ 5♥ 8♠ 2♣", "7♣ 7♦ 5♥ 8♠ 2♣"] {
+        let rank = poker::evaluate(&poker::parse_hand(hand).unwrap());
+        println!("{} => {}，赔 {}", hand, rank, poker::payout(&rank));
+    }
+    // 解析失败同样是一个枚举，用 match 区分原因
+    for hand in ["A♠ K♠ Q♠ J♠", "A♠ K♠ Q♠ J♠ 1♠", "A♠ K♠ Q♠ J♠ 10X", "A♠ A♠ A♥ A♦ A♣"] {
+        match poker::parse_hand(hand) {
+            Ok(cards) => println!("{:?}", cards),
+            Err(ParseCardError::WrongCount(n)) => println!("{:?}：只有 {} 张", hand, n),
+            Err(ParseCardError::Duplicate(card)) => println!("{:?}：{} 出现了不止一次", hand, card),
+            Err(e @ (ParseCardError::BadRank(_) | ParseCardError::BadSuit(_))) => println!("{:?}：{}", hand, e),
+        }
+    }
+
+    inner_print!("德州扑克");
+    // 每人 2 张手牌，桌上依次翻开 3、1、1 张公共牌，每个人用 7 张中最好的 5 张比大小
+    const SEED: u64 = 7;
+    let mut deck = Deck::new();
+    deck.shuffle(&mut Rng::new(SEED));
+    let players: Vec<Vec<PokerCard>> = (0..3).map(|_| deck.deal(2).unwrap()).collect();
+    for (i, hole) in players.iter().enumerate() {
+        println!("玩家 {} 的手牌：{} {}", i + 1, hole[0], hole[1]);
+    }
+    let mut board: Vec<PokerCard> = Vec::new();
+    for n in [0, 3, 1, 1] {
+        board.extend(deck.deal(n).unwrap());
+        // 切片模式直接描述了牌局进行到哪一步
+        let street = match board.as_slice() {
+            [] => "翻牌前".to_string(),
+            [a, b, c] => format!("翻牌 {} {} {}", a, b, c),
+            [_, _, _, turn] => format!("转牌 {}", turn),
+            [_, _, _, _, river] => format!("河牌 {}", river),
+            // board 是普通的 Vec，编译器不知道它只会有 0、3、4、5 张，其余长度也要给出结果
+            other => format!("公共牌有 {} 张，不是正常的牌局", other.len()),
+        };
+        println!("{}", street);
+        let mut ranks = Vec::new();
+        for (i, hole) in players.iter().enumerate() {
+            let cards: Vec<PokerCard> = hole.iter().chain(&board).copied().collect();
+            // 不足 5 张时 best_hand 返回 None，只能看手牌是不是一对
+            match (poker::best_hand(&cards), hole.as_slice()) {
+                (Some((rank, _)), _) => {
+                    println!("  玩家 {}：{}", i + 1, rank);
+                    ranks.push(rank);
+                }
+                (None, [a, b]) if a.rank() == b.rank() => println!("  玩家 {}：口袋对 {}", i + 1, a.rank().symbol()),
+                (None, [a, b]) if a.suit() == b.suit() => println!("  玩家 {}：同花色的 {} {}", i + 1, a, b),
+                (None, _) => println!("  玩家 {}：-", i + 1),
+            }
+        }
+        if let [_, _, _, _, _] = board.as_slice() {
+            let winners: Vec<String> = poker::winners(&ranks).iter().map(|i| format!("玩家 {}", i + 1)).collect();
+            println!("赢家：{}", winners.join("、"));
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! # 消息解释器
+//! 为枚举实现方法一节中的 Message 原本只有一个空的 call，这里让它真正做事：
+//! + 文本命令解析成 Message，例如 `move 3 -4`、`write hello world`、`color 255 0 0`、`quit`，解析失败返回 [ParseMessageError]
+//! + [Message::call] 作用在一个很小的状态机 [Machine] 上：它记录光标位置、颜色和写下的文字，收到 Quit 之后停止，之后的消息都会被拒绝
+//!
+//! Message 的 Display 输出的就是命令文本，解析之后再格式化可以得到原来的命令。
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(String),
+    ChangeColor(i32, i32, i32),
+}
+
+/// 解析命令的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMessageError {
+    Empty,
+    UnknownCommand(String),
+    /// 命令和它需要的参数个数
+    WrongArgCount { command: &'static str, expected: usize },
+    BadNumber(String),
+}
+
+impl fmt::Display for ParseMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMessageError::Empty => f.write_str("空命令"),
+            ParseMessageError::UnknownCommand(command) => write!(f, "未知的命令: {}，可选 quit/move/write/color", command),
+            ParseMessageError::WrongArgCount { command, expected } => write!(f, "{} 需要 {} 个参数", command, expected),
+            ParseMessageError::BadNumber(s) => write!(f, "无法解析的数字: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for ParseMessageError {}
+
+fn number(s: &str) -> Result<i32, ParseMessageError> {
+    s.parse().map_err(|_| ParseMessageError::BadNumber(s.to_string()))
+}
+
+impl FromStr for Message {
+    type Err = ParseMessageError;
+
+    /// write 之后的第一个空白字符之后全部是要写的文字，保留其中的空白，其它命令的参数用空白分隔
+    fn from_str(line: &str) -> Result<Message, ParseMessageError> {
+        let line = line.trim_start();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        if command == "write" {
+            return Ok(Message::Write(rest.to_string()));
+        }
+        let args: Vec<&str> = rest.split_whitespace().collect();
+        match (command, args.as_slice()) {
+            ("", _) => Err(ParseMessageError::Empty),
+            ("quit", []) => Ok(Message::Quit),
+            ("move", [x, y]) => Ok(Message::Move { x: number(x)?, y: number(y)? }),
+            ("color", [r, g, b]) => Ok(Message::ChangeColor(number(r)?, number(g)?, number(b)?)),
+            ("quit", _) => Err(ParseMessageError::WrongArgCount { command: "quit", expected: 0 }),
+            ("move", _) => Err(ParseMessageError::WrongArgCount { command: "move", expected: 2 }),
+            ("color", _) => Err(ParseMessageError::WrongArgCount { command: "color", expected: 3 }),
+            (command, _) => Err(ParseMessageError::UnknownCommand(command.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::Quit => f.write_str("quit"),
+            Message::Move { x, y } => write!(f, "move {} {}", x, y),
+            Message::Write(text) => write!(f, "write {}", text),
+            Message::ChangeColor(r, g, b) => write!(f, "color {} {} {}", r, g, b),
+        }
+    }
+}
+
+/// 执行消息的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallError {
+    /// 已经收到过 Quit
+    Stopped,
+    /// 颜色的每个分量必须在 0 到 255 之间
+    BadColor(i32, i32, i32),
+    /// 移动之后坐标超出了 i32 的范围
+    OutOfBounds,
+}
+
+impl fmt::Display for CallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallError::Stopped => f.write_str("已经退出，不再接受消息"),
+            CallError::BadColor(r, g, b) => write!(f, "颜色 ({}, {}, {}) 的分量必须在 0 到 255 之间", r, g, b),
+            CallError::OutOfBounds => f.write_str("坐标超出范围"),
+        }
+    }
+}
+
+impl std::error::Error for CallError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Running,
+    Stopped,
+}
+
+/// 平面上的坐标
+pub type Point = (i32, i32);
+/// RGB 颜色
+pub type Color = (u8, u8, u8);
+
+/// Message 作用的对象：一支在平面上移动的笔
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub state: State,
+    pub position: Point,
+    pub color: Color,
+    /// 写下的文字，以及写的时候的位置和颜色
+    pub written: Vec<(Point, Color, String)>,
+}
+
+impl Machine {
+    /// 在原点，黑色
+    pub fn new() -> Machine {
+        Machine { state: State::Running, position: (0, 0), color: (0, 0, 0), written: Vec::new() }
+    }
+}
+
+impl Default for Machine {
+    fn default() -> Machine {
+        Machine::new()
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (r, g, b) = self.color;
+        let state = match self.state {
+            State::Running => "运行中",
+            State::Stopped => "已退出",
+        };
+        write!(f, "位置 {:?}，颜色 #{:02x}{:02x}{:02x}，写了 {} 段文字，{}", self.position, r, g, b, self.written.len(), state)
+    }
+}
+
+impl Message {
+    /// 执行消息，出错时 machine 保持不变
+    pub fn call(&self, machine: &mut Machine) -> Result<(), CallError> {
+        // 先匹配状态，再匹配消息，已经退出时所有消息都会被拒绝
+        match (machine.state, self) {
+            (State::Stopped, _) => return Err(CallError::Stopped),
+            (State::Running, Message::Quit) => machine.state = State::Stopped,
+            (State::Running, Message::Move { x, y }) => {
+                let (px, py) = machine.position;
+                let (Some(nx), Some(ny)) = (px.checked_add(*x), py.checked_add(*y)) else { return Err(CallError::OutOfBounds) };
+                machine.position = (nx, ny);
+            }
+            (State::Running, Message::Write(text)) => machine.written.push((machine.position, machine.color, text.clone())),
+            (State::Running, &Message::ChangeColor(r, g, b)) => {
+                let (Ok(r8), Ok(g8), Ok(b8)) = (u8::try_from(r), u8::try_from(g), u8::try_from(b)) else {
+                    return Err(CallError::BadColor(r, g, b));
+                };
+                machine.color = (r8, g8, b8);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 逐行解析并执行命令，跳过空行和 # 开头的注释，返回每一行的行号、命令和结果
+pub fn interpret<'a>(script: &'a str, machine: &mut Machine) -> Vec<(usize, &'a str, Result<(), String>)> {
+    script
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            let result = match line.parse::<Message>() {
+                Ok(message) => message.call(machine).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            (i + 1, line, result)
+        })
+        .collect()
+}
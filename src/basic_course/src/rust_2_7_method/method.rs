@@ -1,26 +1,27 @@
 use common::{inner_print, outer_print};
+use super::message::{self, Machine, Message};
 
 
-struct Circle {
-    x: f64,
-    y: f64,
-    radius: f64,
+pub struct Circle {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) radius: f64,
 }
 
 impl Circle {
     // new是Circle的关联函数，因为它的第一个参数不是self，且new并不是关键字
     // 这种方法往往用于初始化当前结构体的实例
-    fn new(x: f64, y: f64, radius: f64) -> Circle {
+    pub fn new(x: f64, y: f64, radius: f64) -> Circle {
         Circle {
             x,
             y,
             radius,
         }
     }
-    fn area(&self) -> f64 {
+    pub fn area(&self) -> f64 {
         self.radius * self.radius * std::f64::consts::PI
     }
-    fn radius(self: &Self) -> f64 {
+    pub fn radius(self: &Self) -> f64 {
         self.radius
     }
     // impl Circle {} 表示为 Circle 实现方法(impl 是实现 implementation 的缩写)，这样的写法表明 impl 语句块中的一切都是跟 Circle 相关联的。
@@ -58,9 +59,9 @@ pub fn define_method() {
 }
 
 #[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
+pub struct Rectangle {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
 }
 
 impl Rectangle {
@@ -71,7 +72,7 @@ impl Rectangle {
     fn can_hold_(&self, other: &Rectangle) -> bool {
         self.width > other.width && self.height > other.height
     }
-    fn new(w: u32, h: u32) -> Rectangle {
+    pub fn new(w: u32, h: u32) -> Rectangle {
         Rectangle { width: w, height: h }
     }
 }
@@ -115,23 +116,39 @@ pub fn multipart_impl() {
 
 }
 
-
-#[allow(unused)]
-enum Message {
-    Quit,
-    Move { x: i32, y: i32 },
-    Write(String),
-    ChangeColor(i32, i32, i32),
-}
-
-impl Message {
-    fn call(&self){
-
-    }
-}
 #[outer_print("为枚举实现方法")]
 ///枚举类型之所以强大，不仅仅在于它好用、可以同一化类型，还在于，我们可以像结构体一样，为枚举实现方法：
 pub fn impl_enum() {
+    // Message 定义在 message 模块中，call 是它的方法，&self 是当前这条消息，machine 是被它修改的状态
+    let mut machine = Machine::new();
     let m = Message::Write(String::from("hello"));
-    m.call();
+    m.call(&mut machine).unwrap();
+    println!("{:?} => {}", m, machine);
+
+    inner_print!("命令解释器");
+    // 文本命令通过 FromStr 解析成 Message，再调用 call，解析和执行都可能失败，各自返回自己的错误枚举
+    let script = "\
+# 移动到 (3, 4)，换成红色，写字
+move 3 4
+color 255 0 0
+write hello
+move -1 0
+write  world
+# 下面几行会出错
+jump 1 2
+color 256 0 0
+move 1
+quit
+write again";
+    let mut machine = Machine::new();
+    for (line_no, line, result) in message::interpret(script, &mut machine) {
+        match result {
+            Ok(()) => println!("{:>2} {:<16} => 成功", line_no, line),
+            Err(e) => println!("{:>2} {:<16} => 错误：{}", line_no, line, e),
+        }
+    }
+    println!("{}", machine);
+    for (position, color, text) in &machine.written {
+        println!("在 {:?} 用 {:?} 写了 {:?}", position, color, text);
+    }
 }
\ No newline at end of file
@@ -1 +1,2 @@
-pub mod method;
\ No newline at end of file
+pub mod method;
+pub mod message;
@@ -0,0 +1,223 @@
+//! # 深入了解特征
+//! 特征之于 Rust 更甚于接口之于其他语言，因此特征在 Rust 中很重要也相对较为复杂，我们决定把特征分为两篇进行介绍，第一篇在之前已经讲过，现在就是第二篇：关联类型、默认泛型类型参数、完全限定语法、特征定义中的特征约束以及 newtype 模式。
+
+use std::fmt;
+use std::ops::Add;
+use common::{inner_print, outer_print};
+use crate::rust_2_7_method::method::{Circle, Rectangle};
+use super::r#trait::{Canvas, Shape, Square};
+use super::trait_object::Scene;
+use crate::rust_2_14_formatted_output::table::display_width;
+
+/// 把场景渲染成某种输出，输出的类型由实现者通过关联类型 Output 决定
+pub trait Renderer {
+    type Output;
+
+    fn render(&self, scene: &Scene) -> Self::Output;
+}
+
+/// 渲染成字符画
+pub struct AsciiRenderer {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Renderer for AsciiRenderer {
+    type Output = Canvas;
+
+    fn render(&self, scene: &Scene) -> Canvas {
+        scene.render(self.width, self.height)
+    }
+}
+
+/// 只统计每种图形的名称和面积
+pub struct SummaryRenderer;
+
+impl Renderer for SummaryRenderer {
+    type Output = Vec<(String, f64)>;
+
+    fn render(&self, scene: &Scene) -> Vec<(String, f64)> {
+        scene.shapes().iter().map(|s| (s.name(), s.area())).collect()
+    }
+}
+
+#[outer_print("关联类型")]
+/// 关联类型是在特征定义的语句块中，申明一个自定义类型，这样就可以在特征的方法签名中使用该类型。
+/// 标准库中的迭代器特征 Iterator 就有一个 Item 关联类型，用于替代遍历的值的类型。
+/// 同样是可以"由实现者决定类型"，为何不用泛型 trait Renderer<Output>？
+/// + 用泛型的话，同一个类型可以实现多次 Renderer<Canvas>、Renderer<String>，调用时必须写明是哪一个
+/// + 用关联类型，一个类型只能有一种 Output，签名中只需写 Self::Output，代码可读性更好
+pub fn associated_type() {
+    let mut scene = Scene::new();
+    scene.add(Rectangle::new(2, 1)).add(Circle::new(4.0, 1.0, 1.0));
+    let canvas = AsciiRenderer { width: 6, height: 2 }.render(&scene);
+    println!("{}", canvas);
+    let summary = SummaryRenderer.render(&scene);
+    assert_eq!(summary[0], ("矩形".to_string(), 2.0));
+    println!("{:?}", summary);
+
+    // 使用约束时可以指定关联类型，例如只接收输出为 Canvas 的渲染器
+    fn to_text<R: Renderer<Output = Canvas>>(r: &R, scene: &Scene) -> String {
+        r.render(scene).to_string()
+    }
+    assert_eq!(to_text(&AsciiRenderer { width: 6, height: 2 }, &scene), canvas.to_string());
+}
+
+/// 二维向量，用来平移图形
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Offset {
+    pub dx: f64,
+    pub dy: f64,
+}
+
+/// Add 的定义是 trait Add<Rhs = Self>，Rhs 有默认类型 Self，这里两个 Offset 相加使用的就是默认值
+impl Add for Offset {
+    type Output = Offset;
+
+    fn add(self, other: Offset) -> Offset {
+        Offset { dx: self.dx + other.dx, dy: self.dy + other.dy }
+    }
+}
+
+/// 为 Square + Offset 指定 Rhs = Offset，结果是平移后的正方形
+impl Add<Offset> for Square {
+    type Output = Square;
+
+    fn add(self, offset: Offset) -> Square {
+        Square { x: self.x + offset.dx, y: self.y + offset.dy, ..self }
+    }
+}
+
+#[outer_print("默认泛型类型参数")]
+/// 当使用泛型类型参数时，可以为其指定一个默认的具体类型，例如标准库中的 std::ops::Add 特征：
+/// ```
+/// trait Add<RHS=Self> {
+///     type Output;
+///     fn add(self, rhs: RHS) -> Self::Output;
+/// }
+/// ```
+/// 默认类型参数主要用于两个方面：
+/// 1. 减少实现的样板代码
+/// 2. 扩展类型但是无需大幅修改现有的代码
+pub fn default_generic_param() {
+    let offset = Offset { dx: 1.0, dy: 0.0 } + Offset { dx: 1.0, dy: 2.0 };
+    assert_eq!(offset, Offset { dx: 2.0, dy: 2.0 });
+    let square = Square { x: 0.0, y: 0.0, side: 1.0 } + offset;
+    assert_eq!((square.x, square.y), (2.0, 2.0));
+    let mut canvas = Canvas::new(4, 4);
+    square.draw(&mut canvas);
+    println!("{}", canvas);
+}
+
+/// 能给出自身类型名的图形，type_name 没有 self 参数，是一个关联函数
+pub trait Named {
+    fn type_name() -> String;
+}
+
+impl Named for Circle {
+    fn type_name() -> String {
+        String::from("Circle")
+    }
+}
+
+impl Circle {
+    /// 与 Shape::name 同名的固有方法
+    pub fn name(&self) -> String {
+        format!("circle@({}, {})", self.x, self.y)
+    }
+
+    /// 与 Named::type_name 同名的关联函数
+    pub fn type_name() -> String {
+        String::from("圆形")
+    }
+}
+
+#[outer_print("调用同名的方法")]
+/// 不同特征拥有同名的方法是很正常的事情，你没有任何办法阻止这一点；甚至除了特征上的同名方法外，在你的类型上，也有同名方法：
+/// + 当调用 circle.name() 时，编译器默认调用类型中定义的固有方法
+/// + 调用特征上的方法，需要显式调用：Shape::name(&circle)
+/// + 关联函数没有 self 参数，编译器无法推断该用哪个实现，这时就需要完全限定语法 <Circle as Named>::type_name()
+pub fn fully_qualified_syntax() {
+    let circle = Circle::new(1.0, 2.0, 1.0);
+    assert_eq!(circle.name(), "circle@(1, 2)");
+    assert_eq!(Shape::name(&circle), "圆");
+    assert_eq!(<Circle as Shape>::name(&circle), "圆");
+
+    assert_eq!(Circle::type_name(), "圆形");
+    assert_eq!(<Circle as Named>::type_name(), "Circle");
+    // 下面的代码无法编译，因为 Named 可能被多个类型实现，编译器不知道该调用哪一个：
+    // Named::type_name();
+    // error[E0790]: cannot call associated function on trait without specifying the corresponding `impl` type
+
+    // 完全限定语法的完整形式：<Type as Trait>::function(receiver_if_method, next_arg, ...);
+    inner_print!("area 也有两个");
+    // Circle 的固有方法 area 和 Shape::area 恰好算的是同一个值
+    assert_eq!(circle.area(), <Circle as Shape>::area(&circle));
+}
+
+/// 带边框输出的图形：要实现 Framed，类型必须先实现 Shape 和 Display，这就是特征定义中的特征约束，也叫 supertrait
+pub trait Framed: Shape + fmt::Display {
+    fn framed(&self) -> String {
+        let text = format!("{} | {}", self, self.name());
+        // 中文占两列，按显示宽度画边框才能对齐
+        let line = "-".repeat(display_width(&text) + 2);
+        format!("+{}+\n| {} |\n+{}+", line, text, line)
+    }
+}
+
+impl Framed for Rectangle {}
+
+impl Framed for Circle {}
+
+#[outer_print("特征定义中的特征约束")]
+/// 有时，我们会需要让某个特征 A 能使用另一个特征 B 的功能(另一种形式的特征约束)，这种情况下，不仅仅要为类型实现特征 A，还要为类型实现特征 B 才行，这就是 supertrait。
+pub fn supertrait() {
+    let rect = Rectangle::new(2, 3);
+    println!("{}", rect.framed());
+    assert_eq!(
+        Circle::new(0.0, 0.0, 1.0).framed(),
+        "+--------------------+\n| 圆 (0, 0) r=1 | 圆 |\n+--------------------+"
+    );
+    // Square 没有实现 Display，因此无法实现 Framed：
+    // impl Framed for Square {}
+    // error[E0277]: `Square` doesn't implement `std::fmt::Display`
+}
+
+/// 为 Vec<Box<dyn Shape>> 包一层，就可以为它实现 Display 了
+pub struct Shapes(pub Vec<Box<dyn Shape>>);
+
+impl fmt::Display for Shapes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self.0.iter().map(|s| s.name()).collect();
+        write!(f, "[{}]", names.join(", "))
+    }
+}
+
+impl Shapes {
+    /// newtype 不会自动拥有内部类型的方法，需要什么就暴露什么
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[outer_print("在外部类型上实现外部特征(newtype)")]
+/// 在特征章节中，有提到孤儿规则，简单来说，就是特征或者类型必需至少有一个是本地的，才能在此类型上定义特征。
+/// 这里提供一个办法来绕过孤儿规则，那就是使用 newtype 模式，简而言之：就是为一个元组结构体创建新类型。该元组结构体封装有一个字段，该字段就是希望实现特征的具体类型。
+/// 该封装类型是本地的，因此我们可以为此类型实现外部的特征。newtype 不仅仅能实现以上的功能，而且它在运行时没有任何性能损耗，因为在编译期，该类型会被自动忽略。
+pub fn newtype() {
+    // Vec 和 Display 都定义在标准库中，无法直接实现：
+    // impl fmt::Display for Vec<Box<dyn Shape>> {}
+    // error[E0117]: only traits defined in the current crate can be implemented for types defined outside of the crate
+    let shapes = Shapes(vec![
+        Box::new(Rectangle::new(1, 1)),
+        Box::new(Square { x: 0.0, y: 0.0, side: 1.0 }),
+    ]);
+    assert_eq!(shapes.to_string(), "[矩形, 正方形]");
+    assert_eq!(shapes.len(), 2);
+    println!("shapes = {}", shapes);
+    assert_eq!(std::mem::size_of::<Shapes>(), std::mem::size_of::<Vec<Box<dyn Shape>>>());
+}
@@ -1,5 +1,13 @@
-use common::outer_print;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hint::black_box;
+use std::ops::Add;
+use std::time::{Duration, Instant};
+use common::{inner_print, outer_print};
 use crate::add;
+use crate::rust_2_7_method::method::{Circle, Rectangle};
+use crate::rust_2_14_formatted_output::table::{Align, Table};
+use super::r#trait::{Shape, Square};
 
 #[outer_print("泛型")]
 pub fn intro(){
@@ -8,38 +16,347 @@ pub fn intro(){
     // }
     // println!("a+b={:}",add(1,2))
     // 将之前的代码改成上面这样，就是 Rust 泛型的初印象，这段代码虽然很简洁，但是并不能编译通过，我们会在后面进行详细讲解，现在只要对泛型有个大概的印象即可。
+    /// 之所以不能编译，是因为不是所有 T 类型都能进行相加操作，因此我们需要用 std::ops::Add<Output = T> 对 T 进行限制：
+    fn add<T: Add<Output = T>>(a: T, b: T) -> T {
+        a + b
+    }
+    println!("add i8: {}", add(2i8, 3i8));
+    println!("add i32: {}", add(20, 30));
+    println!("add f64: {}", add(1.23, 1.23));
 }
 
 #[outer_print("泛型详解")]
 pub fn generic_explained(){
     /// 上面代码的 T 就是泛型参数，实际上在 Rust 中，泛型参数的名称你可以任意起，但是出于惯例，我们都用 T ( T 是 type 的首字母)来作为首选，这个名称越短越好，除非需要表达含义，否则一个字母是最完美的。
     // 使用泛型参数，有一个先决条件，必需在使用前对其进行声明：
-    fn largest<T>(list: &[T]) -> T {
-        
+    // fn largest<T>(list: &[T]) -> T {}
+    // 该泛型函数的作用是从列表中找出最大的值，其中列表中的元素类型为 T。首先 largest<T> 对泛型参数 T 进行了声明，然后才在函数参数中进行使用该泛型参数 list: &[T]
+    // 不过只有声明还不够，函数体中需要用 > 比较元素，还要把元素从切片中拷贝出来返回，因此 T 必须满足：
+    /// + std::cmp::PartialOrd，否则会报错 binary operation `>` cannot be applied to type `T`
+    /// + Copy，否则会报错 cannot move out of type [T], a non-copy slice
+    fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
+        let mut largest = list[0];
+        for &item in list.iter() {
+            if item > largest {
+                largest = item;
+            }
+        }
+        largest
+    }
+    assert_eq!(largest(&[34, 50, 25, 100, 65]), 100);
+    assert_eq!(largest(&[1.5, -2.0, 0.3]), 1.5);
+    assert_eq!(largest(&['y', 'm', 'a', 'q']), 'y');
+
+    inner_print!("不需要 Copy 的版本");
+    // 如果不想限制 T 必须是 Copy 的，可以返回元素的引用，这样 String 这种堆上的类型也能使用了
+    fn largest_ref<T: PartialOrd>(list: &[T]) -> &T {
+        let mut largest = &list[0];
+        for item in list {
+            if item > largest {
+                largest = item;
+            }
+        }
+        largest
     }
+    let words = vec![String::from("hello"), String::from("world"), String::from("rust")];
+    assert_eq!(largest_ref(&words), "world");
+    println!("largest word: {}", largest_ref(&words));
+}
 
+/// 结构体中的字段类型也可以用泛型来定义，x 和 y 的类型可以不同
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point<T, U> {
+    pub x: T,
+    pub y: U,
 }
 
 #[outer_print("结构体中使用泛型")]
 pub fn generic_struct(){
+    /// 提前声明，跟泛型函数定义类似，首先我们在使用泛型参数之前必需要进行声明 Point<T>，接着就可以在结构体的字段类型中使用 T 来替代具体的类型
+    /// x 和 y 是相同的类型
+    #[derive(Debug)]
+    struct SamePoint<T> {
+        x: T,
+        y: T,
+    }
+    let integer = SamePoint { x: 5, y: 10 };
+    let float = SamePoint { x: 1.0, y: 4.0 };
+    println!("{:?} {:?}", integer, float);
+    // 下面的代码无法编译，因为 x 和 y 被限制为同一类型：
+    // let p = SamePoint { x: 1, y: 1.1 };
+    // error[E0308]: mismatched types
 
+    // 如果想让 x 和 y 既能类型相同，又能类型不同，就需要使用不同的泛型参数：
+    let p = Point { x: 1, y: 1.1 };
+    let q = Point { x: "x", y: 'y' };
+    println!("{:?} {:?}", p, q);
+    // 需要注意的是，所有的泛型参数都要提前声明：Point<T, U> ! 但是如果你的结构体变成这鬼样：struct Woo<T, U, V, W, X>，那么你需要考虑拆分这个结构体，减少泛型参数的个数和代码复杂度。
 }
 
 #[outer_print("枚举中使用泛型")]
 pub fn generic_enum(){
+    /// 提到枚举类型，Option 永远是第一个应该被想起来的：
+    /// ```
+    /// enum Option<T> {
+    ///     Some(T),
+    ///     None,
+    /// }
+    /// ```
+    /// Option<T> 是一个拥有泛型 T 的枚举类型，它第一个成员是 Some(T)，存放了一个类型为 T 的值。得益于泛型的引入，我们可以在任何一个需要返回值的函数中，去使用 Option<T> 枚举类型来做为返回值，用于返回一个任意类型的值 Some(T)，或者没有值 None。
+    /// 对于枚举而言，卧龙凤雏永远是绕不过去的存在：如果说 Option 是卧龙，那么 Result 就一定是凤雏：
+    /// ```
+    /// enum Result<T, E> {
+    ///     Ok(T),
+    ///     Err(E),
+    /// }
+    /// ```
+    fn parse_number(s: &str) -> Result<i32, String> {
+        s.parse::<i32>().map_err(|e| format!("{}: {}", s, e))
+    }
+    assert_eq!(parse_number("42"), Ok(42));
+    println!("{:?}", parse_number("4x2"));
+
+    // 自己定义一个带泛型的枚举：树的节点要么是叶子，要么是带左右子树的分支
+    #[derive(Debug)]
+    enum Tree<T> {
+        Leaf(T),
+        Node(Box<Tree<T>>, Box<Tree<T>>),
+    }
+    fn sum<T: Add<Output = T> + Copy>(tree: &Tree<T>) -> T {
+        match tree {
+            Tree::Leaf(v) => *v,
+            Tree::Node(l, r) => sum(l) + sum(r),
+        }
+    }
+    let tree = Tree::Node(
+        Box::new(Tree::Leaf(1.5)),
+        Box::new(Tree::Node(Box::new(Tree::Leaf(2.0)), Box::new(Tree::Leaf(0.5)))),
+    );
+    assert_eq!(sum(&tree), 4.0);
+    println!("{:?}", tree);
+}
 
+impl<T, U> Point<T, U> {
+    /// 使用泛型参数前，依然需要提前声明：impl<T, U>，只有提前声明了，我们才能在 Point<T, U> 中使用它们
+    pub fn x(&self) -> &T {
+        &self.x
+    }
+
+    /// 方法中还可以定义额外的泛型参数：用 self 的 x 和 other 的 y 组合出一个新的点
+    pub fn mixup<V, W>(self, other: Point<V, W>) -> Point<T, W> {
+        Point {
+            x: self.x,
+            y: other.y,
+        }
+    }
 }
+
+/// 为具体的泛型类型实现方法：只有 Point<f64, f64> 才有 distance_from_origin
+impl Point<f64, f64> {
+    pub fn distance_from_origin(&self) -> f64 {
+        (self.x.powi(2) + self.y.powi(2)).sqrt()
+    }
+}
+
 #[outer_print("方法中使用泛型")]
 pub fn generic_method(){
+    let p = Point { x: 5, y: 10 };
+    println!("p.x = {}", p.x());
+
+    inner_print!("mixup");
+    // 这个例子中，T,U 是定义在结构体 Point 上的泛型参数，V,W 是单独定义在方法 mixup 上的泛型参数，它们并不冲突，说白了，你可以理解为，一个是结构体泛型，一个是函数泛型。
+    let p1 = Point { x: 5, y: 10.4 };
+    let p2 = Point { x: "Hello", y: 'c' };
+    let p3 = p1.mixup(p2);
+    assert_eq!(p3, Point { x: 5, y: 'c' });
+    println!("p3.x = {}, p3.y = {}", p3.x, p3.y);
+
+    inner_print!("为具体的泛型类型实现方法");
+    let p = Point { x: 3.0, y: 4.0 };
+    assert_eq!(p.distance_from_origin(), 5.0);
+    // Point<i32, i32> 没有这个方法：
+    // Point { x: 3, y: 4 }.distance_from_origin();
+    // error[E0599]: no method named `distance_from_origin` found for struct `Point<{integer}, {integer}>`
+}
+
+/// 打印任意长度的数组，N 是一个 const 泛型参数，它的值是 usize 类型
+pub fn display_array<T: Debug, const N: usize>(arr: [T; N]) -> String {
+    format!("{}个元素: {:?}", N, arr)
+}
+
+/// const 泛型也能出现在结构体中：一个容量固定、在栈上分配的缓冲区
+#[derive(Debug)]
+pub struct Buffer<const N: usize> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Buffer<N> {
+    pub fn new() -> Buffer<N> {
+        Buffer { data: [0; N], len: 0 }
+    }
+
+    /// 缓冲区满了就返回 Err，把放不下的值还给调用者
+    pub fn push(&mut self, byte: u8) -> Result<(), u8> {
+        if self.len == N {
+            return Err(byte);
+        }
+        self.data[self.len] = byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
 
+    pub const CAPACITY: usize = N;
+}
+
+impl<const N: usize> Default for Buffer<N> {
+    fn default() -> Self {
+        Buffer::new()
+    }
 }
 
 #[outer_print("const泛型")]
+/// 在之前的泛型中，可以抽象为一句话：针对类型实现的泛型，所有的泛型都是为了抽象不同的类型，那有没有针对值的泛型？
+/// 数组的类型 [i32; 2] 和 [i32; 3] 是不同的数组类型，想用一个函数处理所有长度的数组，以前只能借助切片 &[i32]，
+/// 现在可以用 const 泛型，也就是针对值的泛型，正好可以用于处理数组长度的问题：
 pub fn generic_const(){
+    let arr: [i32; 3] = [1, 2, 3];
+    println!("{}", display_array(arr));
+    let arr: [i32; 2] = [1, 2];
+    println!("{}", display_array(arr));
+    assert_eq!(display_array(["a"; 4]), "4个元素: [\"a\", \"a\", \"a\", \"a\"]");
+
+    inner_print!("const 泛型结构体");
+    let mut buf: Buffer<2> = Buffer::new();
+    assert_eq!(buf.push(1), Ok(()));
+    assert_eq!(buf.push(2), Ok(()));
+    assert_eq!(buf.push(3), Err(3));
+    assert_eq!(buf.as_slice(), &[1, 2]);
+    assert_eq!(Buffer::<16>::CAPACITY, 16);
+    println!("{:?}", buf);
+    // N 是编译期常量，Buffer<2> 和 Buffer<16> 的大小在编译期就确定了
+    assert_eq!(std::mem::size_of::<Buffer<16>>(), 16 + std::mem::size_of::<usize>());
 
+    // const 泛型的值只能是整数、bool、char，且在泛型表达式中(例如 N + 1)使用目前仍需要 nightly 的 generic_const_exprs
+}
+
+/// 一次静态分发与动态分发的测量结果
+#[derive(Debug, Clone, Copy)]
+pub struct DispatchReport {
+    pub iterations: u32,
+    pub static_time: Duration,
+    pub dyn_time: Duration,
+    /// 二进制中 total_area_static 的实例数量，读取失败时为 None
+    pub static_symbols: Option<usize>,
+    /// 二进制中 total_area_dyn 的实例数量，读取失败时为 None
+    pub dyn_symbols: Option<usize>,
+}
+
+/// 泛型版本：编译器会为 Circle、Rectangle、Square 各生成一份
+#[inline(never)]
+fn total_area_static<T: Shape>(shapes: &[T]) -> f64 {
+    let mut total = 0.0;
+    for shape in shapes {
+        total += shape.area();
+    }
+    total
+}
+
+/// 特征对象版本：只有一份代码，运行时通过虚表找到 area
+#[inline(never)]
+fn total_area_dyn(shapes: &[&dyn Shape]) -> f64 {
+    let mut total = 0.0;
+    for shape in shapes {
+        total += shape.area();
+    }
+    total
+}
+
+/// 在当前可执行文件中统计名字里带有 name 的函数符号个数
+///
+/// 不依赖 nm 等外部工具：符号表和调试信息中的函数名都是以 \0 结尾的字符串，
+/// 把文件按 \0 切开，挑出 Rust 的修饰名(_ZN 或 _R 开头)去重即可，每个单态化的实例都有不同的哈希后缀。
+/// 二进制被 strip 过时找不到任何符号，返回 Some(0)
+pub fn count_symbols(name: &str) -> Option<usize> {
+    let exe = std::env::current_exe().ok()?;
+    let bytes = std::fs::read(exe).ok()?;
+    let needle = format!("{}{}", name.len(), name);
+    let symbols: HashSet<&[u8]> = bytes
+        .split(|&b| b == 0)
+        .filter(|s| s.starts_with(b"_ZN") || s.starts_with(b"_R"))
+        .filter(|s| s.windows(needle.len()).any(|w| w == needle.as_bytes()))
+        .collect();
+    Some(symbols.len())
+}
+
+/// 分别用静态分发和动态分发计算 iterations 次总面积，并统计两个函数在二进制中的实例数量
+pub fn measure_dispatch(iterations: u32) -> DispatchReport {
+    let circles = [Circle::new(0.0, 0.0, 1.0), Circle::new(1.0, 1.0, 2.0)];
+    let rects = [Rectangle::new(1, 2), Rectangle::new(3, 4)];
+    let squares = [Square { x: 0.0, y: 0.0, side: 1.0 }, Square { x: 1.0, y: 1.0, side: 2.0 }];
+    let objects: [&dyn Shape; 6] = [&circles[0], &circles[1], &rects[0], &rects[1], &squares[0], &squares[1]];
+
+    let start = Instant::now();
+    let mut total = 0.0;
+    for _ in 0..iterations {
+        total += total_area_static(black_box(&circles));
+        total += total_area_static(black_box(&rects));
+        total += total_area_static(black_box(&squares));
+    }
+    let static_time = start.elapsed();
+    black_box(total);
+
+    let start = Instant::now();
+    let mut total = 0.0;
+    for _ in 0..iterations {
+        total += total_area_dyn(black_box(&objects));
+    }
+    let dyn_time = start.elapsed();
+    black_box(total);
+
+    DispatchReport {
+        iterations,
+        static_time,
+        dyn_time,
+        static_symbols: count_symbols("total_area_static"),
+        dyn_symbols: count_symbols("total_area_dyn"),
+    }
 }
 
 #[outer_print("泛型的性能")]
+/// 在 Rust 中泛型是零成本的抽象，意味着你在使用泛型时，完全不用担心性能上的问题。
+/// 但是任何选择都是权衡得失的，既然我们获得了性能上的巨大优势，那么又失去了什么呢？Rust 是在编译期为泛型对应的多个类型，生成各自的代码，因此损失了编译速度和增大了最终生成文件的大小。
+/// Rust 通过在编译时进行泛型代码的单态化(monomorphization)来保证效率。单态化是一个通过填充编译时使用的具体类型，将通用代码转换为特定代码的过程。
+/// 下面对比同一段求面积的代码，分别用泛型(静态分发)和特征对象(动态分发)实现，运行时测量耗时，并从可执行文件里数一数各自生成了几份函数：
+/// + 静态分发：每个具体类型一份代码，调用可以被内联，但二进制更大
+/// + 动态分发：只有一份代码，每次调用都要查虚表
 pub fn generic_performance(){
-
-}
\ No newline at end of file
+    let report = measure_dispatch(100_000);
+    let count = |n: Option<usize>| n.map_or("无法读取".to_string(), |n| n.to_string());
+    let mut table = Table::new(["分发方式", "耗时", "每个图形", "符号数量"])
+        .align(1, Align::Right)
+        .align(2, Align::Right)
+        .align(3, Align::Right);
+    // 静态分发每轮调用 3 次，动态分发每轮调用 1 次，调用次数不同，但每轮处理的都是 6 个图形，所以按图形平均
+    let per_shape = |d: Duration| format!("{:.1} ns", d.as_nanos() as f64 / (report.iterations as f64 * 6.0));
+    table.row([
+        "泛型 T: Shape".to_string(),
+        format!("{:.3?}", report.static_time),
+        per_shape(report.static_time),
+        count(report.static_symbols),
+    ]);
+    table.row([
+        "&dyn Shape".to_string(),
+        format!("{:.3?}", report.dyn_time),
+        per_shape(report.dyn_time),
+        count(report.dyn_symbols),
+    ]);
+    println!("{} 轮，每轮 6 个图形：", report.iterations);
+    println!("{:#}", table);
+    // 耗时与编译模式关系很大：debug 模式下两者差别不大，cargo run --release 时泛型版本通常会被内联和向量化。
+    // release 模式下泛型实例可能被完全内联，符号数量会变少，甚至为 0；strip 过的二进制也读不到符号。
+}
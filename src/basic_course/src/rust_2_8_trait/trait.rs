@@ -0,0 +1,273 @@
+//! # 特征 Trait
+//! 如果我们想定义一个文件系统，那么把该系统跟底层存储解耦是很重要的。文件操作主要包含四个：open 、write、read、close，这些操作可以发生在硬盘，可以发生在内存，还可以发生在网络 IO 甚至(...我实在编不下去了，大家来帮帮我)。
+//! 总之如果你要为每一种情况都单独实现一套代码，那这种实现将过于繁杂，而且也没那个必要。
+//!
+//! 要解决上述问题，需要把这些行为抽象出来，就要使用 Rust 中的特征 trait 概念。
+//! 特征定义了一组可以被共享的行为，只要实现了特征，你就能使用这组行为。
+//!
+//! 本章用方法章节里的 [Circle] 和 [Rectangle] 搭一个小小的画图框架：[Shape] 描述图形的行为，[Canvas] 是一块字符画布，任何实现了 Shape 的类型都能画上去。
+
+use std::fmt;
+use common::{inner_print, outer_print};
+use crate::rust_2_7_method::method::{Circle, Rectangle};
+
+/// 字符画布，左上角为原点，x 轴向右，y 轴向下
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Canvas {
+        Canvas {
+            width,
+            height,
+            cells: vec!['.'; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// 在 (x, y) 处画一个字符，超出画布的部分直接忽略
+    pub fn plot(&mut self, x: usize, y: usize, symbol: char) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = symbol;
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        if x < self.width && y < self.height {
+            Some(self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(&row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+/// # 定义特征
+/// 使用 trait 关键字来声明一个特征，大括号中定义了该特征的所有方法：
+/// + 只有签名、以 ; 结尾的方法，每个实现该特征的类型都必须提供自己的实现
+/// + 带有方法体的是默认实现，实现者可以直接使用，也可以重载它
+/// + 默认实现可以调用同一特征中的其它方法，哪怕这些方法没有默认实现
+pub trait Shape {
+    /// 图形的名称
+    fn name(&self) -> String;
+
+    fn area(&self) -> f64;
+
+    fn perimeter(&self) -> f64;
+
+    /// 点 (x, y) 是否落在图形内部，画图时用它来决定哪些格子需要填充
+    fn contains(&self, x: f64, y: f64) -> bool;
+
+    /// 填充用的字符
+    fn symbol(&self) -> char {
+        '*'
+    }
+
+    /// 一句话描述图形
+    fn describe(&self) -> String {
+        format!("{}: 面积 {:.2}，周长 {:.2}", self.name(), self.area(), self.perimeter())
+    }
+
+    /// 把图形画到画布上：以每个格子的中心点做采样
+    fn draw(&self, canvas: &mut Canvas) {
+        for y in 0..canvas.height() {
+            for x in 0..canvas.width() {
+                if self.contains(x as f64 + 0.5, y as f64 + 0.5) {
+                    canvas.plot(x, y, self.symbol());
+                }
+            }
+        }
+    }
+}
+
+/// 为类型实现特征：impl 特征 for 类型
+impl Shape for Circle {
+    fn name(&self) -> String {
+        String::from("圆")
+    }
+
+    // Circle 自己也有一个叫 area 的方法，这里显式调用它，更多细节见高级特征中的完全限定语法
+    fn area(&self) -> f64 {
+        Circle::area(self)
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * std::f64::consts::PI * self.radius
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        let (dx, dy) = (x - self.x, y - self.y);
+        dx * dx + dy * dy <= self.radius * self.radius
+    }
+
+    fn symbol(&self) -> char {
+        'o'
+    }
+}
+
+/// 矩形以画布原点为左上角
+impl Shape for Rectangle {
+    fn name(&self) -> String {
+        String::from("矩形")
+    }
+
+    fn area(&self) -> f64 {
+        (self.width * self.height) as f64
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height) as f64
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= 0.0 && y >= 0.0 && x < self.width as f64 && y < self.height as f64
+    }
+
+    fn symbol(&self) -> char {
+        '#'
+    }
+
+    // 重载默认实现
+    fn describe(&self) -> String {
+        format!("{}×{} 的矩形，面积 {}", self.width, self.height, self.area())
+    }
+}
+
+/// 一个不在方法章节中的新图形：以 (x, y) 为左上角的正方形
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Square {
+    pub x: f64,
+    pub y: f64,
+    pub side: f64,
+}
+
+impl Shape for Square {
+    fn name(&self) -> String {
+        String::from("正方形")
+    }
+
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+
+    fn perimeter(&self) -> f64 {
+        4.0 * self.side
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.side && y < self.y + self.side
+    }
+}
+
+#[outer_print("定义与实现特征")]
+pub fn define_trait() {
+    let circle = Circle::new(4.0, 2.0, 2.0);
+    let rect = Rectangle::new(3, 2);
+    println!("{}", circle.describe());
+    println!("{}", rect.describe());
+    // 关于特征实现与定义的位置，有一条非常重要的原则：如果你想要为类型 A 实现特征 T，那么 A 或者 T 至少有一个是在当前作用域中定义的！
+    // 例如我们可以为本地的 Circle 实现标准库的 Display 特征(见格式化输出章节)，也可以为标准库的 String 实现本地的 Shape 特征，
+    // 但是无法在当前作用域中，为 String 实现 Display 特征，因为它们俩都定义在标准库中。这个规则被称为孤儿规则。
+
+    inner_print!("默认实现");
+    let square = Square { x: 0.0, y: 0.0, side: 2.0 };
+    // Square 没有实现 describe 和 symbol，使用的是特征中的默认实现
+    assert_eq!(square.describe(), "正方形: 面积 4.00，周长 8.00");
+    assert_eq!(square.symbol(), '*');
+    // Rectangle 重载了 describe
+    assert_eq!(rect.describe(), "3×2 的矩形，面积 6");
+
+    let mut canvas = Canvas::new(8, 4);
+    circle.draw(&mut canvas);
+    rect.draw(&mut canvas);
+    println!("{}", canvas);
+}
+
+#[outer_print("使用特征作为函数参数")]
+/// 特征如果仅仅是用来实现方法，那真的有些大材小用，现在我们来讲下，真正可以让特征大放光彩的地方。
+pub fn trait_as_param() {
+    // impl Shape 意为实现了 Shape 特征的参数，任何实现了该特征的类型都可以作为参数传入
+    fn report(shape: &impl Shape) -> String {
+        format!("[{}] {:.1}", shape.name(), shape.area())
+    }
+
+    inner_print!("特征约束(trait bound)");
+    // impl Shape 只是语法糖，完整书写形式是 T: Shape，这种形式可以约束多个参数必须是同一类型
+    fn larger<T: Shape>(a: T, b: T) -> T {
+        if a.area() >= b.area() {
+            a
+        } else {
+            b
+        }
+    }
+
+    inner_print!("多重约束与 where");
+    // 除了单个约束条件，还可以指定多个约束条件，例如既能画图，又能用 {} 打印
+    fn label<T: Shape + fmt::Display>(shape: &T) -> String {
+        format!("{} -> {}", shape, shape.name())
+    }
+    // 当约束变得很多时，函数签名将变得很复杂，可以使用 where 改写
+    fn total_area<A, B>(a: &A, b: &B) -> f64
+    where
+        A: Shape,
+        B: Shape,
+    {
+        a.area() + b.area()
+    }
+
+    inner_print!("函数返回中的 impl Trait");
+    // 只需要知道返回的类型实现了 Shape，而不用关心具体是哪个类型
+    fn unit_square() -> impl Shape {
+        Square { x: 0.0, y: 0.0, side: 1.0 }
+    }
+    // 但是这种返回值方式有一个很大的限制：只能有一个具体的类型，想返回不同的类型需要特征对象，见下一节
+
+    let rect = Rectangle::new(3, 2);
+    let circle = Circle::new(0.0, 0.0, 1.0);
+    println!("{}", report(&rect));
+    println!("{}", report(&circle));
+    let big = larger(Rectangle::new(1, 1), Rectangle::new(2, 3));
+    assert_eq!(big.area(), 6.0);
+    assert_eq!(label(&rect), "矩形 3×2 -> 矩形");
+    assert_eq!(total_area(&rect, &unit_square()), 7.0);
+}
+
+#[outer_print("通过 derive 派生特征")]
+/// 在本书中，形如 #[derive(Debug)] 的代码已经出现了很多次，这种是一种特征派生语法，被 derive 标记的对象会自动实现对应的默认特征代码，继承相应的功能。
+pub fn derive_trait() {
+    // Square 派生了 Clone、Copy、PartialEq，因此可以直接复制和比较
+    let a = Square { x: 0.0, y: 0.0, side: 1.0 };
+    let b = a;
+    assert_eq!(a, b);
+    println!("{:?}", b);
+
+    inner_print!("使用特征方法前需要引入特征");
+    // 如果你要使用一个特征的方法，那么你需要将该特征引入当前的作用域中，例如 try_into 来自 std::convert::TryInto(2021 版本已在 prelude 中)
+    let a: i32 = 10;
+    let b: u16 = 100;
+    let b_: i32 = b.into();
+    let c: u8 = a.try_into().unwrap();
+    assert_eq!((b_, c), (100, 10));
+}
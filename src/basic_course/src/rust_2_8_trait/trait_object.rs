@@ -0,0 +1,152 @@
+//! # 特征对象
+//! 在上一节中有一段代码无法通过编译：返回 impl Shape 时只能有一种具体类型。
+//! 假如我们要把圆、矩形、正方形放进同一个集合里统一绘制，泛型也无能为力，因为 Vec<T> 中的 T 只能是一种类型。
+//! 这时就需要特征对象：dyn Shape 表示"某个实现了 Shape 的类型"，具体是哪个类型要到运行时才知道。
+
+use std::mem::size_of;
+use common::{inner_print, outer_print};
+use crate::rust_2_7_method::method::{Circle, Rectangle};
+use super::r#trait::{Canvas, Shape, Square};
+
+/// 由多个不同图形组成的场景
+#[derive(Default)]
+pub struct Scene {
+    shapes: Vec<Box<dyn Shape>>,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene { shapes: Vec::new() }
+    }
+
+    /// 任何实现了 Shape 的类型都可以加入场景，'static 表示图形不能借用临时数据
+    pub fn add(&mut self, shape: impl Shape + 'static) -> &mut Scene {
+        self.shapes.push(Box::new(shape));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    pub fn shapes(&self) -> &[Box<dyn Shape>] {
+        &self.shapes
+    }
+
+    pub fn total_area(&self) -> f64 {
+        self.shapes.iter().map(|s| s.area()).sum()
+    }
+
+    /// 按加入顺序依次绘制，后加入的图形覆盖先加入的
+    pub fn render(&self, width: usize, height: usize) -> Canvas {
+        let mut canvas = Canvas::new(width, height);
+        for shape in &self.shapes {
+            shape.draw(&mut canvas);
+        }
+        canvas
+    }
+}
+
+#[outer_print("特征对象")]
+/// 特征对象指向实现了 Shape 特征的类型的实例，可以通过 & 引用或者 Box<T> 智能指针的方式来创建：
+/// + &dyn Shape 借用一个图形
+/// + Box<dyn Shape> 拥有一个堆上的图形
+pub fn trait_object() {
+    let mut scene = Scene::new();
+    scene
+        .add(Rectangle::new(10, 4))
+        .add(Circle::new(14.0, 2.0, 2.0))
+        .add(Square { x: 4.0, y: 1.0, side: 2.0 });
+    for shape in scene.shapes() {
+        println!("{}", shape.describe());
+    }
+    println!("{}", scene.render(17, 4));
+
+    inner_print!("&dyn 与 Box<dyn>");
+    // 函数参数既可以是 &dyn Shape 也可以是 Box<dyn Shape>，两者都是在运行时才决定调用哪个实现
+    fn draw_one(shape: &dyn Shape) -> Canvas {
+        let mut canvas = Canvas::new(3, 3);
+        shape.draw(&mut canvas);
+        canvas
+    }
+    let boxed: Box<dyn Shape> = Box::new(Square { x: 0.0, y: 0.0, side: 2.0 });
+    println!("{}", draw_one(&Rectangle::new(1, 3)));
+    println!("{}", draw_one(boxed.as_ref()));
+}
+
+#[outer_print("特征对象的动态分发")]
+/// 泛型是在编译期完成处理的：编译器会为每一个泛型参数对应的具体类型生成一份代码，这种方式是静态分发(static dispatch)。
+/// 与静态分发相对应的是动态分发(dynamic dispatch)，直到运行时，才能确定需要调用什么方法，特征对象使用的就是动态分发：
+/// + 特征对象是一个胖指针，包含两个指针：ptr 指向实例的数据，vptr 指向虚表 vtable
+/// + 虚表中保存了该类型实现 Shape 特征的方法的地址，调用 shape.area() 时会先查表再跳转
+/// + 因为多了一次查表，编译器也无法内联，动态分发会有一点点运行时开销
+pub fn dynamic_dispatch() {
+    println!("&Circle 的大小: {} 字节", size_of::<&Circle>());
+    println!("&dyn Shape 的大小: {} 字节", size_of::<&dyn Shape>());
+    println!("Box<dyn Shape> 的大小: {} 字节", size_of::<Box<dyn Shape>>());
+    assert_eq!(size_of::<&dyn Shape>(), 2 * size_of::<usize>());
+    assert_eq!(size_of::<Box<dyn Shape>>(), 2 * size_of::<usize>());
+
+    // 同一个胖指针，数据指针不同，虚表也不同
+    let shapes: [&dyn Shape; 2] = [&Rectangle::new(2, 2), &Circle::new(0.0, 0.0, 1.0)];
+    for shape in shapes {
+        println!("{} 的面积 {:.2}", shape.name(), shape.area());
+    }
+}
+
+/// 可以复制自身的图形。clone 返回的是 Self，Self 的大小在编译期未知，因此不能出现在特征对象中，
+/// 这里换成返回 Box<dyn Shape>，这个特征就是对象安全的
+pub trait ShapeClone: Shape {
+    fn box_clone(&self) -> Box<dyn ShapeClone>;
+}
+
+impl<T: Shape + Clone + 'static> ShapeClone for T {
+    fn box_clone(&self) -> Box<dyn ShapeClone> {
+        Box::new(self.clone())
+    }
+}
+
+#[outer_print("特征对象的限制")]
+/// 不是所有特征都能拥有特征对象，只有对象安全的特征才行。当一个特征的所有方法都有如下属性时，它的对象才是安全的：
+/// + 方法的返回类型不能是 Self
+/// + 方法没有任何泛型参数
+///
+/// 对象安全对于特征对象是必须的，因为一旦有了特征对象，就不再需要知道实现该特征的具体类型是什么了。
+/// 如果特征方法返回了具体的 Self 类型，但是特征对象忘记了其真正的类型，那这个 Self 就非常尴尬，因为没人知道它是谁了。
+/// 同理，对于泛型类型参数来说，当使用特征时其会放入具体的类型参数：此具体类型变成了实现该特征的类型的一部分，而当使用特征对象时其具体类型被抹去了，故而无从得知放入泛型参数类型到底是什么。
+pub fn object_safety() {
+    // 标准库的 Clone 特征就不是对象安全的，下面的代码无法编译：
+    // let v: Vec<Box<dyn Clone>> = vec![];
+    // error[E0038]: the trait `Clone` cannot be made into an object
+
+    // 如果某个方法确实需要返回 Self，可以给它加上 where Self: Sized，这个方法就不会出现在特征对象中，特征依然是对象安全的
+    trait Scalable {
+        fn factor(&self) -> f64;
+        fn scaled(&self, k: f64) -> Self
+        where
+            Self: Sized;
+    }
+    impl Scalable for Square {
+        fn factor(&self) -> f64 {
+            self.side
+        }
+        fn scaled(&self, k: f64) -> Self {
+            Square { side: self.side * k, ..*self }
+        }
+    }
+    let square = Square { x: 0.0, y: 0.0, side: 2.0 };
+    let scaled: &dyn Scalable = &square.scaled(3.0);
+    assert_eq!(scaled.factor(), 6.0);
+
+    inner_print!("克隆特征对象");
+    let shapes: Vec<Box<dyn ShapeClone>> = vec![
+        Box::new(Square { x: 1.0, y: 1.0, side: 1.0 }),
+    ];
+    let copies: Vec<Box<dyn ShapeClone>> = shapes.iter().map(|s| s.box_clone()).collect();
+    assert_eq!(copies[0].describe(), shapes[0].describe());
+    println!("{}", copies[0].describe());
+}
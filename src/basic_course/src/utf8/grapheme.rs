@@ -0,0 +1,224 @@
+//! # 扩展字素簇
+//! 用户眼中的“一个字符”常常由多个 char 组成：e 加上组合用的重音符 U+0301 显示为 é，
+//! 国旗是两个区域指示符，👨‍👩‍👧 是三个 emoji 用零宽连接符 ZWJ 连起来的。
+//! Unicode 标准附录 UAX #29 定义了在哪里可以断开，断开的位置之间就是一个扩展字素簇(extended grapheme cluster)。
+//!
+//! 规则只看断点两边字符的属性，以及前面的少量上下文。属性表 grapheme_break.txt 随源码一起编译进来，第一次使用时解析。
+
+use std::sync::OnceLock;
+
+/// Grapheme_Cluster_Break 属性，Extended_Pictographic 也放在这里，二者没有重叠
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    Any,
+    CR,
+    LF,
+    Control,
+    Extend,
+    ZWJ,
+    RegionalIndicator,
+    Prepend,
+    SpacingMark,
+    /// 谚文的初声
+    L,
+    /// 谚文的中声
+    V,
+    /// 谚文的终声
+    T,
+    /// 谚文的初声+中声音节
+    LV,
+    /// 谚文的初声+中声+终声音节
+    LVT,
+    ExtendedPictographic,
+}
+
+/// Indic_Conjunct_Break 属性，用于婆罗米系文字的辅音连写
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conjunct {
+    None,
+    Consonant,
+    Extend,
+    Linker,
+}
+
+/// 从源码中读入的属性表，每一项是闭区间和属性，按起点排序
+struct Tables {
+    properties: Vec<(u32, u32, Property)>,
+    conjuncts: Vec<(u32, u32, Conjunct)>,
+}
+
+const DATA: &str = include_str!("grapheme_break.txt");
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut tables = Tables { properties: Vec::new(), conjuncts: Vec::new() };
+        for line in DATA.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let (range, name) = line.split_once(';').unwrap_or_else(|| panic!("属性表格式错误：{}", line));
+            let (start, end) = range.trim().split_once("..").unwrap_or((range.trim(), range.trim()));
+            let parse = |hex: &str| u32::from_str_radix(hex, 16).unwrap_or_else(|_| panic!("属性表格式错误：{}", line));
+            let (start, end) = (parse(start), parse(end));
+            let conjunct = match name.trim() {
+                "InCB_Consonant" => Some(Conjunct::Consonant),
+                "InCB_Extend" => Some(Conjunct::Extend),
+                "InCB_Linker" => Some(Conjunct::Linker),
+                _ => None,
+            };
+            if let Some(conjunct) = conjunct {
+                tables.conjuncts.push((start, end, conjunct));
+                continue;
+            }
+            let property = match name.trim() {
+                "CR" => Property::CR,
+                "LF" => Property::LF,
+                "Control" => Property::Control,
+                "Extend" => Property::Extend,
+                "ZWJ" => Property::ZWJ,
+                "Regional_Indicator" => Property::RegionalIndicator,
+                "Prepend" => Property::Prepend,
+                "SpacingMark" => Property::SpacingMark,
+                "L" => Property::L,
+                "V" => Property::V,
+                "T" => Property::T,
+                "LV" => Property::LV,
+                "LVT" => Property::LVT,
+                "Extended_Pictographic" => Property::ExtendedPictographic,
+                other => panic!("属性表中有未知的属性：{}", other),
+            };
+            tables.properties.push((start, end, property));
+        }
+        tables.properties.sort_by_key(|entry| entry.0);
+        tables.conjuncts.sort_by_key(|entry| entry.0);
+        tables
+    })
+}
+
+/// 在按起点排序的区间表中二分查找
+fn lookup<P: Copy>(table: &[(u32, u32, P)], c: char) -> Option<P> {
+    let c = c as u32;
+    let i = table.partition_point(|&(start, _, _)| start <= c);
+    let &(_, end, value) = table.get(i.checked_sub(1)?)?;
+    (c <= end).then_some(value)
+}
+
+/// c 的 Grapheme_Cluster_Break 属性
+pub fn property(c: char) -> Property {
+    lookup(&tables().properties, c).unwrap_or(Property::Any)
+}
+
+/// c 的 Indic_Conjunct_Break 属性
+pub fn conjunct(c: char) -> Conjunct {
+    lookup(&tables().conjuncts, c).unwrap_or(Conjunct::None)
+}
+
+/// 断点规则需要记住的上下文，依次喂入每个字符
+#[derive(Debug, Clone, Default)]
+pub struct Segmenter {
+    prev: Option<Property>,
+    /// 前面连续的区域指示符个数
+    regional: usize,
+    /// 前面是 Extended_Pictographic Extend*，GB11
+    pictographic: bool,
+    /// 前面是 Extended_Pictographic Extend* ZWJ，GB11
+    pictographic_zwj: bool,
+    /// 前面是 InCB=Consonant [InCB=Extend InCB=Linker]*，GB9c
+    consonant: bool,
+    /// 在上面的序列中已经出现过 InCB=Linker，GB9c
+    linked: bool,
+}
+
+impl Segmenter {
+    pub fn new() -> Segmenter {
+        Segmenter::default()
+    }
+
+    /// 喂入下一个字符，返回它前面是不是一个断点，第一个字符前面总是断点
+    pub fn feed(&mut self, c: char) -> bool {
+        use Property::*;
+        let cur = property(c);
+        let incb = conjunct(c);
+        let boundary = match self.prev {
+            // GB1
+            None => true,
+            Some(prev) => match (prev, cur) {
+                // GB3
+                (CR, LF) => false,
+                // GB4、GB5
+                (CR | LF | Control, _) | (_, CR | LF | Control) => true,
+                // GB6、GB7、GB8 谚文音节
+                (L, L | V | LV | LVT) | (LV | V, V | T) | (LVT | T, T) => false,
+                // GB9、GB9a、GB9b
+                (_, Extend | ZWJ | SpacingMark) | (Prepend, _) => false,
+                // GB9c 辅音 + 连接符 + 辅音
+                _ if incb == Conjunct::Consonant && self.consonant && self.linked => false,
+                // GB11 emoji ZWJ 序列
+                (ZWJ, ExtendedPictographic) if self.pictographic_zwj => false,
+                // GB12、GB13 区域指示符两两成对
+                (RegionalIndicator, RegionalIndicator) => self.regional.is_multiple_of(2),
+                // GB999
+                _ => true,
+            },
+        };
+
+        self.regional = if cur == RegionalIndicator { self.regional + 1 } else { 0 };
+        self.pictographic_zwj = self.pictographic && cur == ZWJ;
+        self.pictographic = cur == ExtendedPictographic || (self.pictographic && cur == Extend);
+        match incb {
+            Conjunct::Consonant => {
+                self.consonant = true;
+                self.linked = false;
+            }
+            Conjunct::Linker if self.consonant => self.linked = true,
+            Conjunct::Extend if self.consonant => {}
+            _ => {
+                self.consonant = false;
+                self.linked = false;
+            }
+        }
+        self.prev = Some(cur);
+        boundary
+    }
+}
+
+/// 按扩展字素簇遍历字符串，产生每个字素簇的起始字节位置和内容
+#[derive(Debug, Clone)]
+pub struct GraphemeIndices<'a> {
+    s: &'a str,
+    /// 下一个字素簇的起点
+    start: usize,
+    /// 还没有喂给 segmenter 的字符和它们的字节位置
+    chars: std::str::CharIndices<'a>,
+    segmenter: Segmenter,
+}
+
+impl<'a> GraphemeIndices<'a> {
+    pub fn new(s: &'a str) -> GraphemeIndices<'a> {
+        let mut chars = s.char_indices();
+        let mut segmenter = Segmenter::new();
+        // 第一个字符前面总是断点，先喂进去
+        if let Some((_, c)) = chars.next() {
+            segmenter.feed(c);
+        }
+        GraphemeIndices { s, start: 0, chars, segmenter }
+    }
+}
+
+impl<'a> Iterator for GraphemeIndices<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        if self.start >= self.s.len() {
+            return None;
+        }
+        let start = self.start;
+        let mut end = self.s.len();
+        for (i, c) in self.chars.by_ref() {
+            if self.segmenter.feed(c) {
+                end = i;
+                break;
+            }
+        }
+        self.start = end;
+        Some((start, &self.s[start..end]))
+    }
+}
@@ -0,0 +1,107 @@
+//! # UTF-8 字符串工具
+//! &str 的下标是字节，`&s[0..2]` 落在一个字符中间时会 panic；chars 能按 char 遍历，却没有按 char 取子串的方法。
+//! 而用户看到的“一个字”又可能由多个 char 组成，例如 é 可以是 e 加上组合用的重音符，👨‍👩‍👧 是五个 char。
+//!
+//! 这里用三种单位 [Unit] 计数：字节、char 和扩展字素簇（见 [grapheme]），提供：
+//! + 长度：[char_len]、[grapheme_len]
+//! + 下标换算：[byte_offset]、[index_at]、[convert]
+//! + 取子串：[substring]（按 char）、[grapheme_substring]（按字素簇）、[slice]（指定单位），越界或者不在边界上时返回 None，不会 panic
+//! + 截断：[truncate_chars]、[truncate_graphemes]，不会截断在一个字符中间
+
+pub mod grapheme;
+
+use std::iter;
+use std::ops::{Bound, RangeBounds};
+pub use grapheme::GraphemeIndices;
+
+/// 计数的单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Byte,
+    Char,
+    /// 扩展字素簇
+    Grapheme,
+}
+
+/// char 的个数
+pub fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// 扩展字素簇的个数
+pub fn grapheme_len(s: &str) -> usize {
+    graphemes(s).count()
+}
+
+/// 按扩展字素簇遍历
+pub fn graphemes(s: &str) -> impl Iterator<Item = &str> + '_ {
+    grapheme_indices(s).map(|(_, grapheme)| grapheme)
+}
+
+/// 按扩展字素簇遍历，同时给出每个字素簇的起始字节位置
+pub fn grapheme_indices(s: &str) -> GraphemeIndices<'_> {
+    GraphemeIndices::new(s)
+}
+
+/// 每个单位的起始字节位置，最后再加上 s.len()
+fn boundaries(s: &str, unit: Unit) -> Box<dyn Iterator<Item = usize> + '_> {
+    match unit {
+        Unit::Byte => Box::new(0..=s.len()),
+        Unit::Char => Box::new(s.char_indices().map(|(i, _)| i).chain(iter::once(s.len()))),
+        Unit::Grapheme => Box::new(grapheme_indices(s).map(|(i, _)| i).chain(iter::once(s.len()))),
+    }
+}
+
+/// 第 index 个单位的起始字节位置，index 等于单位的个数时返回 s.len()，再大返回 None
+pub fn byte_offset(s: &str, index: usize, unit: Unit) -> Option<usize> {
+    boundaries(s, unit).nth(index)
+}
+
+/// 字节位置 byte 是第几个单位的起点，不在单位的边界上时返回 None，byte 等于 s.len() 时返回单位的个数
+pub fn index_at(s: &str, byte: usize, unit: Unit) -> Option<usize> {
+    boundaries(s, unit).take_while(|&i| i <= byte).enumerate().find(|&(_, i)| i == byte).map(|(index, _)| index)
+}
+
+/// 把以 from 为单位的下标换算成以 to 为单位，例如第 5 个 char 是第几个字节
+pub fn convert(s: &str, index: usize, from: Unit, to: Unit) -> Option<usize> {
+    index_at(s, byte_offset(s, index, from)?, to)
+}
+
+/// 以 unit 为单位取子串，越界、起点在终点之后、或者字节下标不在 char 边界上时返回 None
+pub fn slice(s: &str, range: impl RangeBounds<usize>, unit: Unit) -> Option<&str> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => byte_offset(s, start, unit)?,
+        Bound::Excluded(&start) => byte_offset(s, start.checked_add(1)?, unit)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => byte_offset(s, end.checked_add(1)?, unit)?,
+        Bound::Excluded(&end) => byte_offset(s, end, unit)?,
+        Bound::Unbounded => s.len(),
+    };
+    s.get(start..end)
+}
+
+/// 按 char 取子串，例如 substring("hello中国人", 5..7) 是 "中国"
+pub fn substring(s: &str, range: impl RangeBounds<usize>) -> Option<&str> {
+    slice(s, range, Unit::Char)
+}
+
+/// 按扩展字素簇取子串
+pub fn grapheme_substring(s: &str, range: impl RangeBounds<usize>) -> Option<&str> {
+    slice(s, range, Unit::Grapheme)
+}
+
+/// 最多保留 max 个 char，String::truncate 的参数是字节数，落在字符中间会 panic
+pub fn truncate_chars(s: &mut String, max: usize) {
+    if let Some(end) = byte_offset(s, max, Unit::Char) {
+        s.truncate(end);
+    }
+}
+
+/// 最多保留 max 个扩展字素簇，不会把 é 截成 e
+pub fn truncate_graphemes(s: &mut String, max: usize) {
+    if let Some(end) = byte_offset(s, max, Unit::Grapheme) {
+        s.truncate(end);
+    }
+}
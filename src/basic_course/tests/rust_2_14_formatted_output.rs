@@ -1,5 +1,54 @@
-
 #[cfg(test)]
 pub mod tests {
+    use basic_course::rust_2_14_formatted_output::formatted_output;
+    use basic_course::rust_2_14_formatted_output::table::{display_width, Align, Table};
+    use basic_course::rust_2_7_method::method::{Circle, Rectangle};
+
+    #[test]
+    fn test_formatted_output() {
+        formatted_output::print_macros();
+        formatted_output::placeholder();
+        formatted_output::width_fill_align();
+        formatted_output::precision_sign_radix();
+        formatted_output::format_args_();
+        formatted_output::custom_display();
+        formatted_output::custom_debug();
+        formatted_output::table_output();
+    }
+
+    #[test]
+    fn test_display_debug() {
+        assert_eq!(format!("{}", Rectangle::new(3, 4)), "矩形 3×4");
+        assert_eq!(format!("{:-<10}|", Rectangle::new(3, 4)), "矩形 3×4----|");
+        assert_eq!(format!("{:.1}", Circle::new(0.0, -1.25, 2.0)), "圆 (0.0, -1.2) r=2.0");
+        assert_eq!(
+            format!("{:#?}", Circle::new(0.0, 0.5, 1.0)),
+            "Circle {\n    center: (\n        0.0,\n        0.5,\n    ),\n    radius: 1.0,\n}"
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let mut table = Table::new(["名称", "值"]).align(1, Align::Right);
+        table.row(["a", "1"]).row(["中文", "100"]);
+        assert_eq!(
+            table.to_string(),
+            "名称 |  值\n-----+----\na    |   1\n中文 | 100\n"
+        );
+        assert_eq!(
+            format!("{:#}", table),
+            "+------+-----+\n| 名称 |  值 |\n+------+-----+\n| a    |   1 |\n| 中文 | 100 |\n+------+-----+\n"
+        );
+
+        let mut centered = Table::new(["x"]).align(0, Align::Center);
+        centered.row(["abc"]);
+        assert_eq!(centered.to_string(), " x \n---\nabc\n");
+    }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("中国人"), 6);
+        assert_eq!(display_width("hello中国"), 9);
+    }
+}
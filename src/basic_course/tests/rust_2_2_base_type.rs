@@ -3,8 +3,16 @@ pub mod tests {
     use basic_course::rust_2_2_base_type::base_type;
     use basic_course::rust_2_2_base_type::char_bool;
     use basic_course::rust_2_2_base_type::numbers;
+    use basic_course::rust_2_2_base_type::overflow::{self, Mode, Op, OverflowError, OverflowInt, Outcome, Rhs};
     use basic_course::rust_2_2_base_type::statement_expression;
     use basic_course::rust_2_2_base_type::function;
+    use lesson_runtime::{isolate, registry};
+    use std::env;
+    use std::process::{self, Command};
+    use std::time::Duration;
+    use basic_course::rust_2_14_formatted_output::table::display_width;
+    use num::complex::Complex;
+    use num::rational::{BigRational, Ratio};
 
     #[test]
     fn test_base_type() {
@@ -21,6 +29,138 @@ pub mod tests {
         numbers::range();
         numbers::rational_complex();
         numbers::summary();
+
+        // 是否 panic 取决于构建配置
+        match isolate::observe(numbers::overflow_default) {
+            Ok(()) => assert!(!overflow::overflow_checks()),
+            Err(msg) => {
+                assert!(overflow::overflow_checks());
+                assert_eq!(msg, "attempt to add with overflow");
+            }
+        }
+        let lesson = registry::find("numbers::overflow_default").unwrap();
+        assert!(lesson.profile_dependent);
+        assert!(!registry::find("numbers::int_type").unwrap().profile_dependent);
+    }
+
+    #[test]
+    fn test_exact_numbers() {
+        assert_eq!(numbers::factorial(0).to_string(), "1");
+        assert_eq!(numbers::factorial(25).to_string(), "15511210043330985984000000");
+        assert_eq!(numbers::factorial(30).to_string(), "265252859812191058636308480000000");
+        assert_eq!(numbers::harmonic(10), BigRational::new(7381.into(), 2520.into()));
+
+        assert_ne!(0.1_f64 + 0.2, 0.3);
+        assert_eq!(Ratio::new(1_i64, 10) + Ratio::new(2, 10), Ratio::new(3, 10));
+        assert_eq!((Ratio::new(1_i64, 10) + Ratio::new(2, 10)).to_string(), "3/10");
+        assert_eq!(
+            BigRational::from_float(0.1).unwrap().to_string(),
+            "3602879701896397/36028797018963968"
+        );
+
+        assert_eq!(Complex::new(2, -1) + Complex::new(11, 22), Complex::new(13, 21));
+        assert_eq!((Complex::new(1, 2) * Complex::new(3, -4)).to_string(), "11+2i");
+
+        let (nan, same) = (f64::NAN, f64::NAN);
+        assert_ne!(nan, same);
+        assert_eq!(nan.partial_cmp(&0.0), None);
+        assert_eq!(nan.partial_cmp(&same), None);
+    }
+
+    #[test]
+    fn test_int_type_table() {
+        let table = numbers::int_type_table().to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 8);
+        let cells: Vec<&str> = lines[2].split('|').map(str::trim).collect();
+        assert_eq!(cells, ["8 位", "i8", "-128", "127", "u8", "255"]);
+        let cells: Vec<&str> = lines[6].split('|').map(str::trim).collect();
+        assert_eq!(cells[2], i128::MIN.to_string());
+        assert_eq!(cells[5], u128::MAX.to_string());
+        // 每一行在终端里的显示宽度都相同，说明中文表头也对齐了
+        let width = display_width(lines[0]);
+        assert!(lines.iter().all(|l| display_width(l) == width));
+    }
+
+    #[test]
+    fn test_overflow() {
+        overflow::overflow_explorer();
+        assert_eq!(200_u8.eval(Op::Add, Rhs::Same(100), Mode::Wrapping), Outcome::Value("44".into()));
+        assert_eq!(200_u8.eval(Op::Add, Rhs::Same(100), Mode::Checked), Outcome::Checked(None));
+        assert_eq!(200_u8.eval(Op::Add, Rhs::Same(50), Mode::Checked), Outcome::Checked(Some("250".into())));
+        assert_eq!(i8::MIN.eval(Op::Neg, Rhs::None, Mode::Overflowing), Outcome::Overflowing("-128".into(), true));
+        assert_eq!(i8::MIN.eval(Op::Abs, Rhs::None, Mode::Saturating), Outcome::Value("127".into()));
+        assert_eq!(1_u32.eval(Op::Shl, Rhs::Bits(33), Mode::Wrapping), Outcome::Value("2".into()));
+        assert_eq!(1_u32.eval(Op::Shl, Rhs::Bits(33), Mode::Saturating), Outcome::Unsupported);
+        assert_eq!(5_u16.eval(Op::Abs, Rhs::None, Mode::Wrapping), Outcome::Unsupported);
+        assert_eq!(3_i32.eval(Op::Pow, Rhs::Bits(40), Mode::Saturating), Outcome::Value(i32::MAX.to_string()));
+
+        // 命令行：overflow i16 mul 300 200
+        let table = overflow::explore("i16", "mul", &["300", "200"]).unwrap().to_string();
+        let row = table.lines().nth(2).unwrap();
+        let cells: Vec<&str> = row.split(" | ").map(str::trim).collect();
+        assert_eq!(&cells[..5], ["300 * 200", "-5536", "None", "(-5536, true)", "32767"]);
+        if overflow::overflow_checks() {
+            assert_eq!(cells[5], "panic: attempt to multiply with overflow");
+        } else {
+            assert_eq!(cells[5], "-5536");
+        }
+        assert_eq!(overflow::explore("i7", "add", &[]).unwrap_err(), OverflowError::UnknownType("i7".into()));
+        assert_eq!(overflow::explore("i8", "div", &[]).unwrap_err(), OverflowError::UnknownOp("div".into()));
+        assert_eq!(overflow::explore("i8", "add", &["1"]).unwrap_err(), OverflowError::MissingOperand);
+        assert_eq!(overflow::explore("i8", "neg", &["1", "2"]).unwrap_err(), OverflowError::TooManyOperands);
+        assert_eq!(overflow::explore("u8", "add", &["256", "1"]).unwrap_err(), OverflowError::BadOperand("256".into()));
+    }
+
+    /// 在所有类型、所有运算的边界值上，直接使用运算符的结果要与四种方法一致：
+    /// debug 构建中运算符恰好在 checked_* 返回 None 时 panic，release 构建中运算符的结果与 wrapping_* 相同
+    fn cross_check<T: OverflowInt>() {
+        let debug = overflow::overflow_checks();
+        for op in Op::ALL {
+            for (lhs, rhs) in overflow::boundary_cases::<T>(op) {
+                let expr = overflow::expression(op, lhs, rhs);
+                let plain = overflow::plain_outcome(op, lhs, rhs);
+                let wrapping = lhs.eval(op, rhs, Mode::Wrapping);
+                let checked = lhs.eval(op, rhs, Mode::Checked);
+                let overflowing = lhs.eval(op, rhs, Mode::Overflowing);
+                if plain == Outcome::Unsupported {
+                    // 无符号整数的 -x 和 x.abs() 无法编译
+                    assert!(!T::SIGNED && op.is_unary(), "{}: {}", T::NAME, expr);
+                    continue;
+                }
+                let Outcome::Value(wrapped) = wrapping else { panic!("{}: {}", T::NAME, expr) };
+                let Outcome::Overflowing(value, overflowed) = overflowing else { panic!("{}: {}", T::NAME, expr) };
+                assert_eq!(wrapped, value, "{}: {}", T::NAME, expr);
+                assert_eq!(checked == Outcome::Checked(None), overflowed, "{}: {}", T::NAME, expr);
+                match plain {
+                    Outcome::Panicked(msg) => {
+                        assert!(debug && overflowed, "{}: {} panicked: {}", T::NAME, expr, msg);
+                        assert!(msg.contains("overflow"), "{}", msg);
+                    }
+                    Outcome::Value(v) => {
+                        assert!(!(debug && overflowed), "{}: {} 应当 panic", T::NAME, expr);
+                        assert_eq!(v, wrapped, "{}: {}", T::NAME, expr);
+                    }
+                    other => panic!("{}: {} -> {:?}", T::NAME, expr, other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflow_cross_check() {
+        cross_check::<i8>();
+        cross_check::<i16>();
+        cross_check::<i32>();
+        cross_check::<i64>();
+        cross_check::<i128>();
+        cross_check::<isize>();
+        cross_check::<u8>();
+        cross_check::<u16>();
+        cross_check::<u32>();
+        cross_check::<u64>();
+        cross_check::<u128>();
+        cross_check::<usize>();
     }
 
     #[test]
@@ -44,6 +184,58 @@ pub mod tests {
         function::function_non_return();
         function::function_diverge();
     }
+
+    /// 由 isolated_child 在子进程中执行的课程或者动作
+    const CHILD_TASK: &str = "ISOLATED_CHILD_TASK";
+
+    /// 用同一个测试程序启动子进程，只执行被 ignore 的 isolated_child
+    fn run_child(task: &str, timeout: Duration) -> isolate::Report {
+        let mut command = Command::new(env::current_exe().unwrap());
+        command.args(["--exact", "tests::isolated_child", "--ignored", "--nocapture", "--test-threads=1"]).env(CHILD_TASK, task);
+        isolate::run(&mut command, timeout).unwrap()
+    }
+
+    #[test]
+    #[ignore = "只在 test_isolated 启动的子进程中执行"]
+    fn isolated_child() {
+        isolate::report_panics();
+        match env::var(CHILD_TASK).unwrap().as_str() {
+            "abort" => process::abort(),
+            "exit" => process::exit(3),
+            lesson => (registry::find(lesson).unwrap().run)(),
+        }
+    }
+
+    #[test]
+    fn test_isolated() {
+        let report = run_child("function::diverge_panic", Duration::from_secs(30));
+        assert_eq!(report.outcome, isolate::Outcome::Panicked("你已经到了穷途末路，崩溃吧！".to_string()));
+        // panic 时 Guard 依然输出了 [end]
+        assert!(report.stdout.contains("[end]====================================================[发散函数 panic]"));
+
+        let report = run_child("function::diverge_forever", Duration::from_millis(300));
+        assert_eq!(report.outcome, isolate::Outcome::TimedOut(Duration::from_millis(300)));
+        assert_eq!(report.outcome.label(), "timed-out");
+        assert!(report.elapsed >= Duration::from_millis(300));
+        // 死循环的输出被截断，不会无限增长
+        assert!(report.stdout.contains("loop_"));
+        assert!(report.stdout.len() <= isolate::CAPTURE_LIMIT + 100);
+
+        let report = run_child("function::function_diverge", Duration::from_secs(30));
+        assert_eq!(report.outcome, isolate::Outcome::Ok);
+
+        let report = run_child("abort", Duration::from_secs(30));
+        assert_eq!(report.outcome, isolate::Outcome::Aborted("信号 6 (SIGABRT)".to_string()));
+        let report = run_child("exit", Duration::from_secs(30));
+        assert_eq!(report.outcome.to_string(), "aborted: 退出码 3");
+    }
+
+    #[test]
+    fn test_panic_marker() {
+        let stderr = format!("thread 'main' panicked\n{} 第一行\\n第二行 \\\\ 结束\nnote: ...", isolate::PANIC_MARKER);
+        assert_eq!(isolate::panic_message(&stderr).as_deref(), Some("第一行\n第二行 \\ 结束"));
+        assert_eq!(isolate::panic_message("no marker"), None);
+    }
 }
 
 
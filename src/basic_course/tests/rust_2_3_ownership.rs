@@ -1,13 +1,33 @@
 #[cfg(test)]
 pub mod tests {
+    use basic_course::c_compare;
     use basic_course::rust_2_3_ownership::ownership;
     use basic_course::rust_2_3_ownership::borrowing;
+    use basic_course::rust_2_3_ownership::tracked::{self, Event, Tracked};
+
+    /// 事件去掉位置之后的文本，例如 移动 s1 -> s2
+    fn kinds(events: &[Event]) -> Vec<String> {
+        events.iter().map(|event| event.kind.to_string()).collect()
+    }
 
     #[test]
     pub fn test_ownership()->(){
         ownership::ownership_rule();
         ownership::variable_bind();
         ownership::function_args_return();
+        ownership::compare_use_after_free();
+    }
+
+    #[test]
+    fn test_compare_use_after_free() {
+        assert_eq!(
+            ownership::use_after_free_rust(),
+            "before free: 42\nRc after drop: 42, strong_count = 1\n"
+        );
+        let c = ownership::use_after_free_c().unwrap();
+        assert!(!c.success(), "{}", c.stdout);
+        assert_eq!(c.stdout, "before free: 42\n");
+        assert_eq!(c_compare::sanitizer_error(&c.stderr), Some("heap-use-after-free"), "{}", c.stderr);
     }
     #[test]
     pub fn test_borrowing(){
@@ -15,4 +35,87 @@ pub mod tests {
         borrowing::immut_ref();
         borrowing::ref_and_deref();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_tracked_ownership_lessons() {
+        let ((), events) = tracked::trace(ownership::ownership_rule);
+        assert_eq!(kinds(&events), [r#"创建 s = "hello""#, r#"丢弃 s = "hello""#]);
+
+        let ((), events) = tracked::trace(ownership::variable_bind);
+        assert_eq!(
+            kinds(&events),
+            [
+                r#"创建 s1 = "hello""#,
+                "移动 s1 -> s2",
+                r#"创建 s1 = "hello""#,
+                "克隆 s1 -> s2",
+                // 函数结束时按声明的相反顺序丢弃，被移走的第一个 s1 不会被丢弃
+                r#"丢弃 s2 = "hello""#,
+                r#"丢弃 s1 = "hello""#,
+                r#"丢弃 s2 = "hello""#,
+            ]
+        );
+
+        let ((), events) = tracked::trace(ownership::function_args_return);
+        assert_eq!(
+            kinds(&events),
+            [
+                r#"创建 some_string = "hello""#,
+                "移动 some_string -> s1",
+                r#"创建 s2 = "hello""#,
+                "移动 s2 -> a_string",
+                "移动 a_string -> s3",
+                r#"丢弃 s3 = "hello""#,
+                r#"丢弃 s1 = "hello""#,
+            ]
+        );
+        // 创建、移动带有位置，drop 没有
+        assert!(events[..5].iter().all(|event| event.location.is_some_and(|(file, _)| file == "ownership.rs")));
+        assert!(events[5..].iter().all(|event| event.location.is_none()));
+    }
+
+    #[test]
+    fn test_tracked_borrowing_lessons() {
+        let ((), events) = tracked::trace(borrowing::immut_ref);
+        assert_eq!(kinds(&events), [r#"创建 s1 = "hello""#, "借用 s1 -> s", r#"丢弃 s1 = "hello""#]);
+
+        // 可变借用的修改在丢弃时可以看到
+        let ((), events) = tracked::trace(borrowing::mut_ref);
+        assert_eq!(kinds(&events), [r#"创建 s = "hello""#, "可变借用 s -> some_string", r#"丢弃 s = "hello, world""#]);
+    }
+
+    #[test]
+    fn test_tracked() {
+        // 位置是调用者所在的行
+        let (line, events) = tracked::trace(|| {
+            let a = Tracked::new("a", vec![1, 2]);
+            let line = line!() - 1;
+            let b = a.clone_as("b");
+            assert_eq!((a.name(), b.name()), ("a", "b"));
+            line
+        });
+        assert_eq!(events[0].location, Some(("rust_2_3_ownership.rs", line)));
+        assert_eq!(events[1].location, Some(("rust_2_3_ownership.rs", line + 2)));
+        assert_eq!(events[0].to_string(), format!("rust_2_3_ownership.rs:{} 创建 a = [1, 2]", line));
+        assert_eq!(events[2].to_string(), "离开作用域 丢弃 b = [1, 2]");
+
+        // 移进函数的值在函数结束时丢弃，早于调用者中后声明的值
+        fn consume(value: Tracked<i32>) {
+            println!("consume {}", value);
+        }
+        let ((), events) = tracked::trace(|| {
+            let x = Tracked::new("x", 1);
+            let y = Tracked::new("y", 2);
+            consume(x.move_to("value"));
+            let _ = y.borrow("r");
+        });
+        assert_eq!(kinds(&events), ["创建 x = 1", "创建 y = 2", "移动 x -> value", "丢弃 value = 1", "借用 y -> r", "丢弃 y = 2"]);
+
+        // 返回的值还没有被丢弃
+        let (kept, events) = tracked::trace(|| Tracked::new("kept", 'k'));
+        assert_eq!(kinds(&events), ["创建 kept = 'k'"]);
+        drop(kept);
+        assert_eq!(kinds(&tracked::take_events()), ["丢弃 kept = 'k'"]);
+        assert!(tracked::take_events().is_empty());
+    }
+}
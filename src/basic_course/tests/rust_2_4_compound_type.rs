@@ -5,6 +5,19 @@ pub mod tests {
     use basic_course::rust_2_4_compound_type::struct_type;
     use basic_course::rust_2_4_compound_type::enum_type;
     use basic_course::rust_2_4_compound_type::array;
+    use basic_course::rust_2_4_compound_type::heap_inspect::{self, Inspect, Region};
+    use basic_course::rust_2_4_compound_type::ip_addr::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr};
+    use basic_course::rust_2_4_compound_type::stream::{self, Stream, XorStream};
+    use basic_course::rust_2_4_compound_type::poker::{self, Deck, HandRank, ParseCardError, PokerCard, PokerSuit, Rank, Rng};
+    use basic_course::rust_2_3_ownership::tracked;
+    use basic_course::rust_2_3_ownership::ownership::StringRepr;
+    use common::Layout;
+    use lesson_runtime::layout::{self, Segment, Shape, TypeLayout};
+    use lesson_runtime::{input, isolate, registry};
+    use proptest::prelude::*;
+    use std::io::{self, Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
 
     #[test]
     pub fn test_string_slice() {
@@ -20,6 +33,18 @@ pub mod tests {
         string_slice::str_escape();
         string_slice::utf8_str();
         string_slice::str_analyze();
+        string_slice::compare_nul_terminated();
+    }
+
+    #[test]
+    fn test_compare_nul_terminated() {
+        assert_eq!(
+            string_slice::nul_terminated_rust(),
+            "length = 11\nbytes = 11\nprint = \"hello\\0world\"\nCString::new = Err(5)\n"
+        );
+        let c = string_slice::nul_terminated_c().unwrap();
+        assert!(c.success(), "{}", c.stderr);
+        assert_eq!(c.stdout, "length = 5\nbytes = 12\nprint = hello\n");
     }
 
     #[test]
@@ -38,6 +63,41 @@ pub mod tests {
         struct_type::unit_like_struct();
         struct_type::struct_ownership();
         struct_type::struct_print();
+        struct_type::compare_struct_layout();
+    }
+
+    #[test]
+    fn test_struct_partial_move() {
+        let ((), events) = tracked::trace(struct_type::struct_syntax);
+        let kinds: Vec<String> = events.iter().map(|event| event.kind.to_string()).collect();
+        assert_eq!(
+            kinds,
+            [
+                r#"创建 u1.uname = "uname1""#,
+                r#"创建 u1.pass = "123abc""#,
+                r#"创建 u2.uname = "uname2""#,
+                "移动 u1.pass -> u2.pass",
+                // 字段按声明顺序丢弃，部分移动后的 u1 只丢弃剩下的 uname
+                r#"丢弃 u2.uname = "uname2""#,
+                r#"丢弃 u2.pass = "123abc""#,
+                r#"丢弃 u1.uname = "uname1""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_struct_layout() {
+        let rust = struct_type::struct_layout_rust();
+        let lines: Vec<&str> = rust.lines().collect();
+        // Rust 不保证字段顺序，这里只检查大小；repr(C) 的版本则必须与 C 完全一致
+        assert!(lines[0].starts_with("File: size 48,"), "{}", rust);
+        assert!(lines[1].starts_with("Meta: size 16,"), "{}", rust);
+        assert_eq!(lines[2], "MetaC: size 24, readonly@0, size@8, mode@16");
+
+        let c = struct_type::struct_layout_c().unwrap();
+        assert!(c.success(), "{}", c.stderr);
+        assert_eq!(c.stdout, "File: size 48, name@0, data@24\nMeta: size 24, readonly@0, size@8, mode@16\n");
+        assert_eq!(lines[2].strip_prefix("MetaC"), c.stdout.lines().nth(1).unwrap().strip_prefix("Meta"));
     }
 
     #[test]
@@ -45,12 +105,576 @@ pub mod tests {
         enum_type::enum_value();
         enum_type::uniform_type();
         enum_type::option_enum();
+        enum_type::poker_game();
     }
 
     #[test]
     pub fn test_array() {
         array::create_array();
-        array::access_array();
+        input::with_script(["2"], array::access_array);
         array::array_slice();
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn test_access_array_input() {
+        // 越界和非数字的输入都会 panic
+        let out_of_bounds = isolate::observe(|| input::with_script(["7"], array::access_array));
+        assert_eq!(out_of_bounds.unwrap_err(), "index out of bounds: the len is 5 but the index is 7");
+        let not_a_number = isolate::observe(|| input::with_script(["seven"], array::access_array));
+        assert!(not_a_number.unwrap_err().starts_with("Index entered was not a number"));
+        let no_input = isolate::observe(|| input::with_script(Vec::<String>::new(), array::access_array));
+        assert!(no_input.unwrap_err().starts_with("Failed to read line"));
+        // panic 之后脚本被恢复，外层的脚本依然可用
+        input::with_script([" 4 "], || {
+            let _ = isolate::observe(|| input::with_script(["9"], array::access_array));
+            array::access_array();
+        });
+
+        // 通过注册表按名字找到课程再执行，和命令行的 run 子命令相同
+        let lesson = registry::find("array::access_array").unwrap();
+        assert_eq!((lesson.title, lesson.path()), ("访问数组", "basic_course::rust_2_4_compound_type::array::access_array".to_string()));
+        input::with_script(["0"], lesson.run);
+        assert!(registry::find("create_array").is_ok());
+        assert!(matches!(registry::find("no_such_lesson"), Err(registry::FindError::NotFound(_))));
+    }
+
+    /// 枚举每个成员的 payload 大小
+    fn payloads(layout: &TypeLayout) -> Vec<usize> {
+        match &layout.shape {
+            Shape::Enum { variants, .. } => variants.iter().map(|variant| variant.payload_size).collect(),
+            Shape::Struct(_) => panic!("{} 不是枚举", layout.name),
+        }
+    }
+
+    fn option_size(layout: &TypeLayout) -> usize {
+        match layout.shape {
+            Shape::Enum { option_size, .. } => option_size,
+            Shape::Struct(_) => panic!("{} 不是枚举", layout.name),
+        }
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_layout_sizes_x86_64() {
+        let repr = layout::of::<StringRepr>();
+        assert_eq!((repr.size, repr.align, repr.padding()), (24, 8, 0));
+        assert_eq!(repr.size, size_of::<String>());
+
+        let meta = layout::of::<struct_type::Meta>();
+        assert_eq!((meta.size, meta.align, meta.padding()), (16, 8, 5));
+        // repr(Rust) 把大的字段排在前面
+        let offsets: Vec<usize> = ["size", "mode", "readonly"].iter().map(|name| meta.field(name).unwrap().offset).collect();
+        assert_eq!(offsets, [0, 8, 10]);
+
+        let meta_c = layout::of::<struct_type::MetaC>();
+        assert_eq!((meta_c.size, meta_c.padding()), (24, 13));
+        assert_eq!(
+            meta_c.segments(),
+            [
+                Segment::Field(meta_c.field("readonly").unwrap()),
+                Segment::Padding { offset: 1, size: 7 },
+                Segment::Field(meta_c.field("size").unwrap()),
+                Segment::Field(meta_c.field("mode").unwrap()),
+                Segment::Padding { offset: 18, size: 6 },
+            ]
+        );
+
+        let enums = enum_type::enum_layouts();
+        let sizes: Vec<(usize, usize)> = enums.iter().map(|layout| (layout.size, layout.align)).collect();
+        assert_eq!(sizes, [(1, 1), (2, 1), (8, 4), (24, 8)]);
+        assert_eq!(payloads(&enums[3]), [0, 8, 24, 12]);
+        // Message 的判别值放进了 String 容量的 niche
+        assert_eq!(enums[3].size, enums[3].max_payload());
+        let Shape::Enum { variants, .. } = &enums[0].shape else { unreachable!() };
+        assert_eq!(variants.iter().map(|variant| variant.discriminant.unwrap()).collect::<Vec<_>>(), [0, 1, 2, 3]);
+
+        let options = enum_type::option_layouts();
+        let sizes: Vec<usize> = options.iter().map(|layout| layout.size).collect();
+        assert_eq!(sizes, [2, 8, 8, 8, 4]);
+        // 引用和 Box 用空指针表示 None，再套一层 Option 就没有 niche 了
+        let nested: Vec<usize> = options.iter().map(option_size).collect();
+        assert_eq!(nested, [2, 8, 16, 16, 4]);
+        let names: Vec<&str> = options.iter().map(|layout| layout.name.as_str()).collect();
+        assert_eq!(names, ["Option<u8>", "Option<i32>", "Option<&i32>", "Option<Box<i32>>", "Option<char>"]);
+        // 成员的顺序和标准库一致，None 在前
+        let Shape::Enum { variants, .. } = &options[0].shape else { unreachable!() };
+        assert_eq!(variants.iter().map(|variant| variant.signature.as_str()).collect::<Vec<_>>(), ["None", "Some(T)"]);
+    }
+
+    #[test]
+    fn test_layout_derive() {
+        #[allow(dead_code)]
+        #[derive(Layout)]
+        #[repr(C)]
+        struct Pair<T>(u8, T);
+        let pair = layout::of::<Pair<u32>>();
+        assert_eq!(pair.name, "Pair<u32>");
+        assert_eq!((pair.size, pair.padding()), (8, 3));
+        assert_eq!((pair.field("1").unwrap().offset, pair.field("1").unwrap().ty), (4, "T"));
+
+        #[derive(Layout)]
+        struct Empty;
+        assert_eq!((layout::of::<Empty>().size, layout::of::<Empty>().padding()), (0, 0));
+
+        #[allow(dead_code)]
+        #[derive(Layout)]
+        #[repr(u16)]
+        enum Code {
+            Ok = 200,
+            NotFound = 404,
+        }
+        let code = layout::of::<Code>();
+        assert_eq!(code.size, 2);
+        let Shape::Enum { variants, .. } = &code.shape else { unreachable!() };
+        assert_eq!((variants[1].signature.as_str(), variants[1].discriminant), ("NotFound", Some(404)));
+
+        assert_eq!(layout::short_type_name("alloc::vec::Vec<core::option::Option<u8>>"), "Vec<Option<u8>>");
+        assert_eq!(layout::short_type_name("(a::B, [c::D; 2], &e::F)"), "(B, [D; 2], &F)");
+        assert_eq!([0, 1, 2, 256, 257].map(layout::discriminant_bytes), [0, 0, 1, 1, 2]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_layout_diagram() {
+        assert_eq!(
+            layout::of::<struct_type::Meta>().diagram(),
+            "struct Meta: size = 16, align = 8
+      +----------------+
+    0 | size: u64      | A 8 字节
+    8 | mode: u16      | B 2 字节
+   10 | readonly: bool | C 1 字节
+   11 | (padding)      | . 5 字节
+   16 +----------------+
+      AAAAAAAA BBC.....
+      填充 5 字节"
+        );
+        let message = enum_type::enum_layouts().pop().unwrap().diagram();
+        assert!(message.contains("| Move { x: i32, y: i32 }    | payload 8 字节"), "{}", message);
+        assert!(message.contains("没有额外占用空间"), "{}", message);
+    }
+
+    #[test]
+    fn test_heap_inspect() {
+        let literal = "hi中";
+        let inspection = literal.inspect();
+        assert_eq!((inspection.kind.as_str(), inspection.len, inspection.capacity), ("&str", 5, None));
+        assert_eq!(inspection.hex_dump(), "     0  |68|69|e4 b8 ad|\n        |h |i |中      |\n");
+        // 复制 &str 只复制指针，切片指向原来的内存
+        let copy = literal;
+        assert_eq!(copy.as_ptr(), literal.as_ptr());
+        assert_eq!(literal[2..].inspect().ptr, inspection.ptr + 2);
+
+        let owned = String::from(literal);
+        let heap = owned.inspect();
+        assert_eq!((heap.capacity, heap.bytes.as_slice()), (Some(5), literal.as_bytes()));
+        assert_ne!(heap.ptr, inspection.ptr);
+        assert!(heap.to_string().starts_with(&format!("String: ptr = {:#x}, len = 5, capacity = 5, ", heap.ptr)));
+
+        let empty = String::new().inspect();
+        assert_eq!((&empty.region, empty.hex_dump()), (&Region::Dangling, String::new()));
+
+        // 元素按字节展开，小端序
+        let numbers: Vec<u16> = vec![1, 0x1234];
+        assert_eq!(numbers.inspect().hex_dump(), "     0  |01 00|34 12|\n        |1    |4660 |\n");
+        assert_eq!(numbers.inspect().kind, "Vec<u16>");
+        // 一个元素不会被拆到两行
+        let wide: Vec<u64> = vec![7; 3];
+        let dump = wide.inspect().hex_dump();
+        assert_eq!(dump.lines().map(|line| line.split_whitespace().next().unwrap()).collect::<Vec<_>>(), ["0", "|7", "16", "|7"]);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_heap_regions() {
+        assert_eq!("literal".inspect().region, Region::ReadOnly);
+        // 测试不在主线程中执行，分配器可能用这个线程自己的 arena，它是匿名映射
+        assert!(matches!(String::from("heap").inspect().region, Region::Heap | Region::Anonymous));
+        assert!(matches!(vec![1u8; 1 << 20].inspect().region, Region::Heap | Region::Anonymous));
+        // 非主线程的栈同样是匿名映射，不能当作堆
+        let spawned = std::thread::spawn(|| {
+            let buffer = [b'x'; 16];
+            std::str::from_utf8(&buffer).unwrap().inspect().region
+        });
+        assert_eq!(spawned.join().unwrap(), Region::Anonymous);
+        static mut COUNTER: u8 = 0;
+        assert_eq!(heap_inspect::region_of(&raw const COUNTER as usize), Region::Static);
+    }
+
+    #[test]
+    fn test_push_str_growth() {
+        let steps = heap_inspect::push_str_growth("rust", 10);
+        assert_eq!(steps[0].capacity, 0);
+        assert_eq!(steps.iter().map(|step| step.len).collect::<Vec<_>>(), (0..=40).step_by(4).collect::<Vec<_>>());
+        // 容量只在放不下时增长，每次至少翻倍
+        for pair in steps.windows(2) {
+            assert!(pair[1].capacity >= pair[1].len);
+            if pair[1].capacity != pair[0].capacity {
+                assert!(pair[0].len + 4 > pair[0].capacity);
+                assert!(pair[1].capacity >= 2 * pair[0].capacity);
+            } else {
+                assert_eq!(pair[0].ptr, pair[1].ptr);
+            }
+        }
+    }
+
+    fn hand(s: &str) -> HandRank {
+        poker::evaluate(&poker::parse_hand(s).unwrap())
+    }
+
+    #[test]
+    fn test_poker_cards() {
+        assert_eq!("A♠".parse(), Ok(PokerCard::Spades(Rank::Ace)));
+        assert_eq!("10h".parse(), Ok(PokerCard::Hearts(Rank::Ten)));
+        assert_eq!("TD".parse(), Ok(PokerCard::Diamonds(Rank::Ten)));
+        assert_eq!("1♣".parse::<PokerCard>(), Err(ParseCardError::BadRank("1♣".into())));
+        assert_eq!("AX".parse::<PokerCard>(), Err(ParseCardError::BadSuit("AX".into())));
+        assert_eq!(poker::parse_hand("A♠ K♠"), Err(ParseCardError::WrongCount(2)));
+        assert_eq!(poker::parse_hand("AS AS AH AD AC"), Err(ParseCardError::Duplicate(PokerCard::Spades(Rank::Ace))));
+        assert_eq!(poker::parse_hand("2C 3C 4C 5C 3♣"), Err(ParseCardError::Duplicate(PokerCard::Clubs(Rank::Three))));
+        assert_eq!(PokerCard::new(PokerSuit::Diamonds, Rank::Queen).to_string(), "Q♦");
+        for suit in PokerSuit::ALL {
+            for rank in Rank::ALL {
+                let card = PokerCard::new(suit, rank);
+                assert_eq!((card.suit(), card.rank()), (suit, rank));
+                assert_eq!(card.to_string().parse(), Ok(card));
+            }
+        }
+        assert_eq!(Rank::ALL.map(Rank::value), [2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(Rank::from_value(11), Some(Rank::Jack));
+        assert_eq!(Rank::from_value(1), None);
+    }
+
+    #[test]
+    fn test_poker_deck() {
+        let mut deck = Deck::new();
+        let cards: Vec<PokerCard> = deck.cards().collect();
+        assert_eq!((cards.len(), cards[0], cards[51]), (52, PokerCard::Clubs(Rank::Two), PokerCard::Hearts(Rank::Ace)));
+        let mut unique = cards.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 52);
+
+        assert_eq!(deck.deal(2), Some(vec![PokerCard::Clubs(Rank::Two), PokerCard::Clubs(Rank::Three)]));
+        assert_eq!(deck.deal(51), None);
+        assert_eq!(deck.len(), 50);
+        // 轮流发牌：第一个人拿到第 1、3、5、7、9 张
+        let hands = deck.deal_hands(2).unwrap();
+        assert_eq!(hands[0].map(PokerCard::rank), [Rank::Four, Rank::Six, Rank::Eight, Rank::Ten, Rank::Queen]);
+        assert_eq!(hands[1].map(PokerCard::rank), [Rank::Five, Rank::Seven, Rank::Nine, Rank::Jack, Rank::King]);
+        assert_eq!(deck.deal_hands(9), None);
+        // 人数太多时 players * 5 会溢出，同样是牌不够
+        assert_eq!(deck.deal_hands(usize::MAX / 2), None);
+        assert_eq!(deck.len(), 40);
+        assert_eq!(deck.deal(40).map(|cards| cards.len()), Some(40));
+        assert!(deck.is_empty());
+    }
+
+    #[test]
+    fn test_poker_rng() {
+        // SplitMix64 参考实现以 0 为种子的第一个输出
+        assert_eq!(Rng::new(0).next_u64(), 0xE220_A839_7B1D_CDAF);
+        let sequence = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..8).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(42), sequence(42));
+        assert_ne!(sequence(42), sequence(43));
+
+        let mut rng = Rng::new(1);
+        let mut counts = [0; 6];
+        for _ in 0..6000 {
+            counts[rng.below(6) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&n| (850..1150).contains(&n)), "{:?}", counts);
+
+        // 种子相同洗出的牌相同，且仍然是同样的 52 张
+        let shuffled = |seed| {
+            let mut deck = Deck::new();
+            deck.shuffle(&mut Rng::new(seed));
+            deck
+        };
+        assert_eq!(shuffled(2024), shuffled(2024));
+        assert_ne!(shuffled(2024), shuffled(2025));
+        let mut cards: Vec<PokerCard> = shuffled(2024).cards().collect();
+        cards.sort();
+        // 新牌按花色、点数排列，正好是派生的 Ord 的顺序
+        assert_eq!(cards, Deck::new().cards().collect::<Vec<_>>());
+        // 课程输出依赖的牌局
+        let hands = shuffled(2024).deal_hands(4).unwrap();
+        assert_eq!(hands[1].map(|card| card.to_string()), ["6♥", "A♣", "10♦", "K♥", "K♦"]);
+        assert_eq!(poker::winners(&hands.iter().map(poker::evaluate).collect::<Vec<_>>()), [1]);
+    }
+
+    #[test]
+    fn test_poker_evaluate() {
+        use Rank::*;
+        assert_eq!(hand("2♣ 3♦ 5♥ 7♠ 9♣"), HandRank::HighCard([Nine, Seven, Five, Three, Two]));
+        assert_eq!(hand("9♣ 3♦ 9♥ 7♠ 2♣"), HandRank::OnePair(Nine, [Seven, Three, Two]));
+        assert_eq!(hand("9♣ 3♦ 9♥ 3♠ K♣"), HandRank::TwoPair(Nine, Three, King));
+        assert_eq!(hand("9♣ 9♦ 9♥ 3♠ K♣"), HandRank::ThreeOfAKind(Nine, [King, Three]));
+        assert_eq!(hand("6♣ 3♦ 5♥ 4♠ 7♣"), HandRank::Straight(Seven));
+        assert_eq!(hand("A♣ 3♦ 5♥ 4♠ 2♣"), HandRank::Straight(Five));
+        assert_eq!(hand("10♣ J♦ Q♥ K♠ A♣"), HandRank::Straight(Ace));
+        // 不能绕圈
+        assert_eq!(hand("Q♣ K♦ A♥ 2♠ 3♣"), HandRank::HighCard([Ace, King, Queen, Three, Two]));
+        assert_eq!(hand("2♥ 7♥ 9♥ J♥ Q♥"), HandRank::Flush([Queen, Jack, Nine, Seven, Two]));
+        assert_eq!(hand("3♣ 3♦ 3♥ 9♠ 9♦"), HandRank::FullHouse(Three, Nine));
+        assert_eq!(hand("3♣ 3♦ 3♥ 3♠ 9♦"), HandRank::FourOfAKind(Three, Nine));
+        assert_eq!(hand("A♦ 2♦ 3♦ 4♦ 5♦"), HandRank::StraightFlush(Five));
+        assert_eq!(hand("10♠ J♠ Q♠ K♠ A♠").to_string(), "皇家同花顺");
+
+        // 枚举的顺序就是牌型的大小
+        assert!(hand("A♣ 3♦ 5♥ 4♠ 2♣") < hand("6♣ 3♦ 5♥ 4♠ 2♣"));
+        assert!(hand("J♣ J♦ 4♥ 4♠ A♣") < hand("J♥ J♠ 9♣ 9♥ 2♦"));
+        assert!(hand("K♥ K♦ K♠ 3♣ 3♦") > hand("2♥ 7♥ 9♥ J♥ Q♥"));
+        assert_eq!(hand("9♣ 3♦ 9♥ 3♠ K♣"), hand("9♦ 3♥ 9♠ 3♣ K♦"));
+        assert_eq!(poker::winners(&[hand("9♣ 3♦ 9♥ 3♠ K♣"), hand("2♣ 3♦ 5♥ 7♠ 9♣"), hand("9♦ 3♥ 9♠ 3♣ K♦")]), [0, 2]);
+
+        assert_eq!([hand("10♠ J♠ Q♠ K♠ A♠"), hand("J♣ J♦ 4♥ 8♠ 2♣"), hand("10♣ 10♦ 4♥ 8♠ 2♣")].map(|rank| poker::payout(&rank)), [250, 1, 0]);
+    }
+
+    /// 调用者自己构造的数组可能有重复的牌，evaluate 对任何 5 张牌都有结果
+    #[test]
+    fn test_poker_evaluate_duplicates() {
+        use Rank::*;
+        let ace = PokerCard::Spades(Ace);
+        assert_eq!(poker::evaluate(&[ace; 5]), HandRank::FourOfAKind(Ace, Ace));
+        let flush = [ace, ace, PokerCard::Spades(King), PokerCard::Spades(Queen), PokerCard::Spades(Jack)];
+        assert_eq!(poker::evaluate(&flush), HandRank::Flush([Ace, Ace, King, Queen, Jack]));
+        // 点数的所有组合，花色只用两种，必然有大量重复的牌
+        for n in 0..13usize.pow(5) {
+            let hand: [PokerCard; 5] = std::array::from_fn(|i| {
+                let rank = Rank::ALL[n / 13usize.pow(i as u32) % 13];
+                PokerCard::new(if n % 2 == 0 { PokerSuit::Hearts } else { PokerSuit::ALL[i % 2] }, rank)
+            });
+            poker::evaluate(&hand);
+        }
+    }
+
+    /// 所有 C(52, 5) 种 5 张牌的组合中每种牌型的个数
+    #[test]
+    fn test_poker_hand_frequencies() {
+        let cards: Vec<PokerCard> = Deck::new().cards().collect();
+        let mut counts = [0u32; 10];
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let index = match poker::evaluate(&[cards[a], cards[b], cards[c], cards[d], cards[e]]) {
+                                HandRank::HighCard(_) => 0,
+                                HandRank::OnePair(..) => 1,
+                                HandRank::TwoPair(..) => 2,
+                                HandRank::ThreeOfAKind(..) => 3,
+                                HandRank::Straight(_) => 4,
+                                HandRank::Flush(_) => 5,
+                                HandRank::FullHouse(..) => 6,
+                                HandRank::FourOfAKind(..) => 7,
+                                HandRank::StraightFlush(Rank::Ace) => 9,
+                                HandRank::StraightFlush(_) => 8,
+                            };
+                            counts[index] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(counts, [1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 36, 4]);
+    }
+
+    #[test]
+    fn test_poker_best_hand() {
+        let cards = |s: &str| s.split_whitespace().map(|card| card.parse().unwrap()).collect::<Vec<PokerCard>>();
+        // 2 张手牌 + 5 张公共牌，最好的是 A 到 5 的顺子
+        let (rank, best) = poker::best_hand(&cards("4♥ 7♥ 5♦ 2♥ A♦ 3♣ 10♣")).unwrap();
+        assert_eq!(rank, HandRank::Straight(Rank::Five));
+        assert_eq!(poker::evaluate(&best), rank);
+        let (rank, _) = poker::best_hand(&cards("3♥ 3♦ 5♦ 2♥ A♦ 3♣ 10♣")).unwrap();
+        assert_eq!(rank, HandRank::ThreeOfAKind(Rank::Three, [Rank::Ace, Rank::Ten]));
+        assert_eq!(poker::best_hand(&cards("3♥ 3♦ 5♦ 2♥")), None);
+    }
+
+    #[test]
+    fn test_ip_addr_round_trip() {
+        let cases = [
+            ("0.0.0.0", "0.0.0.0"),
+            ("255.255.255.255", "255.255.255.255"),
+            ("::", "::"),
+            ("::1", "::1"),
+            ("1::", "1::"),
+            ("2001:DB8:0:0:1:0:0:1", "2001:db8::1:0:0:1"),
+            // 一样长时压缩靠前的一段
+            ("1:0:0:2:0:0:3:4", "1::2:0:0:3:4"),
+            // 只有一组 0 时不压缩
+            ("1:0:2:3:4:5:6:7", "1:0:2:3:4:5:6:7"),
+            ("0001:0002:0003:0004:0005:0006:0007:0008", "1:2:3:4:5:6:7:8"),
+            ("::ffff:192.168.0.1", "::ffff:192.168.0.1"),
+            ("::ffff:c0a8:1", "::ffff:192.168.0.1"),
+            ("64:ff9b::1.2.3.4", "64:ff9b::102:304"),
+            ("1:2:3:4:5:6:1.2.3.4", "1:2:3:4:5:6:102:304"),
+        ];
+        for (text, formatted) in cases {
+            let addr: IpAddr = text.parse().unwrap();
+            assert_eq!(addr.to_string(), formatted, "{}", text);
+            assert_eq!(formatted.parse::<IpAddr>(), Ok(addr));
+        }
+        assert_eq!("127.0.0.1".parse(), Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert_eq!("0:0::0:1".parse(), Ok(Ipv6Addr::LOCALHOST));
+        assert_eq!(format!("[{:>8}]", Ipv4Addr([1, 2, 3, 4])), "[ 1.2.3.4]");
+        assert!(IpAddr::V4(Ipv4Addr([127, 8, 8, 8])).is_loopback());
+        assert!(!IpAddr::V6(Ipv6Addr::UNSPECIFIED).is_loopback());
+    }
+
+    #[test]
+    fn test_ip_addr_errors() {
+        let cases = [
+            ("", AddrParseError::Empty),
+            ("1.2.3", AddrParseError::OctetCount(3)),
+            ("1.2.3.4.5", AddrParseError::OctetCount(5)),
+            ("1.2.3.256", AddrParseError::BadOctet("256".into())),
+            ("1.2.3.04", AddrParseError::BadOctet("04".into())),
+            ("1.2.3.+4", AddrParseError::BadOctet("+4".into())),
+            ("1..3.4", AddrParseError::BadOctet("".into())),
+            ("1:2:3:4:5:6:7", AddrParseError::GroupCount(7)),
+            ("1:2:3:4:5:6:7:8:9", AddrParseError::GroupCount(9)),
+            ("1:2:3:4::5:6:7:8", AddrParseError::GroupCount(8)),
+            ("12345::", AddrParseError::BadGroup("12345".into())),
+            ("g::", AddrParseError::BadGroup("g".into())),
+            (":1:2:3:4:5:6:7", AddrParseError::BadGroup("".into())),
+            ("1:2:3:4:5:6:7:", AddrParseError::BadGroup("".into())),
+            ("1:::2", AddrParseError::BadGroup("".into())),
+            ("1::2::3", AddrParseError::MultipleDoubleColon),
+            ("1.2.3.4::", AddrParseError::MisplacedIpv4("1.2.3.4".into())),
+            ("::1.2.3.4:5", AddrParseError::MisplacedIpv4("1.2.3.4".into())),
+            ("::1.2.3", AddrParseError::OctetCount(3)),
+        ];
+        for (text, error) in cases {
+            assert_eq!(text.parse::<IpAddr>(), Err(error), "{:?}", text);
+            assert!(text.parse::<std::net::IpAddr>().is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_xor_stream() {
+        let mut tls = XorStream::new(Vec::new(), b"ab");
+        tls.write_all(b"abc").unwrap();
+        tls.write_all(b"ab").unwrap();
+        // 第二次写入接着用密钥的第 2 位
+        assert_eq!(tls.get_ref(), &[0, 0, b'c' ^ b'a', b'a' ^ b'b', b'b' ^ b'a']);
+
+        // 一次只读几个字节，读出的仍然是明文
+        let wire = tls.into_inner();
+        let mut reader = XorStream::new(io::Cursor::new(wire), b"ab");
+        let mut plain = Vec::new();
+        let mut buf = [0; 2];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            plain.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(plain, b"abcab");
+    }
+
+    /// 底层每次最多写入 3 个字节，剩下的要从正确的密钥位置重新编码
+    #[test]
+    fn test_xor_stream_partial_write() {
+        struct Short(Vec<u8>);
+        impl Write for Short {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let n = buf.len().min(3);
+                self.0.extend_from_slice(&buf[..n]);
+                Ok(n)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let message = b"partial writes keep the key in step";
+        let mut short = XorStream::new(Short(Vec::new()), b"k3y!");
+        short.write_all(message).unwrap();
+        let mut whole = XorStream::new(Vec::new(), b"k3y!");
+        whole.write_all(message).unwrap();
+        assert_eq!(short.get_ref().0, *whole.get_ref());
+    }
+
+    #[test]
+    fn test_stream_echo() {
+        let (addr, server) = stream::echo_server(3).unwrap();
+        let big: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let clients: [(Option<&[u8]>, &str); 3] = [(None, "Tcp"), (Some(b"secret"), "Tls"), (Some(&[0xff; 255]), "Tls")];
+        for (key, kind) in clients {
+            let mut stream = Stream::connect(addr, key).unwrap();
+            stream.tcp().set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            assert_eq!(stream.kind(), kind);
+            assert_eq!(stream::round_trip(&mut stream, b"hello").unwrap(), b"hello");
+            assert_eq!(stream::round_trip(&mut stream, &big).unwrap(), big);
+        }
+        let served = server.join().unwrap().unwrap();
+        assert_eq!(served, [("Tcp", 10_005), ("Tls", 10_005), ("Tls", 10_005)]);
+    }
+
+    /// Tls 连接在线路上看到的不是明文
+    #[test]
+    fn test_stream_wire() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut client = Stream::connect(addr, Some(b"k")).unwrap();
+        client.write_all(b"hi").unwrap();
+        let (mut raw, _) = listener.accept().unwrap();
+        let mut wire = [0; 5];
+        raw.read_exact(&mut wire).unwrap();
+        assert_eq!(wire, [b'X', 1, b'k', b'h' ^ b'k', b'i' ^ b'k']);
+
+        // 不认识的握手
+        let mut bad = TcpStream::connect(addr).unwrap();
+        bad.write_all(b"?").unwrap();
+        let (accepted, _) = listener.accept().unwrap();
+        assert_eq!(Stream::accept(accepted).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        // 密钥的长度放在 1 个字节里
+        assert_eq!(Stream::connect(addr, Some(b"")).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(Stream::connect(addr, Some(&[0; 256])).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        // 密钥不合法时没有建立连接
+        listener.set_nonblocking(true).unwrap();
+        assert_eq!(listener.accept().unwrap_err().kind(), io::ErrorKind::WouldBlock);
+    }
+
+    /// 由地址的片段拼成的字符串，比随机字符更容易拼出合法的地址，也覆盖了各种边界情况
+    fn ip_like_string() -> impl Strategy<Value = String> {
+        const PIECES: &[&str] = &[
+            "0", "1", "00", "01", "255", "256", "ff", "FFFF", "0000", "12345", "g", ":", ":", ":", "::", ".", ".", "1.2.3.4",
+        ];
+        let random = "[0-9a-fA-F:.]{0,24}".boxed();
+        let pieces = prop::collection::vec(prop::sample::select(PIECES), 0..16).prop_map(|pieces| pieces.concat()).boxed();
+        prop_oneof![random, pieces]
+    }
+
+    proptest! {
+        /// 和标准库对照：接受和拒绝的输入相同，解析出的值和格式化的结果也相同
+        #[test]
+        fn prop_ip_addr_matches_std(text in ip_like_string()) {
+            let ours = text.parse::<IpAddr>();
+            let std = text.parse::<std::net::IpAddr>();
+            prop_assert_eq!(ours.is_ok(), std.is_ok(), "{:?} {:?}", ours, std);
+            if let (Ok(ours), Ok(std)) = (ours, std) {
+                prop_assert_eq!(ours.to_string(), std.to_string());
+            }
+        }
+
+        #[test]
+        fn prop_ipv6_round_trip(segments in prop::array::uniform8(prop_oneof![Just(0u16), Just(0xffff), any::<u16>()])) {
+            let addr = Ipv6Addr(segments);
+            let std = std::net::Ipv6Addr::from(segments);
+            prop_assert_eq!(addr.to_string(), std.to_string());
+            prop_assert_eq!(addr.to_string().parse(), Ok(addr));
+        }
+
+        #[test]
+        fn prop_ipv4_round_trip(octets in any::<[u8; 4]>()) {
+            let addr = Ipv4Addr(octets);
+            prop_assert_eq!(addr.to_string(), std::net::Ipv4Addr::from(octets).to_string());
+            prop_assert_eq!(addr.to_string().parse(), Ok(addr));
+        }
+    }
+}
@@ -36,6 +36,7 @@ pub mod tests {
         all_patterns::ignore_mode_value();
         all_patterns::match_guard();
         all_patterns::at_bind();
+        all_patterns::poker_patterns();
     }
 
     #[test]
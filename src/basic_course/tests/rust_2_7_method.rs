@@ -1,7 +1,8 @@
-
 #[cfg(test)]
 pub mod tests {
         use basic_course::rust_2_7_method::method;
+    use basic_course::rust_2_7_method::message::{self, CallError, Machine, Message, ParseMessageError, State};
+    use proptest::prelude::*;
     #[test]
     fn test_method(){
         method::define_method();
@@ -10,4 +11,88 @@ pub mod tests {
         method::multipart_impl();
         method::impl_enum();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_message_parse() {
+        let cases = [
+            ("quit", Message::Quit),
+            ("  move 3 -4", Message::Move { x: 3, y: -4 }),
+            ("move\t+1   2", Message::Move { x: 1, y: 2 }),
+            ("color 255 0 0", Message::ChangeColor(255, 0, 0)),
+            ("color 300 -1 0", Message::ChangeColor(300, -1, 0)),
+            ("write", Message::Write(String::new())),
+            ("write hello  world ", Message::Write(String::from("hello  world "))),
+            ("write  x", Message::Write(String::from(" x"))),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(text.parse::<Message>(), Ok(expected), "{:?}", text);
+        }
+
+        let errors = [
+            ("", ParseMessageError::Empty),
+            ("   ", ParseMessageError::Empty),
+            ("jump 1", ParseMessageError::UnknownCommand(String::from("jump"))),
+            ("Quit", ParseMessageError::UnknownCommand(String::from("Quit"))),
+            ("quit now", ParseMessageError::WrongArgCount { command: "quit", expected: 0 }),
+            ("move 1", ParseMessageError::WrongArgCount { command: "move", expected: 2 }),
+            ("color 1 2 3 4", ParseMessageError::WrongArgCount { command: "color", expected: 3 }),
+            ("move 1 y", ParseMessageError::BadNumber(String::from("y"))),
+            ("move 1 3000000000", ParseMessageError::BadNumber(String::from("3000000000"))),
+        ];
+        for (text, error) in errors {
+            assert_eq!(text.parse::<Message>(), Err(error), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_message_call() {
+        let mut machine = Machine::new();
+        Message::Move { x: 3, y: 4 }.call(&mut machine).unwrap();
+        Message::ChangeColor(255, 128, 0).call(&mut machine).unwrap();
+        Message::Write(String::from("hi")).call(&mut machine).unwrap();
+        assert_eq!(machine.written, vec![((3, 4), (255, 128, 0), String::from("hi"))]);
+        assert_eq!(machine.to_string(), "位置 (3, 4)，颜色 #ff8000，写了 1 段文字，运行中");
+
+        // 出错时状态不变
+        let before = machine.clone();
+        assert_eq!(Message::ChangeColor(0, 256, 0).call(&mut machine), Err(CallError::BadColor(0, 256, 0)));
+        assert_eq!(Message::Move { x: i32::MAX, y: 0 }.call(&mut machine), Err(CallError::OutOfBounds));
+        assert_eq!(machine, before);
+
+        Message::Quit.call(&mut machine).unwrap();
+        assert_eq!(machine.state, State::Stopped);
+        for m in [Message::Quit, Message::Move { x: 1, y: 1 }, Message::Write(String::new())] {
+            assert_eq!(m.call(&mut machine), Err(CallError::Stopped));
+        }
+    }
+
+    #[test]
+    fn test_interpret() {
+        let script = "move 1 2\n\n# 注释\nfly\ncolor 1 2 3\nquit\nwrite late";
+        let mut machine = Machine::new();
+        let results = message::interpret(script, &mut machine);
+        let lines: Vec<(usize, &str, bool)> = results.iter().map(|(n, line, r)| (*n, *line, r.is_ok())).collect();
+        assert_eq!(lines, [(1, "move 1 2", true), (4, "fly", false), (5, "color 1 2 3", true), (6, "quit", true), (7, "write late", false)]);
+        assert_eq!(results[4].2, Err(String::from("已经退出，不再接受消息")));
+        assert_eq!(machine.position, (1, 2));
+        assert_eq!(machine.color, (1, 2, 3));
+        assert!(machine.written.is_empty());
+    }
+
+    fn any_message() -> impl Strategy<Value = Message> {
+        prop_oneof![
+            Just(Message::Quit),
+            (any::<i32>(), any::<i32>()).prop_map(|(x, y)| Message::Move { x, y }),
+            ".*".prop_map(|text| Message::Write(text.replace(['\n', '\r'], ""))),
+            (any::<i32>(), any::<i32>(), any::<i32>()).prop_map(|(r, g, b)| Message::ChangeColor(r, g, b)),
+        ]
+    }
+
+    proptest! {
+        /// 格式化成命令再解析，得到原来的消息
+        #[test]
+        fn prop_message_round_trip(m in any_message()) {
+            prop_assert_eq!(m.to_string().parse::<Message>(), Ok(m));
+        }
+    }
+}
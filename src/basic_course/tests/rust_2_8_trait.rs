@@ -1,10 +1,10 @@
-
 #[cfg(test)]
 pub mod tests {
-    use basic_course::rust_2_8_trait::advance_trait;
-    use basic_course::rust_2_8_trait::r#trait;
-    use basic_course::rust_2_8_trait::generic;
-    use basic_course::rust_2_8_trait::trait_object;
+    use basic_course::rust_2_7_method::method::{Circle, Rectangle};
+    use basic_course::rust_2_8_trait::advance_trait::{self, AsciiRenderer, Framed, Named, Offset, Renderer, Shapes, SummaryRenderer};
+    use basic_course::rust_2_8_trait::r#trait::{self, Canvas, Shape, Square};
+    use basic_course::rust_2_8_trait::generic::{self, Buffer, Point};
+    use basic_course::rust_2_8_trait::trait_object::{self, Scene, ShapeClone};
     #[test]
     fn test_genetic(){
         generic::intro();
@@ -12,18 +12,106 @@ pub mod tests {
         generic::generic_struct();
         generic::generic_enum();
         generic::generic_method();
+        generic::generic_const();
         generic::generic_performance();
     }
 
+    #[test]
+    fn test_generic_types(){
+        let p = Point { x: 1u8, y: "y" }.mixup(Point { x: 'x', y: 2.5 });
+        assert_eq!(p, Point { x: 1u8, y: 2.5 });
+        assert_eq!(Point { x: -3.0, y: 4.0 }.distance_from_origin(), 5.0);
+        assert_eq!(generic::display_array([true, false]), "2个元素: [true, false]");
+
+        let mut buf = Buffer::<3>::default();
+        for b in b"abcd" {
+            let _ = buf.push(*b);
+        }
+        assert_eq!(buf.as_slice(), b"abc");
+    }
+
+    #[test]
+    fn test_generic_monomorphization(){
+        let report = generic::measure_dispatch(10);
+        assert_eq!(report.iterations, 10);
+        // debug 模式下泛型函数为 Circle、Rectangle、Square 各生成了一个实例，特征对象版本只有一个；
+        // release 模式下它们可能被完全内联，数量不确定
+        #[cfg(debug_assertions)]
+        {
+            assert_eq!(report.static_symbols, Some(3));
+            assert_eq!(report.dyn_symbols, Some(1));
+        }
+        assert_eq!(generic::count_symbols("no_such_function_in_this_binary"), Some(0));
+    }
+
 
 
     #[test]
     fn test_trait(){
+        r#trait::define_trait();
+        r#trait::trait_as_param();
+        r#trait::derive_trait();
+
+        let circle = Circle::new(0.0, 0.0, 1.0);
+        assert_eq!(Shape::area(&circle), std::f64::consts::PI);
+        assert_eq!(circle.perimeter(), 2.0 * std::f64::consts::PI);
+        assert!(circle.contains(0.5, 0.5));
+        assert!(!circle.contains(1.0, 1.0));
+        assert_eq!(Rectangle::new(3, 4).perimeter(), 14.0);
+
+        let mut canvas = Canvas::new(4, 3);
+        Rectangle::new(2, 2).draw(&mut canvas);
+        Square { x: 3.0, y: 2.0, side: 5.0 }.draw(&mut canvas);
+        assert_eq!(canvas.to_string(), "##..\n##..\n...*");
+        assert_eq!(canvas.get(0, 0), Some('#'));
+        assert_eq!(canvas.get(4, 0), None);
     }
     #[test]
     fn test_trait_object(){
+        trait_object::trait_object();
+        trait_object::dynamic_dispatch();
+        trait_object::object_safety();
+
+        let mut scene = Scene::new();
+        assert!(scene.is_empty());
+        scene
+            .add(Rectangle::new(10, 4))
+            .add(Circle::new(14.0, 2.0, 2.0))
+            .add(Square { x: 4.0, y: 1.0, side: 2.0 });
+        assert_eq!(scene.len(), 3);
+        assert_eq!(
+            scene.render(17, 4).to_string(),
+            "##########...oo..\n####**####..oooo.\n####**####..oooo.\n##########...oo.."
+        );
+        assert_eq!(scene.total_area(), 40.0 + 4.0 * std::f64::consts::PI + 4.0);
+        let names: Vec<String> = scene.shapes().iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["矩形", "圆", "正方形"]);
+
+        let square: Box<dyn ShapeClone> = Box::new(Square { x: 0.0, y: 0.0, side: 3.0 });
+        assert_eq!(square.box_clone().area(), 9.0);
     }
     #[test]
     fn test_advance_trait(){
+        advance_trait::associated_type();
+        advance_trait::default_generic_param();
+        advance_trait::fully_qualified_syntax();
+        advance_trait::supertrait();
+        advance_trait::newtype();
+
+        let mut scene = Scene::new();
+        scene.add(Square { x: 0.0, y: 0.0, side: 2.0 } + Offset { dx: 1.0, dy: 0.0 });
+        assert_eq!(AsciiRenderer { width: 4, height: 2 }.render(&scene).to_string(), ".**.\n.**.");
+        assert_eq!(SummaryRenderer.render(&scene), vec![("正方形".to_string(), 4.0)]);
+
+        let circle = Circle::new(0.0, 0.0, 1.0);
+        assert_eq!(circle.name(), "circle@(0, 0)");
+        assert_eq!(<Circle as Shape>::name(&circle), "圆");
+        assert_eq!(<Circle as Named>::type_name(), "Circle");
+        assert_eq!(
+            Rectangle::new(2, 3).framed(),
+            "+-----------------+\n| 矩形 2×3 | 矩形 |\n+-----------------+"
+        );
+        let shapes = Shapes(vec![Box::new(circle)]);
+        assert_eq!(format!("{}", shapes), "[圆]");
     }
-}
\ No newline at end of file
+}
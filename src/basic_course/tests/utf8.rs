@@ -0,0 +1,138 @@
+#[cfg(test)]
+pub mod tests {
+    use basic_course::utf8::{self, grapheme, Unit};
+    use proptest::prelude::*;
+    use unicode_segmentation::UnicodeSegmentation;
+
+    /// 各种断点规则会用到的字符：ASCII、CR LF、控制字符、组合符号、ZWJ、区域指示符、emoji、谚文字母和音节、
+    /// 天城文的辅音、virama 和 nukta、Prepend、SpacingMark
+    const INTERESTING: &[char] = &[
+        'a', '中', '\r', '\n', '\u{1}', '\u{301}', '\u{200D}', '\u{1F1E8}', '\u{1F1F3}', '👨', '👩', '\u{FE0F}', '\u{1F3FB}',
+        '\u{1100}', '\u{1161}', '\u{11A8}', '\u{AC00}', '\u{AC01}', '\u{915}', '\u{937}', '\u{94D}', '\u{93C}', '\u{600}',
+        '\u{903}',
+    ];
+
+    fn interesting_string() -> impl Strategy<Value = String> {
+        let c = prop_oneof![3 => prop::sample::select(INTERESTING), 1 => any::<char>()];
+        prop::collection::vec(c, 0..24).prop_map(|chars| chars.into_iter().collect())
+    }
+
+    #[test]
+    fn test_grapheme_examples() {
+        let cases: &[(&str, &[&str])] = &[
+            ("hello中国人", &["h", "e", "l", "l", "o", "中", "国", "人"]),
+            ("e\u{301}", &["e\u{301}"]),
+            ("\r\n\n", &["\r\n", "\n"]),
+            // 国旗两两成对
+            ("🇨🇳🇯🇵🇺", &["🇨🇳", "🇯🇵", "🇺"]),
+            // emoji ZWJ 序列和肤色修饰
+            ("👨‍👩‍👧!👍🏻", &["👨‍👩‍👧", "!", "👍🏻"]),
+            // 谚文字母拼成音节
+            ("\u{1100}\u{1161}\u{11A8}한", &["\u{1100}\u{1161}\u{11A8}", "한"]),
+            // 天城文 क्ष：辅音 + virama + 辅音
+            ("क्षa", &["क्ष", "a"]),
+            ("", &[]),
+        ];
+        for &(s, expected) in cases {
+            assert_eq!(utf8::graphemes(s).collect::<Vec<_>>(), expected, "{:?}", s);
+        }
+        assert_eq!(grapheme::property('\u{AC00}'), grapheme::Property::LV);
+        assert_eq!(grapheme::property('a'), grapheme::Property::Any);
+        assert_eq!(grapheme::conjunct('\u{94D}'), grapheme::Conjunct::Linker);
+    }
+
+    /// 每个码点放进几种上下文里，和 unicode-segmentation 对比，检查属性表没有遗漏
+    #[test]
+    fn test_every_char() {
+        for c in (0..=0x10FFFF).filter_map(char::from_u32) {
+            let s = format!("a{c}{c}\u{1F1E8}{c}\u{915}\u{94D}{c}\u{1100}{c}👨\u{200D}{c}");
+            let ours: Vec<&str> = utf8::graphemes(&s).collect();
+            let oracle: Vec<&str> = s.graphemes(true).collect();
+            assert_eq!(ours, oracle, "U+{:04X}", c as u32);
+        }
+    }
+
+    #[test]
+    fn test_substring() {
+        let s = "hello中国人";
+        assert_eq!((utf8::char_len(s), utf8::grapheme_len(s), s.len()), (8, 8, 14));
+        assert_eq!(utf8::substring(s, 5..7), Some("中国"));
+        assert_eq!(utf8::substring(s, 5..), Some("中国人"));
+        assert_eq!(utf8::substring(s, ..=4), Some("hello"));
+        assert_eq!(utf8::substring(s, 8..), Some(""));
+        assert_eq!(utf8::substring(s, 7..9), None);
+        // 起点在终点之后
+        let (start, end) = (6, 5);
+        assert_eq!(utf8::substring(s, start..end), None);
+        // 按字节取子串不在 char 边界上时返回 None，不会 panic
+        assert_eq!(utf8::slice(s, 5..7, Unit::Byte), None);
+        assert_eq!(utf8::slice(s, 5..8, Unit::Byte), Some("中"));
+
+        let family = "家👨‍👩‍👧e\u{301}";
+        assert_eq!(utf8::grapheme_substring(family, 1..2), Some("👨‍👩‍👧"));
+        assert_eq!(utf8::substring(family, 1..2), Some("👨"));
+        assert_eq!(utf8::convert(family, 2, Unit::Grapheme, Unit::Char), Some(6));
+        assert_eq!(utf8::convert(family, 2, Unit::Grapheme, Unit::Byte), Some(21));
+        // 第 2 个 char 在字素簇中间
+        assert_eq!(utf8::convert(family, 2, Unit::Char, Unit::Grapheme), None);
+        assert_eq!(utf8::index_at(family, family.len(), Unit::Grapheme), Some(3));
+
+        let mut truncated = String::from(family);
+        utf8::truncate_chars(&mut truncated, 7);
+        assert_eq!(truncated, "家👨‍👩‍👧e");
+        let mut truncated = String::from(family);
+        utf8::truncate_graphemes(&mut truncated, 2);
+        assert_eq!(truncated, "家👨‍👩‍👧");
+        utf8::truncate_graphemes(&mut truncated, 10);
+        assert_eq!(truncated, "家👨‍👩‍👧");
+    }
+
+    proptest! {
+        /// 按 char 的下标换算和 char_indices 一致
+        #[test]
+        fn prop_char_indices(s in any::<String>()) {
+            let indices: Vec<(usize, char)> = s.char_indices().collect();
+            prop_assert_eq!(utf8::char_len(&s), indices.len());
+            for (n, &(byte, c)) in indices.iter().enumerate() {
+                prop_assert_eq!(utf8::byte_offset(&s, n, Unit::Char), Some(byte));
+                prop_assert_eq!(utf8::index_at(&s, byte, Unit::Char), Some(n));
+                prop_assert_eq!(utf8::convert(&s, byte, Unit::Byte, Unit::Char), Some(n));
+                let expected = c.to_string();
+                prop_assert_eq!(utf8::substring(&s, n..=n), Some(expected.as_str()));
+                // char 中间的字节不是边界
+                for inner in byte + 1..byte + c.len_utf8() {
+                    prop_assert_eq!(utf8::index_at(&s, inner, Unit::Char), None);
+                }
+            }
+            prop_assert_eq!(utf8::byte_offset(&s, indices.len(), Unit::Char), Some(s.len()));
+            prop_assert_eq!(utf8::byte_offset(&s, indices.len() + 1, Unit::Char), None);
+        }
+
+        /// 按 char 取子串等于把 chars 的一段拼起来
+        #[test]
+        fn prop_substring(s in any::<String>(), a in 0usize..40, b in 0usize..40) {
+            let chars: Vec<char> = s.chars().collect();
+            let expected: Option<String> = (a <= b && b <= chars.len()).then(|| chars[a..b].iter().collect());
+            prop_assert_eq!(utf8::substring(&s, a..b).map(str::to_string), expected);
+
+            let mut truncated = s.clone();
+            utf8::truncate_chars(&mut truncated, a);
+            prop_assert_eq!(truncated, chars.iter().take(a).collect::<String>());
+        }
+
+        /// 字素簇的切分和 unicode-segmentation 一致，拼起来还是原来的字符串，边界都在 char 边界上
+        #[test]
+        fn prop_graphemes(s in interesting_string()) {
+            let ours: Vec<(usize, &str)> = utf8::grapheme_indices(&s).collect();
+            let oracle: Vec<(usize, &str)> = s.grapheme_indices(true).collect();
+            prop_assert_eq!(&ours, &oracle);
+            prop_assert_eq!(ours.iter().map(|(_, g)| *g).collect::<String>(), s.clone());
+            for (n, &(byte, g)) in ours.iter().enumerate() {
+                prop_assert!(s.is_char_boundary(byte));
+                prop_assert_eq!(utf8::grapheme_substring(&s, n..=n), Some(g));
+                let chars = utf8::convert(&s, n, Unit::Grapheme, Unit::Char).unwrap();
+                prop_assert_eq!(chars, s[..byte].chars().count());
+            }
+        }
+    }
+}
@@ -1,47 +1,326 @@
 extern crate proc_macro;
 
+mod pretty;
+
 use proc_macro::TokenStream;
 
 use convert_case::{Case, Casing};
-use quote::{format_ident, quote};
-use syn::{Expr, ItemFn, LitStr, parse_macro_input};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{Data, DeriveInput, Expr, Fields, Ident, Item, ItemFn, LitStr, Meta, NestedMeta, ReturnType, Token, parse_macro_input};
 
-/// print_start_end
-#[proc_macro_attribute]
-pub fn outer_print(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
-    let attr = parse_macro_input!(attr as LitStr);
+/// outer_print 的参数：`#[outer_print("标题")]`，标题后面可以跟 `expand`、`profile_dependent`
+struct OuterPrintArgs {
+    title: LitStr,
+    /// 执行时先打印这个函数展开后的代码
+    expand: bool,
+    /// 在 dev 和 release 下的输出或者结果预期不同，记录到注册表中由测试验证
+    profile_dependent: bool,
+}
+
+impl Parse for OuterPrintArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let title = input.parse()?;
+        let mut expand = false;
+        let mut profile_dependent = false;
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let flag: Ident = input.parse()?;
+            match flag.to_string().as_str() {
+                "expand" => expand = true,
+                "profile_dependent" => profile_dependent = true,
+                _ => return Err(syn::Error::new(flag.span(), "未知的参数，可选的参数有：expand、profile_dependent")),
+            }
+        }
+        Ok(OuterPrintArgs { title, expand, profile_dependent })
+    }
+}
+
+fn outer_print_tokens(args: &OuterPrintArgs, input: &ItemFn) -> TokenStream2 {
+    let attr = &args.title;
     let name = &input.sig.ident;
     let block = &input.block;
     let inputs = &input.sig.inputs;
     let output = &input.sig.output;
     let guard_struct_ident = format_ident!("{}Guard", name.to_string().to_case(Case::Camel));
-    let result = quote! {
+    let expansion = args.expand.then(|| {
+        let plain = OuterPrintArgs { title: args.title.clone(), expand: false, profile_dependent: args.profile_dependent };
+        let code = pretty::pretty(outer_print_tokens(&plain, input));
+        quote! { println!("{}", #code); }
+    });
+    // 没有参数、没有泛型的普通函数才能注册成可以按名字执行的课程
+    let sig = &input.sig;
+    let registrable = inputs.is_empty() && sig.generics.params.is_empty() && sig.asyncness.is_none() && sig.unsafety.is_none() && sig.constness.is_none();
+    let profile_dependent = args.profile_dependent;
+    let registration = registrable.then(|| {
+        let call = match output {
+            ReturnType::Default => quote!(#name();),
+            ReturnType::Type(..) => quote!(let _ = #name();),
+        };
+        quote! {
+            ::lesson_runtime::inventory::submit! {
+                ::lesson_runtime::registry::Lesson::new(module_path!(), stringify!(#name), #attr, || { #call }, #profile_dependent)
+            }
+        }
+    });
+    quote! {
         struct #guard_struct_ident;
         impl Drop for #guard_struct_ident {
             fn drop(&mut self) {
-                println!("[end]====================================================[{}]",#attr);
-                println!();
+                ::lesson_runtime::section::end(#attr);
             }
         }
+        #registration
         pub fn #name(#inputs) #output {
-            // println!();
-            println!("[start]==================================================[{}]",#attr);
+            ::lesson_runtime::section::start(#attr);
+            #expansion
             let _guard = #guard_struct_ident;
             #block
         }
-    };
-    result.into()
+    }
 }
 
+/// print_start_end
+///
+/// 展开后的代码调用 lesson_runtime::section 输出标题，没有参数的函数还会注册到 lesson_runtime::registry，
+/// 使用它的 crate 需要依赖 lesson_runtime
+#[proc_macro_attribute]
+pub fn outer_print(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as ItemFn);
+    let args = parse_macro_input!(attr as OuterPrintArgs);
+    outer_print_tokens(&args, &input).into()
+}
+
+fn inner_print_tokens(expr: &Expr) -> TokenStream2 {
+    quote! {
+        ::lesson_runtime::section::inner(#expr);
+    }
+}
 
 #[proc_macro]
 pub fn inner_print(input: TokenStream) -> TokenStream {
     let expr = parse_macro_input!(input as Expr);
+    inner_print_tokens(&expr).into()
+}
+
+/// 属性中的 /// 注释
+fn doc_lines(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(meta)) => match meta.lit {
+                syn::Lit::Str(s) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
 
-    let expanded = quote! {
-        println!("[inner_print]============================================[{}]",#expr);
+fn lesson_describe_tokens(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(syn::Error::new_spanned(&input.ident, "LessonDescribe 只支持结构体")),
     };
+    let name = &input.ident;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    TokenStream::from(expanded)
+    let names: Vec<String> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| field.ident.as_ref().map(Ident::to_string).unwrap_or_else(|| i.to_string()))
+        .collect();
+    let types: Vec<String> = fields.iter().map(|field| pretty::inline(field.ty.to_token_stream())).collect();
+
+    let head = format!("struct {}", pretty::inline(quote!(#name #generics)));
+    let description = match fields {
+        Fields::Named(_) => {
+            let mut out = format!("{} {{\n", head);
+            for ((field, name), ty) in fields.iter().zip(&names).zip(&types) {
+                for doc in doc_lines(&field.attrs) {
+                    out.push_str(&format!("    /// {}\n", doc));
+                }
+                out.push_str(&format!("    {}: {},\n", name, ty));
+            }
+            out.push('}');
+            out
+        }
+        Fields::Unnamed(_) => format!("{}({});", head, types.join(", ")),
+        Fields::Unit => format!("{};", head),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// 字段名和字段类型，元组结构体的字段名是下标
+            pub const FIELDS: &'static [(&'static str, &'static str)] = &[#((#names, #types)),*];
+
+            /// 结构体的定义
+            pub fn describe() -> &'static str {
+                #description
+            }
+        }
+    })
+}
+
+/// 为结构体生成 FIELDS 常量和 describe 方法，用来在课程中打印结构体的字段和类型
+#[proc_macro_derive(LessonDescribe)]
+pub fn lesson_describe(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    lesson_describe_tokens(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// 字段的名字：命名字段是字段名，元组字段是下标
+fn field_members(fields: &Fields) -> Vec<syn::Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index::from(i)),
+        })
+        .collect()
+}
+
+/// 枚举成员的写法，例如 Move { x: i32, y: i32 }、Write(String)
+fn variant_signature(variant: &syn::Variant) -> String {
+    let types: Vec<String> = variant.fields.iter().map(|field| pretty::inline(field.ty.to_token_stream())).collect();
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let parts: Vec<String> = fields.named.iter().zip(&types).map(|(field, ty)| format!("{}: {}", field.ident.as_ref().unwrap(), ty)).collect();
+            format!("{} {{ {} }}", variant.ident, parts.join(", "))
+        }
+        Fields::Unnamed(_) => format!("{}({})", variant.ident, types.join(", ")),
+        Fields::Unit => variant.ident.to_string(),
+    }
+}
+
+fn layout_tokens(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let shape = match &input.data {
+        Data::Struct(data) => {
+            let members = field_members(&data.fields);
+            let names: Vec<String> = members
+                .iter()
+                .map(|member| match member {
+                    syn::Member::Named(ident) => ident.to_string(),
+                    syn::Member::Unnamed(index) => index.index.to_string(),
+                })
+                .collect();
+            let types: Vec<String> = data.fields.iter().map(|field| pretty::inline(field.ty.to_token_stream())).collect();
+            quote! {
+                ::lesson_runtime::layout::Shape::Struct(vec![#({
+                    let (size, align) = ::lesson_runtime::layout::field_size(|v: &Self| &v.#members);
+                    ::lesson_runtime::layout::FieldLayout {
+                        name: #names.to_string(),
+                        ty: #types,
+                        offset: ::core::mem::offset_of!(Self, #members),
+                        size,
+                        align,
+                    }
+                }),*])
+            }
+        }
+        Data::Enum(data) => {
+            // 无字段枚举可以用 as 转换成整数，得到判别值
+            let fieldless = data.variants.iter().all(|variant| matches!(variant.fields, Fields::Unit));
+            let variants = data.variants.iter().map(|variant| {
+                let signature = variant_signature(variant);
+                let types = variant.fields.iter().map(|field| &field.ty);
+                let ident = &variant.ident;
+                let discriminant = if fieldless { quote!(Some(Self::#ident as i128)) } else { quote!(None) };
+                quote! {
+                    ::lesson_runtime::layout::VariantLayout {
+                        signature: #signature.to_string(),
+                        payload_size: ::core::mem::size_of::<(#(#types,)*)>(),
+                        discriminant: #discriminant,
+                    }
+                }
+            });
+            quote! {
+                ::lesson_runtime::layout::Shape::Enum {
+                    variants: vec![#(#variants),*],
+                    option_size: ::core::mem::size_of::<::core::option::Option<Self>>(),
+                }
+            }
+        }
+        Data::Union(data) => return Err(syn::Error::new_spanned(data.union_token, "Layout 只支持结构体和枚举")),
+    };
+    Ok(quote! {
+        impl #impl_generics ::lesson_runtime::layout::Layout for #name #ty_generics #where_clause {
+            fn layout() -> ::lesson_runtime::layout::TypeLayout {
+                ::lesson_runtime::layout::TypeLayout {
+                    name: ::lesson_runtime::layout::short_type_name(::core::any::type_name::<Self>()),
+                    size: ::core::mem::size_of::<Self>(),
+                    align: ::core::mem::align_of::<Self>(),
+                    shape: #shape,
+                }
+            }
+        }
+    })
+}
+
+/// 为结构体和枚举实现 lesson_runtime::layout::Layout，用来在课程中打印内存布局，使用它的 crate 需要依赖 lesson_runtime
+#[proc_macro_derive(Layout)]
+pub fn layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    layout_tokens(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn is_macro(path: &syn::Path, name: &str) -> bool {
+    path.segments.last().is_some_and(|segment| segment.ident == name)
+}
+
+fn expand_item(item: Item) -> syn::Result<String> {
+    match item {
+        Item::Fn(mut function) => {
+            let index = function
+                .attrs
+                .iter()
+                .position(|attr| is_macro(&attr.path, "outer_print"))
+                .ok_or_else(|| syn::Error::new_spanned(&function.sig.ident, "函数上没有 #[outer_print]"))?;
+            let args = function.attrs.remove(index).parse_args::<OuterPrintArgs>()?;
+            Ok(pretty::pretty(outer_print_tokens(&args, &function)))
+        }
+        Item::Struct(mut item) => {
+            // 和 cargo expand 一样，展开后的结构体上不再有 derive(LessonDescribe)
+            let mut derived = false;
+            for attr in item.attrs.iter_mut().filter(|attr| attr.path.is_ident("derive")) {
+                if let Ok(Meta::List(list)) = attr.parse_meta() {
+                    let before = list.nested.len();
+                    let kept: Vec<NestedMeta> =
+                        list.nested.into_iter().filter(|nested| !matches!(nested, NestedMeta::Meta(meta) if is_macro(meta.path(), "LessonDescribe"))).collect();
+                    derived |= kept.len() != before;
+                    attr.tokens = quote!((#(#kept),*));
+                }
+            }
+            if !derived {
+                return Err(syn::Error::new_spanned(&item.ident, "结构体上没有 #[derive(LessonDescribe)]"));
+            }
+            item.attrs.retain(|attr| !(attr.path.is_ident("derive") && attr.tokens.to_string() == "()"));
+            let input: DeriveInput = syn::parse2(item.to_token_stream())?;
+            let implementation = lesson_describe_tokens(&input)?;
+            Ok(pretty::pretty(quote!(#item #implementation)))
+        }
+        Item::Macro(item) if is_macro(&item.mac.path, "inner_print") => Ok(pretty::pretty(inner_print_tokens(&item.mac.parse_body()?))),
+        item => Err(syn::Error::new_spanned(item, "expand! 只支持 #[outer_print] 函数、#[derive(LessonDescribe)] 结构体和 inner_print!")),
+    }
+}
+
+/// 返回宏展开并格式化之后的代码，类似 cargo expand
+/// ```ignore
+/// let code: &str = expand! {
+///     #[outer_print("示例")]
+///     pub fn demo() {}
+/// };
+/// ```
+#[proc_macro]
+pub fn expand(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as Item);
+    match expand_item(item) {
+        Ok(code) => LitStr::new(&code, Span::call_site()).into_token_stream().into(),
+        Err(err) => err.into_compile_error().into(),
+    }
 }
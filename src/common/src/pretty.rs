@@ -0,0 +1,290 @@
+//! 把 TokenStream 格式化成接近 cargo expand 输出的代码
+//!
+//! TokenStream 只保存了 token 和括号的嵌套关系，没有任何空白信息，这里按照 rustfmt 的常见习惯补上空格和换行：
+//! 花括号内每条语句一行，小括号和方括号内保持在同一行。只覆盖了宏展开中常见的写法，不追求和 rustfmt 完全一致。
+
+use proc_macro2::{Delimiter, Group, Spacing, TokenStream, TokenTree};
+use syn::LitStr;
+
+const INDENT: &str = "    ";
+
+/// 后面跟 ( 或 [ 时需要空格的关键字
+const KEYWORDS: &[&str] = &[
+    "as", "break", "dyn", "else", "for", "if", "impl", "in", "let", "loop", "match", "move", "mut", "ref", "return", "unsafe", "where", "while",
+];
+
+/// 后面的 < 一定是泛型参数的关键字
+const GENERIC_KEYWORDS: &[&str] = &["impl", "fn", "struct", "enum", "trait", "type", "union"];
+
+/// 多行格式化
+pub fn pretty(tokens: TokenStream) -> String {
+    let mut printer = Printer::new(true);
+    printer.stream(tokens, true);
+    let mut out = printer.out;
+    while out.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// 单行格式化，用于类型、表达式等较短的片段
+pub fn inline(tokens: TokenStream) -> String {
+    let mut printer = Printer::new(false);
+    printer.stream(tokens, false);
+    printer.out
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Prev {
+    /// 行首或者片段开头
+    Start,
+    Ident { keyword: bool },
+    Literal,
+    Punct(char, Spacing),
+    /// 泛型参数的结束 >
+    GenericClose,
+    Open,
+    Close,
+}
+
+struct Printer {
+    out: String,
+    multiline: bool,
+    indent: usize,
+    line_start: bool,
+    prev: Prev,
+    /// 下一个 token 必须紧贴在后面，例如 . :: & # 之后
+    glue: bool,
+    /// 上一个 token 是 fn、struct 等关键字
+    item_keyword: bool,
+    /// 上一个 token 是 impl 或者紧跟在 fn、struct 等关键字后面的名字，后面的 < 是泛型参数
+    generic_name: bool,
+    generic_depth: usize,
+    /// 花括号刚刚结束，下一个 token 是否换行取决于它是什么
+    after_brace: bool,
+}
+
+impl Printer {
+    fn new(multiline: bool) -> Printer {
+        Printer {
+            out: String::new(),
+            multiline,
+            indent: 0,
+            line_start: false,
+            prev: Prev::Start,
+            glue: false,
+            item_keyword: false,
+            generic_name: false,
+            generic_depth: 0,
+            after_brace: false,
+        }
+    }
+
+    fn newline(&mut self) {
+        if !self.multiline {
+            return;
+        }
+        while self.out.ends_with(' ') {
+            self.out.pop();
+        }
+        self.out.push('\n');
+        self.line_start = true;
+        self.prev = Prev::Start;
+        self.glue = false;
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.line_start {
+            for _ in 0..self.indent {
+                self.out.push_str(INDENT);
+            }
+            self.line_start = false;
+        } else if space && !self.glue && self.prev != Prev::Start {
+            self.out.push(' ');
+        }
+        self.out.push_str(text);
+        self.glue = false;
+    }
+
+    fn stream(&mut self, tokens: TokenStream, block: bool) {
+        let mut iter = tokens.into_iter().peekable();
+        while let Some(token) = iter.next() {
+            if std::mem::take(&mut self.after_brace) && !Self::continues_after_brace(&token) {
+                self.newline();
+            }
+            let item_keyword = std::mem::take(&mut self.item_keyword);
+            let generic_name = std::mem::take(&mut self.generic_name);
+            match token {
+                TokenTree::Ident(ident) => {
+                    let text = ident.to_string();
+                    self.write(&text, true);
+                    self.item_keyword = GENERIC_KEYWORDS.contains(&text.as_str());
+                    self.generic_name = item_keyword || text == "impl";
+                    // 生命周期 'a 后面的括号不是函数调用，和关键字一样需要空格
+                    let lifetime = matches!(self.prev, Prev::Punct('\'', _));
+                    // 函数指针类型 fn(i32) -> i32 的括号紧贴 fn
+                    let keyword = (KEYWORDS.contains(&text.as_str()) || self.item_keyword) && text != "fn";
+                    self.prev = Prev::Ident { keyword: lifetime || keyword };
+                }
+                TokenTree::Literal(literal) => {
+                    self.write(&literal.to_string(), true);
+                    self.prev = Prev::Literal;
+                }
+                TokenTree::Punct(punct) => {
+                    let next_is_bracket = matches!(iter.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Bracket);
+                    if punct.as_char() == '#' && block && self.multiline && next_is_bracket {
+                        if let Some(TokenTree::Group(group)) = iter.peek() {
+                            if let Some(doc) = Self::doc_comment(group) {
+                                iter.next();
+                                self.write(&format!("///{}", doc), true);
+                                self.newline();
+                                continue;
+                            }
+                        }
+                    }
+                    self.punct(punct.as_char(), punct.spacing(), block, generic_name);
+                    if punct.as_char() == '#' {
+                        self.glue = true;
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let attribute = matches!(self.prev, Prev::Punct('#', _) | Prev::Punct('!', _)) && group.delimiter() == Delimiter::Bracket;
+                    self.group(group, block);
+                    if attribute && block {
+                        self.newline();
+                    }
+                }
+            }
+        }
+    }
+
+    fn continues_after_brace(token: &TokenTree) -> bool {
+        match token {
+            TokenTree::Punct(p) => matches!(p.as_char(), ';' | ',' | '.' | '?' | ')'),
+            TokenTree::Ident(i) => i == "else",
+            _ => false,
+        }
+    }
+
+    fn punct(&mut self, ch: char, spacing: Spacing, block: bool, generic_name: bool) {
+        let joined = matches!(self.prev, Prev::Punct(_, Spacing::Joint));
+        let operand_before = matches!(self.prev, Prev::Ident { keyword: false } | Prev::Literal | Prev::Close | Prev::GenericClose);
+        match ch {
+            ',' | ';' => {
+                self.write(&ch.to_string(), false);
+                self.prev = Prev::Punct(ch, spacing);
+                // 泛型参数中的逗号不换行，例如 impl<'a, T>
+                if block && self.generic_depth == 0 {
+                    self.newline();
+                }
+                return;
+            }
+            '.' | '?' => {
+                self.write(&ch.to_string(), false);
+                self.glue = ch == '.';
+            }
+            ':' => {
+                self.write(":", false);
+                // :: 的两个冒号都紧贴后面的 token，单独的 : 后面需要空格
+                self.glue = spacing == Spacing::Joint || joined;
+            }
+            '<' if !joined && (generic_name || matches!(self.prev, Prev::Punct(':', _)) || self.starts_type()) => {
+                self.write("<", false);
+                self.generic_depth += 1;
+                self.glue = true;
+                self.prev = Prev::Punct('<', Spacing::Alone);
+                return;
+            }
+            '>' if !joined && self.generic_depth > 0 => {
+                self.write(">", false);
+                self.generic_depth -= 1;
+                self.prev = Prev::GenericClose;
+                return;
+            }
+            '!' if spacing == Spacing::Alone && matches!(self.prev, Prev::Ident { .. }) => {
+                // 宏调用
+                self.write("!", false);
+                self.glue = true;
+            }
+            '&' | '*' | '-' | '!' if !joined && !operand_before => {
+                // 一元运算符：取引用、解引用、取负、取反
+                self.write(&ch.to_string(), true);
+                self.glue = spacing == Spacing::Alone || ch == '&';
+            }
+            '\'' => {
+                self.write("'", true);
+                self.glue = true;
+            }
+            _ => {
+                self.write(&ch.to_string(), !joined);
+                self.glue = spacing == Spacing::Joint;
+            }
+        }
+        self.prev = Prev::Punct(ch, spacing);
+    }
+
+    /// 首字母大写的标识符后面的 < 是泛型参数，例如 Vec<T>
+    fn starts_type(&self) -> bool {
+        let last = self.out.rsplit(|c: char| !(c.is_alphanumeric() || c == '_')).next().unwrap_or("");
+        matches!(self.prev, Prev::Ident { keyword: false }) && last.starts_with(|c: char| c.is_uppercase())
+    }
+
+    fn group(&mut self, group: Group, block: bool) {
+        let (open, close) = match group.delimiter() {
+            Delimiter::Parenthesis => ("(", ")"),
+            Delimiter::Bracket => ("[", "]"),
+            Delimiter::Brace => ("{", "}"),
+            Delimiter::None => {
+                self.stream(group.stream(), block);
+                return;
+            }
+        };
+        let empty = group.stream().is_empty();
+        if group.delimiter() == Delimiter::Brace {
+            self.write(if empty { "{}" } else { "{" }, true);
+            if !empty {
+                if self.multiline {
+                    self.indent += 1;
+                    self.newline();
+                    self.stream(group.stream(), true);
+                    self.after_brace = false;
+                    if !self.line_start {
+                        self.newline();
+                    }
+                    self.indent -= 1;
+                    self.write("}", false);
+                } else {
+                    self.prev = Prev::Open;
+                    self.out.push(' ');
+                    self.stream(group.stream(), false);
+                    self.write(" }", false);
+                }
+            }
+            self.prev = Prev::Close;
+            self.after_brace = block && self.multiline;
+            return;
+        }
+        // 函数调用、索引、宏调用的括号紧贴前面的 token
+        let space = !matches!(self.prev, Prev::Ident { keyword: false } | Prev::Close | Prev::GenericClose);
+        self.write(open, space);
+        self.prev = Prev::Open;
+        self.glue = true;
+        let depth = std::mem::take(&mut self.generic_depth);
+        self.stream(group.stream(), false);
+        self.generic_depth = depth;
+        self.after_brace = false;
+        self.write(close, false);
+        self.prev = Prev::Close;
+    }
+
+    /// #[doc = "..."] 还原成 /// 注释
+    fn doc_comment(group: &Group) -> Option<String> {
+        let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+        match tokens.as_slice() {
+            [TokenTree::Ident(name), TokenTree::Punct(eq), TokenTree::Literal(lit)] if name == "doc" && eq.as_char() == '=' => {
+                syn::parse2::<LitStr>(TokenTree::Literal(lit.clone()).into()).ok().map(|s| s.value())
+            }
+            _ => None,
+        }
+    }
+}
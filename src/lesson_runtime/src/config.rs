@@ -0,0 +1,142 @@
+//! 全局的课程配置
+//!
+//! 配置在第一次使用时初始化，之后在整个进程中只读，所以用 OnceLock 保存，读取时不需要加锁：
+//! + 在第一次输出之前调用 [init]，使用指定的配置
+//! + 否则第一次调用 [get] 时从环境变量中读取，LESSON_SINK=stdout|stderr|null、
+//!   LESSON_COLOR=auto|always|never、LESSON_VERBOSITY=quiet|normal|verbose，没有设置的项使用默认值
+
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// 课程标题输出到哪里，课程自己的 println! 不受影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sink {
+    #[default]
+    Stdout,
+    Stderr,
+    /// 不输出
+    Null,
+}
+
+/// 是否使用 ANSI 颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// 输出到终端并且没有设置 NO_COLOR 时使用颜色
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// 输出的详细程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// 不输出课程标题
+    Quiet,
+    #[default]
+    Normal,
+    /// 标题后面附带小节路径和用时
+    Verbose,
+}
+
+/// 无法识别的配置项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub key: &'static str,
+    pub value: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "无法识别的配置 {}={}", self.key, self.value)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+macro_rules! impl_from_str {
+    ($t:ty, $key:expr, $($name:expr => $value:expr),+) => {
+        impl FromStr for $t {
+            type Err = ConfigError;
+
+            fn from_str(s: &str) -> Result<Self, ConfigError> {
+                match s.trim().to_ascii_lowercase().as_str() {
+                    $($name => Ok($value),)+
+                    _ => Err(ConfigError { key: $key, value: s.to_string() }),
+                }
+            }
+        }
+    };
+}
+
+impl_from_str!(Sink, "LESSON_SINK", "stdout" => Sink::Stdout, "stderr" => Sink::Stderr, "null" => Sink::Null);
+impl_from_str!(ColorChoice, "LESSON_COLOR", "auto" => ColorChoice::Auto, "always" => ColorChoice::Always, "never" => ColorChoice::Never);
+impl_from_str!(Verbosity, "LESSON_VERBOSITY", "quiet" => Verbosity::Quiet, "normal" => Verbosity::Normal, "verbose" => Verbosity::Verbose);
+
+/// 课程配置
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Config {
+    pub sink: Sink,
+    pub color: ColorChoice,
+    pub verbosity: Verbosity,
+}
+
+impl Config {
+    /// 从键值对中读取配置，lookup 返回 None 的项使用默认值
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Config, ConfigError> {
+        fn parse<T: FromStr<Err = ConfigError> + Default>(value: Option<String>) -> Result<T, ConfigError> {
+            value.map_or(Ok(T::default()), |v| v.parse())
+        }
+        Ok(Config {
+            sink: parse(lookup("LESSON_SINK"))?,
+            color: parse(lookup("LESSON_COLOR"))?,
+            verbosity: parse(lookup("LESSON_VERBOSITY"))?,
+        })
+    }
+
+    /// 从环境变量中读取配置
+    pub fn from_env() -> Result<Config, ConfigError> {
+        Config::from_lookup(|key| env::var(key).ok())
+    }
+
+    /// 实际是否使用颜色
+    pub fn use_color(&self) -> bool {
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                env::var_os("NO_COLOR").is_none()
+                    && match self.sink {
+                        Sink::Stdout => io::stdout().is_terminal(),
+                        Sink::Stderr => io::stderr().is_terminal(),
+                        Sink::Null => false,
+                    }
+            }
+        }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// 设置全局配置，配置已经初始化过时返回 Err，里面是没有生效的配置
+pub fn init(config: Config) -> Result<(), Config> {
+    CONFIG.set(config)
+}
+
+/// 全局配置，还没有初始化时从环境变量中读取，环境变量有误时使用默认配置
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        Config::from_env().unwrap_or_else(|e| {
+            eprintln!("{}，使用默认配置", e);
+            Config::default()
+        })
+    })
+}
+
+/// 全局配置是否已经初始化
+pub fn is_initialized() -> bool {
+    CONFIG.get().is_some()
+}
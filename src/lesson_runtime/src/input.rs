@@ -0,0 +1,73 @@
+//! 课程的输入
+//!
+//! 需要用户输入的课程调用 [read_line]，而不是直接读取标准输入，输入依次来自：
+//! + 当前线程中 [with_script] 提供的脚本，测试用它为每次执行准备输入
+//! + 全局脚本 [set_script]，对应命令行的 --input 参数
+//! + 标准输入
+//!
+//! 脚本中的输入会被回显，输出和在终端中手动输入时一样。
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+use std::sync::Mutex;
+
+static SCRIPT: Mutex<Option<VecDeque<String>>> = Mutex::new(None);
+
+thread_local! {
+    static LOCAL_SCRIPT: RefCell<Option<VecDeque<String>>> = const { RefCell::new(None) };
+}
+
+/// 设置全局脚本，之后所有线程的 read_line 都从脚本中读取，传入空的迭代器也不会再读取标准输入
+pub fn set_script<S: Into<String>>(lines: impl IntoIterator<Item = S>) {
+    *SCRIPT.lock().unwrap_or_else(|e| e.into_inner()) = Some(lines.into_iter().map(Into::into).collect());
+}
+
+/// 清除全局脚本，恢复从标准输入读取
+pub fn clear_script() {
+    *SCRIPT.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// 离开 with_script 时恢复原来的脚本，f panic 时也一样
+struct Restore(Option<VecDeque<String>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        LOCAL_SCRIPT.with(|script| *script.borrow_mut() = previous);
+    }
+}
+
+/// 在当前线程中用 lines 作为输入执行 f，脚本没用完的部分会被丢弃
+pub fn with_script<S: Into<String>, R>(lines: impl IntoIterator<Item = S>, f: impl FnOnce() -> R) -> R {
+    let lines: VecDeque<String> = lines.into_iter().map(Into::into).collect();
+    let _restore = Restore(LOCAL_SCRIPT.with(|script| script.borrow_mut().replace(lines)));
+    f()
+}
+
+fn exhausted() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "输入脚本已经用完")
+}
+
+/// 读取一行输入，不包含行尾的换行符
+pub fn read_line() -> io::Result<String> {
+    let scripted = LOCAL_SCRIPT.with(|script| script.borrow_mut().as_mut().map(VecDeque::pop_front));
+    let scripted = match scripted {
+        Some(line) => Some(line),
+        None => SCRIPT.lock().unwrap_or_else(|e| e.into_inner()).as_mut().map(VecDeque::pop_front),
+    };
+    match scripted {
+        Some(Some(line)) => {
+            println!("> {}", line);
+            Ok(line)
+        }
+        Some(None) => Err(exhausted()),
+        None => {
+            let mut line = String::new();
+            if io::stdin().lock().read_line(&mut line)? == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "标准输入已经结束"));
+            }
+            Ok(line.trim_end_matches(['\r', '\n']).to_string())
+        }
+    }
+}
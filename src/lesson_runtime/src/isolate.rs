@@ -0,0 +1,229 @@
+//! 在子进程中执行课程
+//!
+//! 有的课程会 panic，有的永远不会返回，在同一个进程中执行会中断或者卡住后面的课程。
+//! [run] 把课程交给子进程执行，捕获子进程的输出，超时后杀掉子进程，最后把结果归为四类，见 [Outcome]。
+//!
+//! 子进程中需要先调用 [report_panics]：panic 时除了标准库默认的输出，还会在标准错误中额外输出一行
+//! [PANIC_MARKER] 开头的 panic 信息，父进程据此区分 panic 和其它原因导致的失败。
+//!
+//! 只需要捕获 panic 时不必启动子进程，[observe] 在当前进程中执行并返回 panic 的信息。
+
+use std::any::Any;
+use std::env;
+use std::fmt;
+use std::io::{self, Read};
+use std::panic::{self, UnwindSafe};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 子进程中的环境变量，[run] 会自动设置
+pub const CHILD_ENV: &str = "LESSON_ISOLATED";
+
+/// 标准错误中 panic 信息所在行的前缀
+pub const PANIC_MARKER: &str = "[lesson-panic]";
+
+/// 每个输出流最多保留的字节数，死循环中的输出可能非常多
+pub const CAPTURE_LIMIT: usize = 64 * 1024;
+
+/// 子进程的执行结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// 正常退出
+    Ok,
+    /// panic，附带 panic 信息
+    Panicked(String),
+    /// 超过了时间限制，子进程被杀掉
+    TimedOut(Duration),
+    /// 被信号终止或者以非 0 的退出码退出，但没有 panic，例如 abort、栈溢出、process::exit
+    Aborted(String),
+}
+
+impl Outcome {
+    /// 简短的名字：ok、panicked、timed-out、aborted
+    pub fn label(&self) -> &'static str {
+        match self {
+            Outcome::Ok => "ok",
+            Outcome::Panicked(_) => "panicked",
+            Outcome::TimedOut(_) => "timed-out",
+            Outcome::Aborted(_) => "aborted",
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Ok => write!(f, "ok"),
+            Outcome::Panicked(message) => write!(f, "panicked: {}", message),
+            Outcome::TimedOut(limit) => write!(f, "timed-out: 超过了 {}ms", limit.as_millis()),
+            Outcome::Aborted(reason) => write!(f, "aborted: {}", reason),
+        }
+    }
+}
+
+/// 一次子进程执行的报告
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub outcome: Outcome,
+    pub stdout: String,
+    pub stderr: String,
+    pub elapsed: Duration,
+}
+
+/// 是否运行在 [run] 启动的子进程中
+pub fn is_child() -> bool {
+    env::var_os(CHILD_ENV).is_some()
+}
+
+/// panic 信息中的换行会被转义，保证标记只占一行
+fn escape(message: &str) -> String {
+    message.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// 安装输出 panic 标记的钩子，多次调用只安装一次
+pub fn report_panics() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            previous(info);
+            let message = info.payload_as_str().unwrap_or("<非文本的 panic 信息>");
+            eprintln!("{} {}", PANIC_MARKER, escape(message));
+        }));
+    });
+}
+
+/// 标准错误中最后一次 panic 的信息
+pub fn panic_message(stderr: &str) -> Option<String> {
+    stderr.lines().rev().find_map(|line| line.strip_prefix(PANIC_MARKER)).map(|rest| unescape(rest.trim_start()))
+}
+
+static PANIC_HOOK: Mutex<()> = Mutex::new(());
+
+/// 在当前进程中执行 f 并捕获 panic，执行期间临时关闭 panic 钩子的输出，返回 panic 的信息。
+/// 只需要知道是否 panic、不担心死循环和 abort 时，用它代替 [run]
+pub fn observe<R>(f: impl FnOnce() -> R + UnwindSafe) -> Result<R, String> {
+    let _guard = PANIC_HOOK.lock().unwrap_or_else(|e| e.into_inner());
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(f);
+    panic::set_hook(hook);
+    result.map_err(payload_message)
+}
+
+/// 从 panic 的负载中取出文本信息
+pub fn payload_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(s) => s.to_string(),
+            Err(_) => String::from("<非文本的 panic 信息>"),
+        },
+    }
+}
+
+/// 在后台线程中读完整个输出流，只保留前 CAPTURE_LIMIT 个字节
+fn capture(mut stream: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut kept = Vec::new();
+        let mut dropped = 0usize;
+        let mut buf = [0u8; 8192];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let room = CAPTURE_LIMIT.saturating_sub(kept.len()).min(n);
+                    kept.extend_from_slice(&buf[..room]);
+                    dropped += n - room;
+                }
+            }
+        }
+        let mut text = String::from_utf8_lossy(&kept).into_owned();
+        if dropped > 0 {
+            text.push_str(&format!("\n...(省略了 {} 字节)", dropped));
+        }
+        text
+    })
+}
+
+#[cfg(unix)]
+fn describe_exit(status: ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+    match (status.code(), status.signal()) {
+        (_, Some(signal)) => {
+            let name = match signal {
+                4 => " (SIGILL)",
+                6 => " (SIGABRT)",
+                9 => " (SIGKILL)",
+                11 => " (SIGSEGV)",
+                _ => "",
+            };
+            format!("信号 {}{}", signal, name)
+        }
+        (Some(code), None) => format!("退出码 {}", code),
+        (None, None) => status.to_string(),
+    }
+}
+
+#[cfg(not(unix))]
+fn describe_exit(status: ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("退出码 {}", code),
+        None => status.to_string(),
+    }
+}
+
+/// 根据退出状态和标准错误判断结果，status 为 None 表示超时
+pub fn classify(status: Option<ExitStatus>, stderr: &str, timeout: Duration) -> Outcome {
+    match status {
+        None => Outcome::TimedOut(timeout),
+        Some(status) if status.success() => Outcome::Ok,
+        Some(status) => match panic_message(stderr) {
+            Some(message) => Outcome::Panicked(message),
+            None => Outcome::Aborted(describe_exit(status)),
+        },
+    }
+}
+
+/// 执行子进程，最多等待 timeout，子进程的标准输入是空的
+pub fn run(command: &mut Command, timeout: Duration) -> io::Result<Report> {
+    let start = Instant::now();
+    let mut child = command.env(CHILD_ENV, "1").stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = capture(child.stdout.take().expect("stdout 已经设置为 piped"));
+    let stderr = capture(child.stderr.take().expect("stderr 已经设置为 piped"));
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if start.elapsed() >= timeout {
+            // 子进程可能恰好在这时退出，kill 失败也没关系
+            let _ = child.kill();
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+    let elapsed = start.elapsed();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    Ok(Report { outcome: classify(status, &stderr, timeout), stdout, stderr, elapsed })
+}
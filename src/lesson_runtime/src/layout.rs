@@ -0,0 +1,247 @@
+//! 类型的内存布局
+//!
+//! `#[derive(Layout)]`（在 common 中）为结构体和枚举实现 [Layout]，这里负责收集到的数据和画图：
+//! + 结构体：大小、对齐、每个字段的偏移和大小，字段之间以及末尾的填充
+//! + 枚举：每个成员的 payload 大小，无字段枚举的判别值，除最大成员外额外占用的字节，以及 Option<Self> 是否变大（即还有没有空闲的 niche）
+//!
+//! 稳定版的 offset_of! 不支持枚举成员，所以枚举只能给出这些可以精确测量的数字，不画出判别值具体放在哪个字节。
+//! repr(packed) 的结构体不能取字段的引用，不支持。
+
+use std::fmt;
+use std::mem::{align_of, size_of};
+
+/// 一个字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// 字段名，元组结构体是下标
+    pub name: String,
+    /// 源码中写的类型
+    pub ty: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    pub align: usize,
+}
+
+/// 枚举的一个成员
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantLayout {
+    /// 成员的写法，例如 Move { x: i32, y: i32 }
+    pub signature: String,
+    /// 成员的字段单独组成元组时的大小
+    pub payload_size: usize,
+    /// 无字段枚举的判别值
+    pub discriminant: Option<i128>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shape {
+    /// 字段按声明顺序排列
+    Struct(Vec<FieldLayout>),
+    Enum {
+        variants: Vec<VariantLayout>,
+        /// size_of::<Option<Self>>()
+        option_size: usize,
+    },
+}
+
+/// 一个类型的布局
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeLayout {
+    /// 去掉模块路径的类型名，泛型参数是实际的类型，例如 Option<&i32>
+    pub name: String,
+    pub size: usize,
+    pub align: usize,
+    pub shape: Shape,
+}
+
+/// 由 #[derive(Layout)] 实现
+pub trait Layout {
+    fn layout() -> TypeLayout;
+}
+
+/// T 的布局
+pub fn of<T: Layout>() -> TypeLayout {
+    T::layout()
+}
+
+/// 字段的大小和对齐，derive 展开后用 `|v: &Self| &v.field` 指定字段，不需要写出字段的类型
+pub fn field_size<T, F>(_field: fn(&T) -> &F) -> (usize, usize) {
+    (size_of::<F>(), align_of::<F>())
+}
+
+/// 去掉类型名中的模块路径，例如 alloc::vec::Vec<core::option::Option<u8>> 变成 Vec<Option<u8>>
+pub fn short_type_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    // 当前路径在 out 中开始的位置
+    let mut start = 0;
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            out.truncate(start);
+            continue;
+        }
+        out.push(c);
+        if !(c.is_alphanumeric() || c == '_') {
+            start = out.len();
+        }
+    }
+    out
+}
+
+/// 结构体中的一段内存：字段或者填充
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Field(&'a FieldLayout),
+    Padding { offset: usize, size: usize },
+}
+
+/// 判别值至少需要的字节数
+pub fn discriminant_bytes(variants: usize) -> usize {
+    match variants {
+        0 | 1 => 0,
+        n if n <= 1 << 8 => 1,
+        n if n <= 1 << 16 => 2,
+        n if n as u64 <= 1 << 32 => 4,
+        _ => 8,
+    }
+}
+
+impl TypeLayout {
+    /// 按偏移排列的字段和填充，零大小的字段也会列出
+    pub fn segments(&self) -> Vec<Segment<'_>> {
+        let Shape::Struct(fields) = &self.shape else { return Vec::new() };
+        let mut sorted: Vec<&FieldLayout> = fields.iter().collect();
+        sorted.sort_by_key(|field| (field.offset, field.size));
+        let mut segments = Vec::new();
+        let mut end = 0;
+        for field in sorted {
+            if field.offset > end {
+                segments.push(Segment::Padding { offset: end, size: field.offset - end });
+            }
+            segments.push(Segment::Field(field));
+            end = end.max(field.offset + field.size);
+        }
+        if self.size > end {
+            segments.push(Segment::Padding { offset: end, size: self.size - end });
+        }
+        segments
+    }
+
+    /// 填充的总字节数，枚举返回 0
+    pub fn padding(&self) -> usize {
+        self.segments()
+            .iter()
+            .map(|segment| match segment {
+                Segment::Padding { size, .. } => *size,
+                Segment::Field(_) => 0,
+            })
+            .sum()
+    }
+
+    /// 按名字查找字段
+    pub fn field(&self, name: &str) -> Option<&FieldLayout> {
+        match &self.shape {
+            Shape::Struct(fields) => fields.iter().find(|field| field.name == name),
+            Shape::Enum { .. } => None,
+        }
+    }
+
+    /// 枚举中最大的成员
+    pub fn max_payload(&self) -> usize {
+        match &self.shape {
+            Shape::Struct(_) => self.size,
+            Shape::Enum { variants, .. } => variants.iter().map(|variant| variant.payload_size).max().unwrap_or(0),
+        }
+    }
+
+    /// ASCII 内存图
+    pub fn diagram(&self) -> String {
+        match &self.shape {
+            Shape::Struct(_) => self.struct_diagram(),
+            Shape::Enum { variants, option_size } => self.enum_diagram(variants, *option_size),
+        }
+    }
+
+    fn struct_diagram(&self) -> String {
+        let segments = self.segments();
+        let labels: Vec<String> = segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Field(field) => format!("{}: {}", field.name, field.ty),
+                Segment::Padding { .. } => "(padding)".to_string(),
+            })
+            .collect();
+        let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0).max(8);
+        let border = format!("      +{}+", "-".repeat(width + 2));
+
+        let mut out = format!("struct {}: size = {}, align = {}\n", self.name, self.size, self.align);
+        out.push_str(&border);
+        out.push('\n');
+        // 每个字段用一个字母标记，填充用 .
+        let mut bytes = vec!['.'; self.size];
+        let mut letters = ('A'..='Z').chain('a'..='z').cycle();
+        for (segment, label) in segments.iter().zip(&labels) {
+            let (offset, size, mark) = match segment {
+                Segment::Field(field) => (field.offset, field.size, letters.next().unwrap()),
+                Segment::Padding { offset, size } => (*offset, *size, '.'),
+            };
+            for byte in bytes.iter_mut().skip(offset).take(size) {
+                *byte = mark;
+            }
+            out.push_str(&format!("{:>5} | {:<width$} | {} {} 字节\n", offset, label, mark, size, width = width));
+        }
+        out.push_str(&format!("{:>5} {}\n", self.size, &border[6..]));
+        if !bytes.is_empty() {
+            let groups: Vec<String> = bytes.chunks(8).map(|chunk| chunk.iter().collect()).collect();
+            out.push_str(&format!("      {}\n", groups.join(" ")));
+        }
+        out.push_str(&format!("      填充 {} 字节", self.padding()));
+        out
+    }
+
+    fn enum_diagram(&self, variants: &[VariantLayout], option_size: usize) -> String {
+        let labels: Vec<String> = variants
+            .iter()
+            .map(|variant| match variant.discriminant {
+                Some(value) => format!("{} = {}", variant.signature, value),
+                None => variant.signature.clone(),
+            })
+            .collect();
+        let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0).max(8);
+        let border = format!("      +{}+", "-".repeat(width + 2));
+
+        let mut out = format!("enum {}: size = {}, align = {}\n", self.name, self.size, self.align);
+        out.push_str(&border);
+        out.push('\n');
+        for (variant, label) in variants.iter().zip(&labels) {
+            out.push_str(&format!("      | {:<width$} | payload {} 字节\n", label, variant.payload_size, width = width));
+        }
+        out.push_str(&border);
+        out.push('\n');
+
+        let needed = discriminant_bytes(variants.len());
+        out.push_str(&format!("      判别值：{} 个成员，至少需要 {} 字节\n", variants.len(), needed));
+        let max = self.max_payload();
+        let extra = self.size - max;
+        if variants.iter().all(|variant| variant.payload_size == 0) {
+            out.push_str(&format!("      没有字段，整个值就是判别值，占 {} 字节\n", self.size));
+        } else if extra == 0 && needed > 0 {
+            out.push_str(&format!("      最大的成员 {} 字节，没有额外占用空间：判别值放进了字段的 niche 或者字段之间的填充\n", max));
+        } else {
+            out.push_str(&format!("      最大的成员 {} 字节，额外占用 {} 字节存放判别值和填充\n", max, extra));
+        }
+        if option_size == self.size {
+            out.push_str(&format!("      Option<{}>: {} 字节，没有变大，{} 还有空闲的 niche", self.name, option_size, self.name));
+        } else {
+            out.push_str(&format!("      Option<{}>: {} 字节，{} 没有空闲的 niche，需要额外的判别值", self.name, option_size, self.name));
+        }
+        out
+    }
+}
+
+impl fmt::Display for TypeLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.diagram())
+    }
+}
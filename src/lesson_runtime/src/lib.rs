@@ -0,0 +1,23 @@
+//! 课程运行时
+//!
+//! common 是过程宏 crate，只能导出宏，不能导出函数、类型和静态变量。outer_print 和 inner_print 展开后的代码
+//! 需要在运行时读取全局配置、记录课程的执行情况，这些代码就放在这里，basic_course 和 advance_course 都依赖它。
+//! + [config]：全局的课程配置，输出到哪里、是否使用颜色、输出的详细程度
+//! + [section]：课程开始、结束和小节的输出，以及执行次数和当前小节的记录
+//! + [registry]：outer_print 自动注册的课程，命令行按名字查找并执行
+//! + [input]：课程的输入，测试和命令行可以用脚本代替标准输入
+//! + [isolate]：在子进程中执行课程，panic 和死循环不会影响其它课程
+//! + [profile]：用 dev、release 等不同的构建配置执行课程，并逐行对比输出
+//! + [layout]：`#[derive(Layout)]` 收集的内存布局，画成 ASCII 内存图
+
+pub mod config;
+pub mod input;
+pub mod isolate;
+pub mod layout;
+pub mod profile;
+pub mod registry;
+pub mod section;
+
+/// outer_print 展开后的代码通过这里使用 inventory，使用 outer_print 的 crate 不需要再依赖它
+#[doc(hidden)]
+pub use inventory;
@@ -0,0 +1,162 @@
+//! 用不同的构建配置执行课程
+//!
+//! 同一段代码在 dev 和 release 下的行为可能不同，最典型的是整型溢出：dev 默认检查溢出并 panic，release 默认按补码回绕。
+//! 一次构建只能看到其中一种，这里用 cargo 分别构建出每种配置的可执行文件，再把执行结果逐行对比。
+//!
+//! 每种配置使用单独的 target 目录，互不覆盖，重复执行时 cargo 只做增量构建。
+//! 溢出检查通过环境变量 CARGO_PROFILE_<配置>_OVERFLOW_CHECKS 覆盖，不需要修改 Cargo.toml。
+
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::isolate::Report;
+
+/// 一种构建配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub release: bool,
+    /// None 表示使用配置的默认值：dev 检查溢出，release 不检查
+    pub overflow_checks: Option<bool>,
+}
+
+impl Variant {
+    pub const DEV: Variant = Variant { release: false, overflow_checks: None };
+    pub const RELEASE: Variant = Variant { release: true, overflow_checks: None };
+
+    /// dev、release，toggle_overflow_checks 为 true 时再加上溢出检查取反的两种
+    pub fn matrix(toggle_overflow_checks: bool) -> Vec<Variant> {
+        let mut variants = vec![Variant::DEV, Variant::RELEASE];
+        if toggle_overflow_checks {
+            variants.push(Variant { release: false, overflow_checks: Some(false) });
+            variants.push(Variant { release: true, overflow_checks: Some(true) });
+        }
+        variants
+    }
+
+    pub fn profile(&self) -> &'static str {
+        if self.release {
+            "release"
+        } else {
+            "dev"
+        }
+    }
+
+    /// 是否检查溢出
+    pub fn checks_overflow(&self) -> bool {
+        self.overflow_checks.unwrap_or(!self.release)
+    }
+
+    /// 用作目录名的名字，例如 dev、release-overflow-checks
+    pub fn slug(&self) -> String {
+        match self.overflow_checks {
+            None => self.profile().to_string(),
+            Some(true) => format!("{}-overflow-checks", self.profile()),
+            Some(false) => format!("{}-no-overflow-checks", self.profile()),
+        }
+    }
+
+    /// 构建 bin，返回可执行文件的路径，构建失败时错误中包含 cargo 的输出
+    pub fn build(&self, manifest_dir: &Path, bin: &str) -> io::Result<PathBuf> {
+        let target_dir = manifest_dir.join("target").join("profiles").join(self.slug());
+        let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let mut command = Command::new(cargo);
+        command.current_dir(manifest_dir).args(["build", "--quiet", "--bin", bin]).env("CARGO_TARGET_DIR", &target_dir);
+        if self.release {
+            command.arg("--release");
+        }
+        if let Some(checks) = self.overflow_checks {
+            let key = format!("CARGO_PROFILE_{}_OVERFLOW_CHECKS", self.profile().to_uppercase());
+            command.env(key, checks.to_string());
+        }
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!("{} 构建失败:\n{}", self, String::from_utf8_lossy(&output.stderr))));
+        }
+        let dir = if self.release { "release" } else { "debug" };
+        Ok(target_dir.join(dir).join(format!("{}{}", bin, env::consts::EXE_SUFFIX)))
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.overflow_checks {
+            None => write!(f, "{}", self.profile()),
+            Some(checks) => write!(f, "{}(overflow-checks={})", self.profile(), checks),
+        }
+    }
+}
+
+/// 逐行对比的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Changed(&'a str, &'a str),
+    /// 只在左边出现
+    Left(&'a str),
+    /// 只在右边出现
+    Right(&'a str),
+}
+
+/// 最长公共子序列表最多的格子数，约 32 MB。每个输出流最多捕获 64 KiB，全是空行时每边约 3 万行，
+/// 完整的表需要好几 GB，超过这个限制时不再寻找公共行，按位置逐行配对
+pub const DIFF_TABLE_LIMIT: usize = 1 << 22;
+
+/// 基于最长公共子序列的逐行对比，相邻的删除和插入会配对成 Changed，方便并排显示。
+/// 相同的开头和结尾先去掉，只有中间不同的部分进入最长公共子序列表
+pub fn diff_lines<'a>(left: &'a str, right: &'a str) -> Vec<DiffLine<'a>> {
+    let a: Vec<&str> = left.lines().collect();
+    let b: Vec<&str> = right.lines().collect();
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let mut out: Vec<DiffLine<'a>> = a[..prefix].iter().map(|line| DiffLine::Same(line)).collect();
+    diff_middle(&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix], &mut out);
+    out.extend(a[a.len() - suffix..].iter().map(|line| DiffLine::Same(line)));
+    out
+}
+
+/// 把 removed 和 added 配对成 Changed，多出来的作为 Left 或 Right
+fn flush<'a>(out: &mut Vec<DiffLine<'a>>, removed: &mut Vec<&'a str>, added: &mut Vec<&'a str>) {
+    let paired = removed.len().min(added.len());
+    out.extend(removed.iter().zip(added.iter()).map(|(l, r)| DiffLine::Changed(l, r)));
+    out.extend(removed.drain(..).skip(paired).map(DiffLine::Left));
+    out.extend(added.drain(..).skip(paired).map(DiffLine::Right));
+}
+
+fn diff_middle<'a>(a: &[&'a str], b: &[&'a str], out: &mut Vec<DiffLine<'a>>) {
+    if (a.len() + 1).saturating_mul(b.len() + 1) > DIFF_TABLE_LIMIT {
+        flush(out, &mut a.to_vec(), &mut b.to_vec());
+        return;
+    }
+    // lcs[i][j] 是 a[i..] 和 b[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            flush(out, &mut removed, &mut added);
+            out.push(DiffLine::Same(a[i]));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            removed.push(a[i]);
+            i += 1;
+        } else {
+            added.push(b[j]);
+            j += 1;
+        }
+    }
+    flush(out, &mut removed, &mut added);
+}
+
+/// 两次执行的结果或者标准输出是否不同，用时和标准错误不参与比较
+pub fn differs(a: &Report, b: &Report) -> bool {
+    a.outcome != b.outcome || a.stdout != b.stdout
+}
@@ -0,0 +1,74 @@
+//! 课程注册表
+//!
+//! outer_print 展开后会把没有参数、没有泛型的课程通过 inventory 注册到这里，程序启动时自动收集，
+//! 命令行按名字查找并执行课程，不需要手写课程列表。
+
+use std::fmt;
+
+/// 一节注册过的课程
+#[derive(Debug)]
+pub struct Lesson {
+    /// module_path!()，以 crate 名开头
+    pub module: &'static str,
+    pub name: &'static str,
+    pub title: &'static str,
+    /// 执行课程，返回值会被丢弃
+    pub run: fn(),
+    /// 在 dev 和 release 下的输出或者结果预期不同，由 #[outer_print("标题", profile_dependent)] 声明
+    pub profile_dependent: bool,
+}
+
+impl Lesson {
+    pub const fn new(module: &'static str, name: &'static str, title: &'static str, run: fn(), profile_dependent: bool) -> Lesson {
+        Lesson { module, name, title, run, profile_dependent }
+    }
+
+    /// 完整路径，例如 basic_course::rust_2_4_compound_type::array::access_array
+    pub fn path(&self) -> String {
+        format!("{}::{}", self.module, self.name)
+    }
+
+    /// query 是完整路径，或者是路径中以 :: 分隔的后缀，例如 array::access_array
+    pub fn matches(&self, query: &str) -> bool {
+        let path = self.path();
+        path == query || path.ends_with(&format!("::{}", query))
+    }
+}
+
+inventory::collect!(Lesson);
+
+/// 所有注册过的课程，按路径排序
+pub fn all() -> Vec<&'static Lesson> {
+    let mut lessons: Vec<&'static Lesson> = inventory::iter::<Lesson>.into_iter().collect();
+    lessons.sort_by_key(|lesson| lesson.path());
+    lessons
+}
+
+/// 按名字查找课程时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindError {
+    NotFound(String),
+    /// 有多节课程匹配，附带所有匹配的路径
+    Ambiguous(String, Vec<String>),
+}
+
+impl fmt::Display for FindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FindError::NotFound(query) => write!(f, "没有找到课程 {}", query),
+            FindError::Ambiguous(query, paths) => write!(f, "{} 匹配了多节课程: {}", query, paths.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for FindError {}
+
+/// 按完整路径或者路径后缀查找唯一的一节课程
+pub fn find(query: &str) -> Result<&'static Lesson, FindError> {
+    let mut found: Vec<&'static Lesson> = all().into_iter().filter(|lesson| lesson.matches(query)).collect();
+    match found.len() {
+        0 => Err(FindError::NotFound(query.to_string())),
+        1 => Ok(found.remove(0)),
+        _ => Err(FindError::Ambiguous(query.to_string(), found.iter().map(|lesson| lesson.path()).collect())),
+    }
+}
@@ -0,0 +1,133 @@
+//! 课程和小节的输出
+//!
+//! outer_print 展开后在函数开头调用 [start]，函数返回或 panic 时调用 [end]，inner_print 展开后调用 [inner]。
+//! + 执行次数用 static 原子变量统计，所有线程共享
+//! + 当前正在执行的课程用 thread_local 的栈记录，课程中调用另一个课程时栈会变深，每个线程互不影响
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use crate::config::{self, Config, Sink, Verbosity};
+
+/// 标题的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Banner {
+    Start,
+    End,
+    Inner,
+}
+
+/// 正在执行的课程
+struct Section {
+    title: String,
+    inner: Option<String>,
+    started: Instant,
+}
+
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+static FINISHED: AtomicUsize = AtomicUsize::new(0);
+
+/// 进程中第一次用到时的时间，verbose 模式下用来显示课程开始的相对时间
+static EPOCH: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+thread_local! {
+    static STACK: RefCell<Vec<Section>> = const { RefCell::new(Vec::new()) };
+}
+
+/// 所有线程中开始执行的课程数
+pub fn started() -> usize {
+    STARTED.load(Ordering::Relaxed)
+}
+
+/// 所有线程中执行结束的课程数，panic 的课程也会结束
+pub fn finished() -> usize {
+    FINISHED.load(Ordering::Relaxed)
+}
+
+/// 当前线程中课程的嵌套深度
+pub fn depth() -> usize {
+    STACK.with(|stack| stack.borrow().len())
+}
+
+/// 当前线程中的小节路径，例如 ["全局变量", "OnceLock", "初始化"]
+pub fn path() -> Vec<String> {
+    STACK.with(|stack| {
+        stack
+            .borrow()
+            .iter()
+            .flat_map(|section| std::iter::once(section.title.clone()).chain(section.inner.clone()))
+            .collect()
+    })
+}
+
+/// 渲染一行标题，不需要输出时返回 None
+///
+/// detail 只在 verbose 模式下附加在标题后面
+pub fn render(config: &Config, banner: Banner, title: &str, detail: &str) -> Option<String> {
+    if config.verbosity == Verbosity::Quiet || config.sink == Sink::Null {
+        return None;
+    }
+    let mut line = match banner {
+        Banner::Start => format!("[start]==================================================[{}]", title),
+        Banner::End => format!("[end]====================================================[{}]", title),
+        Banner::Inner => format!("[inner_print]============================================[{}]", title),
+    };
+    if config.verbosity == Verbosity::Verbose && !detail.is_empty() {
+        line = format!("{} {}", line, detail);
+    }
+    if config.use_color() {
+        let code = if banner == Banner::Inner { 33 } else { 36 };
+        line = format!("\x1b[{}m{}\x1b[0m", code, line);
+    }
+    // 课程之间空一行
+    if banner == Banner::End {
+        line.push('\n');
+    }
+    Some(line)
+}
+
+/// 用 print! 而不是直接写 io::stdout()，这样 cargo test 才能捕获输出
+fn emit(banner: Banner, title: &str, detail: impl FnOnce() -> String) {
+    let config = config::get();
+    let detail = if config.verbosity == Verbosity::Verbose { detail() } else { String::new() };
+    if let Some(line) = render(config, banner, title, &detail) {
+        match config.sink {
+            Sink::Stdout => println!("{}", line),
+            Sink::Stderr => eprintln!("{}", line),
+            Sink::Null => {}
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// 课程开始
+pub fn start(title: &str) {
+    let count = STARTED.fetch_add(1, Ordering::Relaxed) + 1;
+    let epoch = *EPOCH;
+    let started = Instant::now();
+    STACK.with(|stack| stack.borrow_mut().push(Section { title: title.to_string(), inner: None, started }));
+    emit(Banner::Start, title, || format!("#{} {} +{}", count, path().join(" > "), format_duration(started - epoch)));
+}
+
+/// 课程结束，outer_print 生成的 Guard 在 drop 时调用
+pub fn end(title: &str) {
+    let section = STACK.with(|stack| stack.borrow_mut().pop());
+    FINISHED.fetch_add(1, Ordering::Relaxed);
+    emit(Banner::End, title, || section.map(|s| format!("用时 {}", format_duration(s.started.elapsed()))).unwrap_or_default());
+}
+
+/// 课程中的小节，会替换当前课程中上一个小节
+pub fn inner(title: impl fmt::Display) {
+    let title = title.to_string();
+    STACK.with(|stack| {
+        if let Some(section) = stack.borrow_mut().last_mut() {
+            section.inner = Some(title.clone());
+        }
+    });
+    emit(Banner::Inner, &title, || path().join(" > "));
+}
@@ -1,10 +1,294 @@
-#![allow(unused)]
-use common;
-use basic_course;
-use advance_course;
-fn main() {
-    println!("Hello, world!");
-    // basic_course::add()
-    // common::inner_print!()
+//! 课程的命令行入口，目前支持的子命令：
+//! + overflow <类型> <运算> [操作数...]：例如 `cargo run -- overflow i16 mul 300 200`，省略操作数时列出该类型的边界值
+//! + list [关键字]：列出 outer_print 注册的课程
+//! + run <课程> [--input <输入>]...：执行一节课程，例如 `cargo run -- run array::access_array --input 7`，
+//!   --input 按顺序提供课程需要读取的每一行输入
+//! + check [关键字] [--timeout <毫秒>] [--input <输入>]...：在子进程中逐个执行匹配的课程，
+//!   panic 和死循环不会影响其它课程，最后输出每节课的结果：ok、panicked、timed-out 或 aborted
+//! + profiles <课程> [--overflow-checks] [--timeout <毫秒>] [--input <输入>]...：分别用 dev 和 release 构建并执行一节课程，
+//!   并排对比两者的输出和结果，--overflow-checks 再加上溢出检查取反的两种构建
+
+use std::env;
+use std::path::Path;
+use std::process::{Command, ExitCode};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use basic_course::rust_2_14_formatted_output::table::{Align, Table};
+use basic_course::rust_2_2_base_type::overflow;
+use lesson_runtime::isolate::{self, Outcome, Report};
+use lesson_runtime::profile::{self, DiffLine, Variant};
+use lesson_runtime::registry::{self, Lesson};
+use lesson_runtime::input;
+
+// 课程通过 inventory 注册，crate 必须被链接进来，即使这里没有直接用到其中的函数
+use advance_course as _;
+
+const USAGE: &str = "用法:
+    rust-course overflow <类型> <运算> [操作数...]
+    rust-course list [关键字]
+    rust-course run <课程> [--input <输入>]...
+    rust-course check [关键字] [--timeout <毫秒>] [--input <输入>]...
+    rust-course profiles <课程> [--overflow-checks] [--timeout <毫秒>] [--input <输入>]...
+
+类型: i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize
+运算: add sub mul shl neg abs pow，shl 和 pow 的右操作数是 u32
+省略操作数时，在该类型的边界值上列出所有结果
+
+课程可以是完整路径，也可以是路径的后缀，例如 array::access_array
+check 和 profiles 中每节课默认最多执行 5000 毫秒
+profiles 会在 target/profiles 下分别构建每种配置，第一次执行需要等待构建完成";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 并排对比时，连续相同的行只保留前后这么多行
+const DIFF_CONTEXT: usize = 3;
+
+/// run、check 和 profiles 的参数
+#[derive(Debug, Default)]
+struct Options<'a> {
+    /// run 和 profiles 的课程名，check 的关键字
+    target: Option<&'a str>,
+    inputs: Vec<&'a str>,
+    timeout: Option<Duration>,
+    /// profiles 是否加上溢出检查取反的构建
+    overflow_checks: bool,
+}
+
+fn parse_options<'a>(args: &[&'a str]) -> Result<Options<'a>, String> {
+    let mut options = Options::default();
+    let mut iter = args.iter();
+    while let Some(&arg) = iter.next() {
+        match arg {
+            "--input" => options.inputs.push(*iter.next().ok_or("--input 后面缺少输入")?),
+            "--timeout" => {
+                let millis = iter.next().ok_or("--timeout 后面缺少毫秒数")?;
+                let millis: u64 = millis.parse().map_err(|_| format!("无效的毫秒数 {}", millis))?;
+                options.timeout = Some(Duration::from_millis(millis));
+            }
+            "--overflow-checks" => options.overflow_checks = true,
+            _ if arg.starts_with("--") => return Err(format!("未知的参数 {}", arg)),
+            _ if options.target.is_none() => options.target = Some(arg),
+            _ => return Err(format!("多余的参数 {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+fn run(args: &[&str]) -> Result<(), String> {
+    let options = parse_options(args)?;
+    if options.timeout.is_some() {
+        return Err("--timeout 只能用于 check 和 profiles".to_string());
+    }
+    if options.overflow_checks {
+        return Err("--overflow-checks 只能用于 profiles".to_string());
+    }
+    let lesson = registry::find(options.target.ok_or("缺少课程名")?).map_err(|e| e.to_string())?;
+    // check 启动的子进程需要把 panic 信息报告给父进程
+    if isolate::is_child() {
+        isolate::report_panics();
+    }
+    if !options.inputs.is_empty() {
+        input::set_script(options.inputs);
+    }
+    (lesson.run)();
+    Ok(())
+}
+
+/// 在子进程中用 exe 的 run 子命令执行一节课程
+fn run_isolated(exe: &Path, lesson: &Lesson, inputs: &[&str], timeout: Duration) -> Result<Report, String> {
+    let mut command = Command::new(exe);
+    command.arg("run").arg(lesson.path());
+    for input in inputs {
+        command.args(["--input", input]);
+    }
+    isolate::run(&mut command, timeout).map_err(|e| format!("无法启动子进程: {}", e))
 }
 
+/// 结果表格中“说明”一列的内容
+fn outcome_detail(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Ok => String::new(),
+        Outcome::Panicked(message) => message.replace('\n', " "),
+        Outcome::TimedOut(limit) => format!("超过了 {}ms", limit.as_millis()),
+        Outcome::Aborted(reason) => reason.clone(),
+    }
+}
+
+fn check(args: &[&str]) -> Result<(), String> {
+    let options = parse_options(args)?;
+    if options.overflow_checks {
+        return Err("--overflow-checks 只能用于 profiles".to_string());
+    }
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let filter = options.target.unwrap_or("");
+    let lessons: Vec<&Lesson> = registry::all().into_iter().filter(|lesson| lesson.path().contains(filter)).collect();
+    if lessons.is_empty() {
+        return Err(format!("没有匹配 {} 的课程", filter));
+    }
+    let timeout = options.timeout.unwrap_or(DEFAULT_TIMEOUT);
+
+    // 几个工作线程依次领取课程，每节课都在自己的子进程中执行
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<Report, String>>>> = Mutex::new(vec![None; lessons.len()]);
+    let jobs = thread::available_parallelism().map_or(1, usize::from).min(lessons.len());
+    thread::scope(|s| {
+        for _ in 0..jobs {
+            s.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(lesson) = lessons.get(index) else { break };
+                let result = run_isolated(&exe, lesson, &options.inputs, timeout);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    let mut table = Table::new(["课程", "结果", "用时", "说明"]).align(2, Align::Right);
+    let mut counts = [0usize; 5];
+    for (lesson, result) in lessons.iter().zip(results.into_inner().unwrap()) {
+        let (label, elapsed, detail) = match result.expect("每节课都已经执行") {
+            Ok(report) => (report.outcome.label(), format!("{}ms", report.elapsed.as_millis()), outcome_detail(&report.outcome)),
+            Err(e) => ("error", String::new(), e),
+        };
+        let slot = ["ok", "panicked", "timed-out", "aborted", "error"].iter().position(|l| *l == label).unwrap();
+        counts[slot] += 1;
+        table.row([lesson.path(), label.to_string(), elapsed, detail]);
+    }
+    println!("{}", table);
+    println!(
+        "共 {} 节课：ok {}，panicked {}，timed-out {}，aborted {}，error {}",
+        lessons.len(),
+        counts[0],
+        counts[1],
+        counts[2],
+        counts[3],
+        counts[4]
+    );
+    Ok(())
+}
+
+/// 把两份输出并排放进表格，中间一列标出差异：≠ 内容不同，< 只在左边，> 只在右边，连续相同的行会被省略
+fn side_by_side(left_name: &str, left: &str, right_name: &str, right: &str) -> Table {
+    let diff = profile::diff_lines(left, right);
+    let mut table = Table::new([left_name, "", right_name]).align(1, Align::Center);
+    let mut index = 0;
+    while index < diff.len() {
+        let same = diff[index..].iter().take_while(|line| matches!(line, DiffLine::Same(_))).count();
+        if same == 0 {
+            let (l, mark, r) = match diff[index] {
+                DiffLine::Changed(l, r) => (l, "≠", r),
+                DiffLine::Left(l) => (l, "<", ""),
+                DiffLine::Right(r) => ("", ">", r),
+                DiffLine::Same(_) => unreachable!(),
+            };
+            table.row([l, mark, r]);
+            index += 1;
+            continue;
+        }
+        // 开头只保留后面几行，结尾只保留前面几行，中间前后各保留几行
+        let head = if index == 0 { 0 } else { DIFF_CONTEXT };
+        let tail = if index + same == diff.len() { 0 } else { DIFF_CONTEXT };
+        // 只省略一行时直接显示出来
+        let hidden = same.saturating_sub(head + tail);
+        for (offset, line) in diff[index..index + same].iter().enumerate() {
+            let DiffLine::Same(text) = line else { unreachable!() };
+            if hidden <= 1 || offset < head || offset >= head + hidden {
+                table.row([*text, "", *text]);
+            } else if offset == head {
+                table.row([format!("...(省略 {} 行相同的输出)", hidden), String::new(), String::new()]);
+            }
+        }
+        index += same;
+    }
+    table
+}
+
+fn profiles(args: &[&str]) -> Result<(), String> {
+    let options = parse_options(args)?;
+    let lesson = registry::find(options.target.ok_or("缺少课程名")?).map_err(|e| e.to_string())?;
+    let timeout = options.timeout.unwrap_or(DEFAULT_TIMEOUT);
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let mut reports = Vec::new();
+    for variant in Variant::matrix(options.overflow_checks) {
+        eprintln!("构建 {} ...", variant);
+        let exe = variant.build(manifest_dir, env!("CARGO_BIN_NAME")).map_err(|e| e.to_string())?;
+        let report = run_isolated(&exe, lesson, &options.inputs, timeout)?;
+        reports.push((variant, report));
+    }
+
+    println!("{}  {}", lesson.path(), lesson.title);
+    let mut table = Table::new(["构建配置", "溢出检查", "结果", "用时", "说明"]).align(3, Align::Right);
+    for (variant, report) in &reports {
+        let checks = if variant.checks_overflow() { "是" } else { "否" };
+        let elapsed = format!("{}ms", report.elapsed.as_millis());
+        table.row([variant.to_string(), checks.to_string(), report.outcome.label().to_string(), elapsed, outcome_detail(&report.outcome)]);
+    }
+    println!("{}", table);
+
+    // 其它构建都和 dev 对比
+    let (base, base_report) = &reports[0];
+    let mut any_differs = false;
+    for (variant, report) in &reports[1..] {
+        if !profile::differs(base_report, report) {
+            println!("{} 和 {} 的结果和输出相同\n", base, variant);
+            continue;
+        }
+        any_differs = true;
+        println!("{} 和 {} 的输出对比：", base, variant);
+        println!("{}\n", side_by_side(&base.to_string(), &base_report.stdout, &variant.to_string(), &report.stdout));
+    }
+    match (lesson.profile_dependent, any_differs) {
+        (true, false) => println!("注意：这节课标记为 profile_dependent，但是各个构建配置的结果相同"),
+        (false, true) => println!("注意：这节课没有标记 profile_dependent，但是各个构建配置的结果不同"),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["overflow", ty, op, operands @ ..] => match overflow::explore(ty, op, operands) {
+            Ok(table) => {
+                println!("{:#}", table);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                ExitCode::FAILURE
+            }
+        },
+        ["list", filter @ ..] if filter.len() <= 1 => {
+            for lesson in registry::all().into_iter().filter(|lesson| filter.iter().all(|f| lesson.path().contains(f))) {
+                let mark = if lesson.profile_dependent { "  [dev/release 不同]" } else { "" };
+                println!("{}  {}{}", lesson.path(), lesson.title, mark);
+            }
+            ExitCode::SUCCESS
+        }
+        [command @ ("run" | "check" | "profiles"), rest @ ..] => {
+            let result = match *command {
+                "run" => run(rest),
+                "check" => check(rest),
+                _ => profiles(rest),
+            };
+            match result {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("{}\n\n{}", e, USAGE);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        [] | ["help" | "-h" | "--help"] => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
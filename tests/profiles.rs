@@ -0,0 +1,103 @@
+#[cfg(test)]
+pub mod tests {
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+    use lesson_runtime::isolate::{self, Outcome, Report};
+    use lesson_runtime::profile::{self, DiffLine, Variant};
+    use lesson_runtime::registry;
+
+    // 链接两门课程，注册表中才有它们的课程
+    use advance_course as _;
+    use basic_course as _;
+
+    fn run_lesson(exe: &Path, path: &str) -> Report {
+        let mut command = Command::new(exe);
+        command.args(["run", path]);
+        isolate::run(&mut command, Duration::from_secs(10)).unwrap()
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(profile::diff_lines("a\nb\nc", "a\nb\nc"), [DiffLine::Same("a"), DiffLine::Same("b"), DiffLine::Same("c")]);
+        assert_eq!(
+            profile::diff_lines("a\nx\nc", "a\ny\nc"),
+            [DiffLine::Same("a"), DiffLine::Changed("x", "y"), DiffLine::Same("c")]
+        );
+        // 删除和插入的行数不同时，多出来的单独列出
+        assert_eq!(
+            profile::diff_lines("a\nx\nz", "a\ny"),
+            [DiffLine::Same("a"), DiffLine::Changed("x", "y"), DiffLine::Left("z")]
+        );
+        assert_eq!(profile::diff_lines("", "a"), [DiffLine::Right("a")]);
+        assert_eq!(profile::diff_lines("a\nb", "b"), [DiffLine::Left("a"), DiffLine::Same("b")]);
+    }
+
+    /// 捕获上限内的空行有 3 万多行，对比不能把整张最长公共子序列表放进内存
+    #[test]
+    fn test_diff_lines_large() {
+        let blank = "\n".repeat(isolate::CAPTURE_LIMIT / 2);
+        let changed = format!("{}x\n{}", "\n".repeat(100), "\n".repeat(isolate::CAPTURE_LIMIT / 2 - 101));
+        let diff = profile::diff_lines(&blank, &changed);
+        assert_eq!(diff.len(), isolate::CAPTURE_LIMIT / 2);
+        assert_eq!(diff[100], DiffLine::Changed("", "x"));
+        assert_eq!(diff.iter().filter(|line| matches!(line, DiffLine::Same(_))).count(), diff.len() - 1);
+
+        // 中间完全不同时超过了表的限制，按位置配对
+        let left: String = (0..30_000).map(|i| format!("l{}\n", i)).collect();
+        let right: String = (0..20_000).map(|i| format!("r{}\n", i)).collect();
+        let diff = profile::diff_lines(&left, &right);
+        assert_eq!(diff.len(), 30_000);
+        assert_eq!((diff[0], diff[29_999]), (DiffLine::Changed("l0", "r0"), DiffLine::Left("l29999")));
+    }
+
+    #[test]
+    fn test_variants() {
+        assert_eq!(Variant::matrix(false), [Variant::DEV, Variant::RELEASE]);
+        let variants = Variant::matrix(true);
+        let names: Vec<String> = variants.iter().map(Variant::to_string).collect();
+        assert_eq!(names, ["dev", "release", "dev(overflow-checks=false)", "release(overflow-checks=true)"]);
+        assert_eq!(variants.iter().map(Variant::checks_overflow).collect::<Vec<_>>(), [true, false, false, true]);
+        assert_eq!(variants[3].slug(), "release-overflow-checks");
+    }
+
+    /// 标记为 profile_dependent 的课程在 dev 和 release 下的结果或者输出必须不同，没有标记的对照课程必须相同
+    ///
+    /// 第一次执行需要在 target/profiles 下构建 dev 和 release，会比较慢
+    #[test]
+    fn test_profile_dependent_lessons() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let dev = Variant::DEV.build(manifest_dir, "rust-course").unwrap();
+        let release = Variant::RELEASE.build(manifest_dir, "rust-course").unwrap();
+
+        let flagged: Vec<String> = registry::all().into_iter().filter(|lesson| lesson.profile_dependent).map(|lesson| lesson.path()).collect();
+        assert!(flagged.contains(&"basic_course::rust_2_2_base_type::numbers::overflow_default".to_string()));
+        assert!(flagged.contains(&"basic_course::rust_2_2_base_type::overflow::overflow_explorer".to_string()));
+        for path in &flagged {
+            let (a, b) = (run_lesson(&dev, path), run_lesson(&release, path));
+            assert!(profile::differs(&a, &b), "{} 标记为 profile_dependent，但 dev 和 release 的结果相同", path);
+        }
+
+        let (a, b) = (run_lesson(&dev, "numbers::overflow_default"), run_lesson(&release, "numbers::overflow_default"));
+        assert_eq!(a.outcome, Outcome::Panicked("attempt to add with overflow".into()));
+        assert_eq!(b.outcome, Outcome::Ok);
+        assert!(b.stdout.contains("200 + 100 = 44"));
+
+        let control = "numbers::int_type";
+        assert!(!profile::differs(&run_lesson(&dev, control), &run_lesson(&release, control)));
+    }
+
+    #[test]
+    fn test_profiles_command() {
+        let output = Command::new(env!("CARGO_BIN_EXE_rust-course")).args(["profiles", "numbers::overflow_default"]).stdin(Stdio::null()).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let row = |name: &str| stdout.lines().find(|line| line.starts_with(name)).unwrap().split('|').map(str::trim).collect::<Vec<_>>();
+        assert_eq!(row("dev ")[..3], ["dev", "是", "panicked"]);
+        assert_eq!(row("release ")[..3], ["release", "否", "ok"]);
+        // 只在 release 中出现的一行
+        let only_release = stdout.lines().find(|line| line.contains("200 + 100 = 44")).unwrap();
+        assert_eq!(only_release.split('|').map(str::trim).collect::<Vec<_>>(), ["", ">", "200 + 100 = 44"]);
+        assert!(!stdout.contains("注意"));
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+pub mod tests {
+    use std::process::{Command, Output, Stdio};
+
+    fn course(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_rust-course")).args(args).stdin(Stdio::null()).output().unwrap()
+    }
+
+    fn text(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    #[test]
+    fn test_list() {
+        let output = course(&["list", "array"]);
+        assert!(output.status.success());
+        let stdout = text(&output.stdout);
+        assert!(stdout.contains("basic_course::rust_2_4_compound_type::array::access_array  访问数组"));
+        // advance_course 中的课程同样被注册
+        assert!(text(&course(&["list", "rust_4_7"]).stdout).contains("advance_course::rust_4_7_global_variable::rust_4_7_3::section_stack"));
+        // profile_dependent 的课程带有标记
+        assert!(text(&course(&["list", "numbers::overflow_default"]).stdout).contains("整型溢出的默认行为  [dev/release 不同]"));
+    }
+
+    #[test]
+    fn test_run_with_input() {
+        let output = course(&["run", "array::access_array", "--input", "2"]);
+        assert!(output.status.success());
+        let stdout = text(&output.stdout);
+        assert!(stdout.contains("> 2\nThe value of the element at index 2 is: 3"));
+
+        let output = course(&["run", "array::access_array", "--input", "7"]);
+        assert!(!output.status.success());
+        assert!(text(&output.stderr).contains("index out of bounds: the len is 5 but the index is 7"));
+        // panic 时 [end] 依然会输出
+        assert!(text(&output.stdout).contains("[end]"));
+
+        // 没有 --input 时读取标准输入，这里标准输入是空的
+        let output = course(&["run", "array::access_array"]);
+        assert!(text(&output.stderr).contains("Failed to read line"));
+    }
+
+    #[test]
+    fn test_check() {
+        let output = course(&["check", "function::diverge", "--timeout", "500"]);
+        assert!(output.status.success());
+        let stdout = text(&output.stdout);
+        let row = |name: &str| stdout.lines().find(|line| line.contains(name)).unwrap().split('|').map(str::trim).collect::<Vec<_>>();
+        let (panicked, forever) = (row("::diverge_panic"), row("::diverge_forever"));
+        assert_eq!((panicked[1], panicked[3]), ("panicked", "你已经到了穷途末路，崩溃吧！"));
+        assert_eq!((forever[1], forever[3]), ("timed-out", "超过了 500ms"));
+        assert!(stdout.contains("共 2 节课：ok 0，panicked 1，timed-out 1，aborted 0，error 0"));
+
+        // --input 会传给每个子进程
+        let stdout = text(&course(&["check", "array::access_array", "--input", "1"]).stdout);
+        assert!(stdout.contains("共 1 节课：ok 1"));
+        assert!(text(&course(&["check", "array::access_array"]).stdout).contains("Failed to read line"));
+        assert!(text(&course(&["check", "no_such_lesson"]).stderr).starts_with("没有匹配 no_such_lesson 的课程"));
+    }
+
+    #[test]
+    fn test_run_errors() {
+        let output = course(&["run", "no_such_lesson"]);
+        assert!(!output.status.success());
+        assert!(text(&output.stderr).starts_with("没有找到课程 no_such_lesson"));
+
+        let output = course(&["run", "array::access_array", "--input"]);
+        assert!(text(&output.stderr).starts_with("--input 后面缺少输入"));
+        assert!(text(&course(&["run", "--verbose"]).stderr).starts_with("未知的参数 --verbose"));
+        assert!(text(&course(&["run", "array::access_array", "--timeout", "10"]).stderr).starts_with("--timeout 只能用于 check"));
+        assert!(text(&course(&["check", "--timeout", "soon"]).stderr).starts_with("无效的毫秒数 soon"));
+        assert!(text(&course(&["check", "--overflow-checks"]).stderr).starts_with("--overflow-checks 只能用于 profiles"));
+        assert!(text(&course(&["profiles"]).stderr).starts_with("缺少课程名"));
+    }
+}